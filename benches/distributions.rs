@@ -112,7 +112,17 @@ distr_float!(distr_gamma_large_shape, f64, Gamma::new(10., 1.0));
 distr_float!(distr_gamma_small_shape, f64, Gamma::new(0.1, 1.0));
 distr_float!(distr_cauchy, f64, Cauchy::new(4.2, 6.9));
 distr_int!(distr_binomial, u64, Binomial::new(20, 0.7));
+distr_int!(distr_binomial_small, u64, Binomial::new(1000000, 1e-6));
+distr_int!(distr_binomial_large, u64, Binomial::new(100000, 0.3));
 distr_int!(distr_poisson, u64, Poisson::new(4.0));
+distr_int!(distr_poisson_medium, u64, Poisson::new(15.0));
+distr_int!(distr_poisson_large, u64, Poisson::new(1000.0));
+distr_int!(distr_geometric, u64, Geometric::new(0.05));
+distr_int!(distr_negative_binomial, u64, NegativeBinomial::new(3.0, 0.4));
+distr_int!(distr_hypergeometric, u64, Hypergeometric::new(52, 13, 5));
+distr_int!(distr_hypergeometric_large, u64, Hypergeometric::new(100_000, 20_000, 5_000));
+distr_int!(distr_zipf, u64, Zipf::new(1000, 1.1));
+distr_int!(distr_zeta, u64, Zeta::new(2.0));
 distr!(distr_bernoulli, bool, Bernoulli::new(0.18));
 
 // Weighted
@@ -121,6 +131,11 @@ distr_int!(distr_weighted_u32, usize, WeightedIndex::new(&[1u32, 2, 3, 4, 12, 0,
 distr_int!(distr_weighted_f64, usize, WeightedIndex::new(&[1.0f64, 0.001, 1.0/3.0, 4.01, 0.0, 3.3, 22.0, 0.001]).unwrap());
 distr_int!(distr_weighted_large_set, usize, WeightedIndex::new((0..10000).rev().chain(1..10001)).unwrap());
 
+distr_int!(distr_weighted_alias_i8, usize, WeightedAliasIndex::new(&[1i8, 2, 3, 4, 12, 0, 2, 1]).unwrap());
+distr_int!(distr_weighted_alias_u32, usize, WeightedAliasIndex::new(&[1u32, 2, 3, 4, 12, 0, 2, 1]).unwrap());
+distr_int!(distr_weighted_alias_f64, usize, WeightedAliasIndex::new(&[1.0f64, 0.001, 1.0/3.0, 4.01, 0.0, 3.3, 22.0, 0.001]).unwrap());
+distr_int!(distr_weighted_alias_large_set, usize, WeightedAliasIndex::new((0..10000u32).rev().chain(1..10001)).unwrap());
+
 // construct and sample from a range
 macro_rules! gen_range_int {
     ($fnn:ident, $ty:ident, $low:expr, $high:expr) => {
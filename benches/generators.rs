@@ -3,7 +3,10 @@
 extern crate test;
 extern crate rand;
 extern crate rand_isaac;
+extern crate rand_mt;
+extern crate rand_pcg;
 extern crate rand_xorshift;
+extern crate rand_xoshiro;
 
 const RAND_BENCH_N: u64 = 1000;
 const BYTES_LEN: usize = 1024;
@@ -12,12 +15,19 @@ use std::mem::size_of;
 use test::{black_box, Bencher};
 
 use rand::prelude::*;
-use rand::prng::{Hc128Rng, ChaChaRng};
+use rand::prng::{Hc128Rng, Hc256Rng, ChaChaRng, ChaCha12Rng, ChaCha8Rng,
+                 Philox4x32Rng, Threefry4x64Rng, HmacDrbg, HashDrbg};
+use rand::prng::drbg::Sha512;
 use rand::prng::hc128::Hc128Core;
 use rand::rngs::adapter::ReseedingRng;
 use rand::rngs::{OsRng, JitterRng, EntropyRng};
 use rand_isaac::{IsaacRng, Isaac64Rng};
+use rand_mt::{Mt19937, Mt19937_64};
+use rand_pcg::{Pcg32, Pcg64, Pcg64Mcg};
 use rand_xorshift::XorShiftRng;
+use rand_xoshiro::{Xoshiro256StarStar, Xoshiro256Plus, Xoroshiro128StarStar,
+                   Xoshiro128StarStar, Xoshiro128PlusPlus, Xoroshiro64Star,
+                   SplitMix64};
 
 macro_rules! gen_bytes {
     ($fnn:ident, $gen:expr) => {
@@ -37,8 +47,27 @@ macro_rules! gen_bytes {
 }
 
 gen_bytes!(gen_bytes_xorshift, XorShiftRng::from_entropy());
+gen_bytes!(gen_bytes_mt19937, Mt19937::from_entropy());
+gen_bytes!(gen_bytes_mt19937_64, Mt19937_64::from_entropy());
+gen_bytes!(gen_bytes_pcg32, Pcg32::from_entropy());
+gen_bytes!(gen_bytes_pcg64, Pcg64::from_entropy());
+gen_bytes!(gen_bytes_pcg64mcg, Pcg64Mcg::from_entropy());
+gen_bytes!(gen_bytes_xoshiro256starstar, Xoshiro256StarStar::from_entropy());
+gen_bytes!(gen_bytes_xoshiro256plus, Xoshiro256Plus::from_entropy());
+gen_bytes!(gen_bytes_xoroshiro128starstar, Xoroshiro128StarStar::from_entropy());
+gen_bytes!(gen_bytes_xoshiro128starstar, Xoshiro128StarStar::from_entropy());
+gen_bytes!(gen_bytes_xoshiro128plusplus, Xoshiro128PlusPlus::from_entropy());
+gen_bytes!(gen_bytes_xoroshiro64star, Xoroshiro64Star::from_entropy());
+gen_bytes!(gen_bytes_splitmix64, SplitMix64::from_entropy());
 gen_bytes!(gen_bytes_chacha20, ChaChaRng::from_entropy());
+gen_bytes!(gen_bytes_chacha12, ChaCha12Rng::from_entropy());
+gen_bytes!(gen_bytes_chacha8, ChaCha8Rng::from_entropy());
 gen_bytes!(gen_bytes_hc128, Hc128Rng::from_entropy());
+gen_bytes!(gen_bytes_hc256, Hc256Rng::from_entropy());
+gen_bytes!(gen_bytes_hmac_drbg, HmacDrbg::<Sha512>::from_entropy());
+gen_bytes!(gen_bytes_hash_drbg, HashDrbg::<Sha512>::from_entropy());
+gen_bytes!(gen_bytes_philox4x32, Philox4x32Rng::from_entropy());
+gen_bytes!(gen_bytes_threefry4x64, Threefry4x64Rng::from_entropy());
 gen_bytes!(gen_bytes_isaac, IsaacRng::from_entropy());
 gen_bytes!(gen_bytes_isaac64, Isaac64Rng::from_entropy());
 gen_bytes!(gen_bytes_std, StdRng::from_entropy());
@@ -63,8 +92,25 @@ macro_rules! gen_uint {
 }
 
 gen_uint!(gen_u32_xorshift, u32, XorShiftRng::from_entropy());
+gen_uint!(gen_u32_mt19937, u32, Mt19937::from_entropy());
+gen_uint!(gen_u32_mt19937_64, u32, Mt19937_64::from_entropy());
+gen_uint!(gen_u32_pcg32, u32, Pcg32::from_entropy());
+gen_uint!(gen_u32_pcg64, u32, Pcg64::from_entropy());
+gen_uint!(gen_u32_pcg64mcg, u32, Pcg64Mcg::from_entropy());
+gen_uint!(gen_u32_xoshiro256starstar, u32, Xoshiro256StarStar::from_entropy());
+gen_uint!(gen_u32_xoshiro256plus, u32, Xoshiro256Plus::from_entropy());
+gen_uint!(gen_u32_xoroshiro128starstar, u32, Xoroshiro128StarStar::from_entropy());
+gen_uint!(gen_u32_xoshiro128starstar, u32, Xoshiro128StarStar::from_entropy());
+gen_uint!(gen_u32_xoshiro128plusplus, u32, Xoshiro128PlusPlus::from_entropy());
+gen_uint!(gen_u32_xoroshiro64star, u32, Xoroshiro64Star::from_entropy());
+gen_uint!(gen_u32_splitmix64, u32, SplitMix64::from_entropy());
 gen_uint!(gen_u32_chacha20, u32, ChaChaRng::from_entropy());
+gen_uint!(gen_u32_chacha12, u32, ChaCha12Rng::from_entropy());
+gen_uint!(gen_u32_chacha8, u32, ChaCha8Rng::from_entropy());
 gen_uint!(gen_u32_hc128, u32, Hc128Rng::from_entropy());
+gen_uint!(gen_u32_hc256, u32, Hc256Rng::from_entropy());
+gen_uint!(gen_u32_philox4x32, u32, Philox4x32Rng::from_entropy());
+gen_uint!(gen_u32_threefry4x64, u32, Threefry4x64Rng::from_entropy());
 gen_uint!(gen_u32_isaac, u32, IsaacRng::from_entropy());
 gen_uint!(gen_u32_isaac64, u32, Isaac64Rng::from_entropy());
 gen_uint!(gen_u32_std, u32, StdRng::from_entropy());
@@ -72,8 +118,25 @@ gen_uint!(gen_u32_small, u32, SmallRng::from_entropy());
 gen_uint!(gen_u32_os, u32, OsRng::new().unwrap());
 
 gen_uint!(gen_u64_xorshift, u64, XorShiftRng::from_entropy());
+gen_uint!(gen_u64_mt19937, u64, Mt19937::from_entropy());
+gen_uint!(gen_u64_mt19937_64, u64, Mt19937_64::from_entropy());
+gen_uint!(gen_u64_pcg32, u64, Pcg32::from_entropy());
+gen_uint!(gen_u64_pcg64, u64, Pcg64::from_entropy());
+gen_uint!(gen_u64_pcg64mcg, u64, Pcg64Mcg::from_entropy());
+gen_uint!(gen_u64_xoshiro256starstar, u64, Xoshiro256StarStar::from_entropy());
+gen_uint!(gen_u64_xoshiro256plus, u64, Xoshiro256Plus::from_entropy());
+gen_uint!(gen_u64_xoroshiro128starstar, u64, Xoroshiro128StarStar::from_entropy());
+gen_uint!(gen_u64_xoshiro128starstar, u64, Xoshiro128StarStar::from_entropy());
+gen_uint!(gen_u64_xoshiro128plusplus, u64, Xoshiro128PlusPlus::from_entropy());
+gen_uint!(gen_u64_xoroshiro64star, u64, Xoroshiro64Star::from_entropy());
+gen_uint!(gen_u64_splitmix64, u64, SplitMix64::from_entropy());
 gen_uint!(gen_u64_chacha20, u64, ChaChaRng::from_entropy());
+gen_uint!(gen_u64_chacha12, u64, ChaCha12Rng::from_entropy());
+gen_uint!(gen_u64_chacha8, u64, ChaCha8Rng::from_entropy());
 gen_uint!(gen_u64_hc128, u64, Hc128Rng::from_entropy());
+gen_uint!(gen_u64_hc256, u64, Hc256Rng::from_entropy());
+gen_uint!(gen_u64_philox4x32, u64, Philox4x32Rng::from_entropy());
+gen_uint!(gen_u64_threefry4x64, u64, Threefry4x64Rng::from_entropy());
 gen_uint!(gen_u64_isaac, u64, IsaacRng::from_entropy());
 gen_uint!(gen_u64_isaac64, u64, Isaac64Rng::from_entropy());
 gen_uint!(gen_u64_std, u64, StdRng::from_entropy());
@@ -92,12 +155,12 @@ fn gen_u64_jitter(b: &mut Bencher) {
 }
 
 macro_rules! init_gen {
-    ($fnn:ident, $gen:ident) => {
+    ($fnn:ident, $gen:ty) => {
         #[bench]
         fn $fnn(b: &mut Bencher) {
             let mut rng = XorShiftRng::from_entropy();
             b.iter(|| {
-                let r2 = $gen::from_rng(&mut rng).unwrap();
+                let r2 = <$gen>::from_rng(&mut rng).unwrap();
                 r2
             });
         }
@@ -105,10 +168,27 @@ macro_rules! init_gen {
 }
 
 init_gen!(init_xorshift, XorShiftRng);
+init_gen!(init_mt19937, Mt19937);
+init_gen!(init_mt19937_64, Mt19937_64);
+init_gen!(init_pcg32, Pcg32);
+init_gen!(init_pcg64, Pcg64);
+init_gen!(init_pcg64mcg, Pcg64Mcg);
+init_gen!(init_xoshiro256starstar, Xoshiro256StarStar);
+init_gen!(init_xoshiro256plus, Xoshiro256Plus);
+init_gen!(init_xoroshiro128starstar, Xoroshiro128StarStar);
+init_gen!(init_xoshiro128starstar, Xoshiro128StarStar);
+init_gen!(init_xoshiro128plusplus, Xoshiro128PlusPlus);
+init_gen!(init_xoroshiro64star, Xoroshiro64Star);
+init_gen!(init_splitmix64, SplitMix64);
 init_gen!(init_hc128, Hc128Rng);
+init_gen!(init_hc256, Hc256Rng);
+init_gen!(init_hmac_drbg, HmacDrbg<Sha512>);
+init_gen!(init_hash_drbg, HashDrbg<Sha512>);
 init_gen!(init_isaac, IsaacRng);
 init_gen!(init_isaac64, Isaac64Rng);
 init_gen!(init_chacha, ChaChaRng);
+init_gen!(init_philox4x32, Philox4x32Rng);
+init_gen!(init_threefry4x64, Threefry4x64Rng);
 
 #[bench]
 fn init_jitter(b: &mut Bencher) {
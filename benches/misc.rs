@@ -8,6 +8,7 @@ const RAND_BENCH_N: u64 = 1000;
 use test::Bencher;
 
 use rand::prelude::*;
+use rand::prng::{Hc128Rng, Threefry4x64Rng};
 
 #[bench]
 fn misc_gen_bool_const(b: &mut Bencher) {
@@ -150,3 +151,25 @@ fn gen_1k_fill(b: &mut Bencher) {
     });
     b.bytes = 1024;
 }
+
+#[bench]
+fn gen_1k_fill_u32_hc128(b: &mut Bencher) {
+    let mut rng = Hc128Rng::from_rng(&mut thread_rng()).unwrap();
+    let mut buf = [0u32; 256];
+    b.iter(|| {
+        rng.fill(&mut buf[..]);
+        buf
+    });
+    b.bytes = 1024;
+}
+
+#[bench]
+fn gen_1k_fill_u64_threefry(b: &mut Bencher) {
+    let mut rng = Threefry4x64Rng::from_rng(&mut thread_rng()).unwrap();
+    let mut buf = [0u64; 128];
+    b.iter(|| {
+        rng.fill(&mut buf[..]);
+        buf
+    });
+    b.bytes = 1024;
+}
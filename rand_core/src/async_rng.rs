@@ -0,0 +1,139 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Non-blocking access to sources of randomness, using `futures` 0.1.
+//!
+//! A source of entropy may not be ready yet, for example the OS generator
+//! early in the boot process. [`RngCore::try_fill_bytes`] then returns an
+//! error of kind [`ErrorKind::NotReady`], leaving it to the caller to retry.
+//! [`AsyncRngCore::poll_fill_bytes`] instead returns `Async::NotReady` and
+//! arranges for the current task to be notified when the source may be
+//! ready, so an executor can run other tasks in the meantime.
+//!
+//! [`RngCore::try_fill_bytes`]: ../trait.RngCore.html#tymethod.try_fill_bytes
+//! [`ErrorKind::NotReady`]: ../enum.ErrorKind.html#variant.NotReady
+//! [`AsyncRngCore::poll_fill_bytes`]: trait.AsyncRngCore.html#tymethod.poll_fill_bytes
+
+use futures::{Async, Future, Poll};
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::boxed::Box;
+use Error;
+
+/// A source of random data which can be polled without blocking.
+///
+/// This follows the conventions of `futures` 0.1: `poll_fill_bytes` must be
+/// called from within a task.
+pub trait AsyncRngCore {
+    /// Attempt to fill `dest` entirely with random data.
+    ///
+    /// Returns `Ok(Async::Ready(()))` when `dest` is filled, and
+    /// `Ok(Async::NotReady)` if the source is not ready yet. In the latter
+    /// case the contents of `dest` are unspecified, and the current task will
+    /// be notified when it should call this method again. Other errors are
+    /// returned as with [`RngCore::try_fill_bytes`].
+    ///
+    /// [`RngCore::try_fill_bytes`]: ../trait.RngCore.html#tymethod.try_fill_bytes
+    fn poll_fill_bytes(&mut self, dest: &mut [u8]) -> Poll<(), Error>;
+
+    /// Return a future which fills `buf` with random data, and resolves to
+    /// the source and the buffer.
+    fn fill_bytes_async<B: AsMut<[u8]>>(self, buf: B) -> FillBytes<Self, B>
+        where Self: Sized
+    {
+        FillBytes { state: Some((self, buf)) }
+    }
+}
+
+impl<'a, R: AsyncRngCore + ?Sized> AsyncRngCore for &'a mut R {
+    fn poll_fill_bytes(&mut self, dest: &mut [u8]) -> Poll<(), Error> {
+        (**self).poll_fill_bytes(dest)
+    }
+}
+
+#[cfg(feature="alloc")]
+impl<R: AsyncRngCore + ?Sized> AsyncRngCore for Box<R> {
+    fn poll_fill_bytes(&mut self, dest: &mut [u8]) -> Poll<(), Error> {
+        (**self).poll_fill_bytes(dest)
+    }
+}
+
+/// A future which fills a buffer with random data, created by
+/// [`AsyncRngCore::fill_bytes_async`].
+///
+/// [`AsyncRngCore::fill_bytes_async`]: trait.AsyncRngCore.html#method.fill_bytes_async
+#[derive(Debug)]
+pub struct FillBytes<R, B> {
+    state: Option<(R, B)>,
+}
+
+impl<R: AsyncRngCore, B: AsMut<[u8]>> Future for FillBytes<R, B> {
+    type Item = (R, B);
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<(R, B), Error> {
+        {
+            let &mut (ref mut rng, ref mut buf) = self.state.as_mut()
+                .expect("FillBytes polled after completion");
+            try_ready!(rng.poll_fill_bytes(buf.as_mut()));
+        }
+        Ok(Async::Ready(self.state.take().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::{Async, Future, Poll};
+    use futures::executor;
+    use futures::task;
+    use super::AsyncRngCore;
+    use {Error, ErrorKind};
+
+    // Not ready for the first `delay` polls, then fills with `0xAA`.
+    struct DelayedRng { delay: usize }
+
+    impl AsyncRngCore for DelayedRng {
+        fn poll_fill_bytes(&mut self, dest: &mut [u8]) -> Poll<(), Error> {
+            if self.delay > 0 {
+                self.delay -= 1;
+                task::current().notify();
+                return Ok(Async::NotReady);
+            }
+            for b in dest.iter_mut() {
+                *b = 0xAA;
+            }
+            Ok(Async::Ready(()))
+        }
+    }
+
+    struct FailingRng;
+
+    impl AsyncRngCore for FailingRng {
+        fn poll_fill_bytes(&mut self, _dest: &mut [u8]) -> Poll<(), Error> {
+            Err(Error::new(ErrorKind::Unavailable, "no entropy"))
+        }
+    }
+
+    #[test]
+    fn test_fill_bytes_async() {
+        let future = DelayedRng { delay: 3 }.fill_bytes_async([0u8; 8]);
+        let (rng, buf) = executor::spawn(future).wait_future().unwrap();
+        assert_eq!(rng.delay, 0);
+        assert_eq!(buf, [0xAA; 8]);
+
+        let mut rng = DelayedRng { delay: 1 };
+        let mut buf = [0u8; 4];
+        executor::spawn((&mut rng).fill_bytes_async(&mut buf[..]))
+            .wait_future().unwrap();
+        assert_eq!(buf, [0xAA; 4]);
+
+        let err = executor::spawn(FailingRng.fill_bytes_async([0u8; 4]))
+            .wait_future().err().unwrap();
+        assert_eq!(err.kind, ErrorKind::Unavailable);
+    }
+}
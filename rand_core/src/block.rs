@@ -55,8 +55,15 @@
 
 use core::convert::AsRef;
 use core::fmt;
-use {RngCore, CryptoRng, SeedableRng, Error};
+use core::cmp::min;
+use core::mem;
+use core::slice;
+use core::ptr;
+#[cfg(feature="zeroize")] use core::sync::atomic;
+use {RngCore, CryptoRng, SeedableRng, Error, ErrorKind};
+use state::{StateReader, StateWriter};
 use impls::{fill_via_u32_chunks, fill_via_u64_chunks};
+#[cfg(feature="serde1")] use serde::{Deserialize, Deserializer};
 
 /// A trait for RNGs which do not generate random numbers individually, but in
 /// blocks (typically `[u32; N]`). This technique is commonly used by
@@ -73,6 +80,56 @@ pub trait BlockRngCore {
 
     /// Generate a new block of results.
     fn generate(&mut self, results: &mut Self::Results);
+
+    /// Generate a new block of results, of which only the first `n` have to
+    /// be valid, and return `n`.
+    ///
+    /// This allows wrapping generators which cannot always fill the whole
+    /// block, such as hardware generators or DRBGs with a limit on the request
+    /// size, without copying their output to a separate buffer. `n` must be
+    /// at least 1 and at most the length of `results`.
+    ///
+    /// [`BlockRng`] and [`BlockRng64`] only use this method. The default
+    /// implementation calls `generate` and returns the full length; cores
+    /// which override it must still implement `generate` to fill the whole
+    /// block, for other users of the trait.
+    ///
+    /// [`BlockRng`]: struct.BlockRng.html
+    /// [`BlockRng64`]: struct.BlockRng64.html
+    fn generate_partial(&mut self, results: &mut Self::Results) -> usize {
+        self.generate(results);
+        results.as_ref().len()
+    }
+}
+
+// Check the number of results returned by `generate_partial`.
+#[inline(always)]
+fn checked_len(len: usize, max: usize) -> usize {
+    assert!(len > 0 && len <= max,
+            "BlockRngCore::generate_partial returned an invalid length");
+    len
+}
+
+// States serialized before `len` was added do not have it; their whole buffer
+// is valid.
+#[cfg(feature="serde1")]
+fn unknown_len() -> usize {
+    ::core::usize::MAX
+}
+
+// Check the number of valid results of a deserialized state, which may have
+// been modified or corrupted.
+#[cfg(feature="serde1")]
+fn deserialized_len<E: ::serde::de::Error>(len: usize, max: usize)
+    -> Result<usize, E>
+{
+    if len == unknown_len() {
+        Ok(max)
+    } else if len <= max {
+        Ok(len)
+    } else {
+        Err(E::custom("BlockRng: more valid results than the buffer length"))
+    }
 }
 
 
@@ -90,7 +147,7 @@ pub trait BlockRngCore {
 ///
 /// `BlockRng` has heavily optimized implementations of the [`RngCore`] methods
 /// reading values from the results buffer, as well as
-/// calling [`BlockRngCore::generate`] directly on the output array when
+/// calling [`BlockRngCore::generate_partial`] directly on the output array when
 /// [`fill_bytes`] / [`try_fill_bytes`] is called on a large array. These methods
 /// also handle the bookkeeping of when to generate a new batch of values.
 ///
@@ -102,13 +159,20 @@ pub trait BlockRngCore {
 /// little-endian order. If the requested byte length is not a multiple of 4,
 /// some bytes will be discarded.
 ///
+/// If the core returns a partial block from
+/// [`BlockRngCore::generate_partial`], only the valid results are used. The
+/// buffered results can be inspected with [`remaining_results`] and skipped
+/// with [`discard`].
+///
 /// See also [`BlockRng64`] which uses `u64` array buffers. Currently there is
 /// no direct support for other buffer types.
 ///
 /// For easy initialization `BlockRng` also implements [`SeedableRng`].
 ///
 /// [`BlockRngCore`]: BlockRngCore.t.html
-/// [`BlockRngCore::generate`]: trait.BlockRngCore.html#tymethod.generate
+/// [`BlockRngCore::generate_partial`]: trait.BlockRngCore.html#method.generate_partial
+/// [`remaining_results`]: #method.remaining_results
+/// [`discard`]: #method.discard
 /// [`BlockRng64`]: struct.BlockRng64.html
 /// [`RngCore`]: ../RngCore.t.html
 /// [`next_u32`]: ../trait.RngCore.html#tymethod.next_u32
@@ -117,14 +181,44 @@ pub trait BlockRngCore {
 /// [`try_fill_bytes`]: ../trait.RngCore.html#tymethod.try_fill_bytes
 /// [`SeedableRng`]: ../SeedableRng.t.html
 #[derive(Clone)]
-#[cfg_attr(feature="serde1", derive(Serialize, Deserialize))]
+#[cfg_attr(feature="serde1", derive(Serialize))]
 pub struct BlockRng<R: BlockRngCore + ?Sized> {
     results: R::Results,
     index: usize,
+    len: usize, // number of valid results
     /// The *core* part of the RNG, implementing the `generate` function.
     pub core: R,
 }
 
+// Custom Deserialize implementation that checks the number of valid results.
+#[cfg(feature="serde1")]
+impl<'de, R> Deserialize<'de> for BlockRng<R>
+    where R: BlockRngCore + Deserialize<'de>, R::Results: Deserialize<'de>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D)
+        -> Result<Self, D::Error>
+    {
+        #[derive(Deserialize)]
+        #[serde(rename = "BlockRng")]
+        struct State<R: BlockRngCore> {
+            results: R::Results,
+            index: usize,
+            #[serde(default = "unknown_len")]
+            len: usize,
+            core: R,
+        }
+
+        let state = State::<R>::deserialize(deserializer)?;
+        let len = deserialized_len(state.len, state.results.as_ref().len())?;
+        Ok(BlockRng {
+            results: state.results,
+            index: state.index,
+            len,
+            core: state.core,
+        })
+    }
+}
+
 // Custom Debug implementation that does not expose the contents of `results`.
 impl<R: BlockRngCore + fmt::Debug> fmt::Debug for BlockRng<R> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
@@ -144,13 +238,14 @@ impl<R: BlockRngCore> BlockRng<R> {
         BlockRng {
             core,
             index: results_empty.as_ref().len(),
+            len: results_empty.as_ref().len(),
             results: results_empty,
         }
     }
 
     /// Get the index into the result buffer.
     /// 
-    /// If this is equal to or larger than the size of the result buffer then
+    /// If this is equal to or larger than the number of valid results then
     /// the buffer is "empty" and `generate()` must be called to produce new
     /// results.
     pub fn index(&self) -> usize {
@@ -160,15 +255,93 @@ impl<R: BlockRngCore> BlockRng<R> {
     /// Reset the number of available results.
     /// This will force a new set of results to be generated on next use.
     pub fn reset(&mut self) {
-        self.index = self.results.as_ref().len();
+        self.index = self.len;
     }
 
     /// Generate a new set of results immediately, setting the index to the
     /// given value.
+    ///
+    /// If the core returns fewer than `index` results, the buffer is empty.
     pub fn generate_and_set(&mut self, index: usize) {
         assert!(index < self.results.as_ref().len());
-        self.core.generate(&mut self.results);
+        let len = self.core.generate_partial(&mut self.results);
+        self.len = checked_len(len, self.results.as_ref().len());
+        self.index = index;
+    }
+
+    /// The buffered results which have not been consumed yet, in the order
+    /// in which they will be used.
+    pub fn remaining_results(&self) -> &[R::Item] {
+        if self.index < self.len {
+            &self.results.as_ref()[self.index..self.len]
+        } else {
+            &[]
+        }
+    }
+
+    /// Discard the next `n` results, as if they were consumed.
+    ///
+    /// Buffered results are skipped without generating anything; new blocks
+    /// are only generated if `n` is larger than the number of remaining
+    /// results.
+    pub fn discard(&mut self, mut n: usize) {
+        loop {
+            let remaining = self.len.saturating_sub(self.index);
+            if n <= remaining {
+                self.index += n;
+                return;
+            }
+            n -= remaining;
+            self.generate_and_set(0);
+        }
+    }
+}
+
+impl<R: BlockRngCore<Item=u32>> BlockRng<R> {
+    /// The length in bytes of the buffer state written by
+    /// `write_buffer_state`: the index as `u32`, followed by the result
+    /// buffer.
+    pub fn buffer_state_len() -> usize {
+        4 + 4 * R::Results::default().as_ref().len()
+    }
+
+    /// Write the index and the result buffer, for implementing
+    /// [`StateBytes`](../state/trait.StateBytes.html).
+    ///
+    /// A partial block is written as a full block with the remaining results
+    /// at the end, so the format does not depend on the number of valid
+    /// results.
+    pub fn write_buffer_state(&self, w: &mut StateWriter) {
+        let results = self.results.as_ref();
+        if self.len == results.len() {
+            w.write_u32(self.index as u32);
+            w.write_u32s(results);
+        } else {
+            let remaining = self.remaining_results();
+            let index = results.len() - remaining.len();
+            w.write_u32(index as u32);
+            w.write_u32s(&results[..index]);
+            w.write_u32s(remaining);
+        }
+    }
+
+    /// Restore the index and the result buffer written by
+    /// `write_buffer_state`.
+    ///
+    /// Returns an error of kind `ErrorKind::Unavailable` if the index is
+    /// larger than the result buffer.
+    pub fn read_buffer_state(&mut self, r: &mut StateReader)
+        -> Result<(), Error>
+    {
+        let index = r.read_u32() as usize;
+        if index > self.results.as_ref().len() {
+            return Err(Error::new(ErrorKind::Unavailable,
+                                  "state bytes: invalid buffer index"));
+        }
+        r.read_u32s(self.results.as_mut());
         self.index = index;
+        self.len = self.results.as_ref().len();
+        Ok(())
     }
 }
 
@@ -177,7 +350,7 @@ where <R as BlockRngCore>::Results: AsRef<[u32]> + AsMut<[u32]>
 {
     #[inline(always)]
     fn next_u32(&mut self) -> u32 {
-        if self.index >= self.results.as_ref().len() {
+        if self.index >= self.len {
             self.generate_and_set(0);
         }
 
@@ -191,7 +364,10 @@ where <R as BlockRngCore>::Results: AsRef<[u32]> + AsMut<[u32]>
         let read_u64 = |results: &[u32], index| {
             if cfg!(any(target_arch = "x86", target_arch = "x86_64")) {
                 // requires little-endian CPU supporting unaligned reads:
-                unsafe { *(&results[index] as *const u32 as *const u64) }
+                unsafe {
+                    ptr::read_unaligned(&results[index] as *const u32
+                                        as *const u64)
+                }
             } else {
                 let x = u64::from(results[index]);
                 let y = u64::from(results[index + 1]);
@@ -199,20 +375,15 @@ where <R as BlockRngCore>::Results: AsRef<[u32]> + AsMut<[u32]>
             }
         };
 
-        let len = self.results.as_ref().len();
-
         let index = self.index;
-        if index < len-1 {
+        if index + 1 < self.len {
             self.index += 2;
             // Read an u64 from the current index
             read_u64(self.results.as_ref(), index)
-        } else if index >= len {
-            self.generate_and_set(2);
-            read_u64(self.results.as_ref(), 0)
         } else {
-            let x = u64::from(self.results.as_ref()[len-1]);
-            self.generate_and_set(1);
-            let y = u64::from(self.results.as_ref()[0]);
+            // The two halves may come from different blocks.
+            let x = u64::from(self.next_u32());
+            let y = u64::from(self.next_u32());
             (y << 32) | x
         }
     }
@@ -225,36 +396,34 @@ where <R as BlockRngCore>::Results: AsRef<[u32]> + AsMut<[u32]>
         let mut filled = 0;
 
         // Continue filling from the current set of results
-        if self.index < self.results.as_ref().len() {
+        if self.index < self.len {
             let (consumed_u32, filled_u8) =
-                fill_via_u32_chunks(&self.results.as_ref()[self.index..],
+                fill_via_u32_chunks(&self.results.as_ref()[self.index..self.len],
                                     dest);
 
             self.index += consumed_u32;
             filled += filled_u8;
         }
 
-        let len_remainder =
-            (dest.len() - filled) % (self.results.as_ref().len() * 4);
-        let end_direct = dest.len() - len_remainder;
-
-        while filled < end_direct {
+        let block_len = self.results.as_ref().len();
+        while dest.len() - filled >= block_len * 4 {
             let dest_u32: &mut R::Results = unsafe {
                 &mut *(dest[filled..].as_mut_ptr() as
                 *mut <R as BlockRngCore>::Results)
             };
-            self.core.generate(dest_u32);
-            filled += self.results.as_ref().len() * 4;
-            self.index = self.results.as_ref().len();
+            let len = self.core.generate_partial(dest_u32);
+            filled += checked_len(len, block_len) * 4;
+            self.index = self.len;
         }
 
-        if len_remainder > 0 {
-            self.core.generate(&mut self.results);
-            let (consumed_u32, _) =
-                fill_via_u32_chunks(self.results.as_ref(),
+        while filled < dest.len() {
+            self.generate_and_set(0);
+            let (consumed_u32, filled_u8) =
+                fill_via_u32_chunks(&self.results.as_ref()[..self.len],
                                     &mut dest[filled..]);
 
             self.index = consumed_u32;
+            filled += filled_u8;
         }
     }
 
@@ -262,11 +431,11 @@ where <R as BlockRngCore>::Results: AsRef<[u32]> + AsMut<[u32]>
     fn fill_bytes(&mut self, dest: &mut [u8]) {
         let mut read_len = 0;
         while read_len < dest.len() {
-            if self.index >= self.results.as_ref().len() {
+            if self.index >= self.len {
                 self.generate_and_set(0);
             }
             let (consumed_u32, filled_u8) =
-                fill_via_u32_chunks(&self.results.as_ref()[self.index..],
+                fill_via_u32_chunks(&self.results.as_ref()[self.index..self.len],
                                     &mut dest[read_len..]);
 
             self.index += consumed_u32;
@@ -278,6 +447,43 @@ where <R as BlockRngCore>::Results: AsRef<[u32]> + AsMut<[u32]>
         self.fill_bytes(dest);
         Ok(())
     }
+
+    // Copy words from the result buffer, and generate whole blocks directly
+    // into `dest` where the layout of the results allows it. This gives the
+    // same values as `fill_bytes`, without the conversion through bytes.
+    fn fill_u32(&mut self, dest: &mut [u32]) {
+        let block_len = self.results.as_ref().len();
+        let mut filled = 0;
+
+        // Continue filling from the current set of results
+        if self.index < self.len {
+            let n = min(self.len - self.index, dest.len());
+            dest[..n].copy_from_slice(
+                &self.results.as_ref()[self.index..self.index + n]);
+            self.index += n;
+            filled = n;
+        }
+
+        let direct = mem::size_of::<R::Results>() == block_len * 4 &&
+                     mem::align_of::<R::Results>() <= mem::align_of::<u32>();
+        while direct && dest.len() - filled >= block_len {
+            let dest_results: &mut R::Results = unsafe {
+                &mut *(dest[filled..].as_mut_ptr() as *mut R::Results)
+            };
+            let len = self.core.generate_partial(dest_results);
+            filled += checked_len(len, block_len);
+            self.index = self.len;
+        }
+
+        while filled < dest.len() {
+            self.generate_and_set(0);
+            let n = min(self.len, dest.len() - filled);
+            dest[filled..filled + n].copy_from_slice(
+                &self.results.as_ref()[..n]);
+            self.index = n;
+            filled += n;
+        }
+    }
 }
 
 impl<R: BlockRngCore + SeedableRng> SeedableRng for BlockRng<R> {
@@ -320,15 +526,47 @@ impl<R: BlockRngCore + SeedableRng> SeedableRng for BlockRng<R> {
 /// [`try_fill_bytes`]: ../trait.RngCore.html#tymethod.try_fill_bytes
 /// [`BlockRng`]: struct.BlockRng.html
 #[derive(Clone)]
-#[cfg_attr(feature="serde1", derive(Serialize, Deserialize))]
+#[cfg_attr(feature="serde1", derive(Serialize))]
 pub struct BlockRng64<R: BlockRngCore + ?Sized> {
     results: R::Results,
     index: usize,
+    len: usize, // number of valid results
     half_used: bool, // true if only half of the previous result is used
     /// The *core* part of the RNG, implementing the `generate` function.
     pub core: R,
 }
 
+// Custom Deserialize implementation that checks the number of valid results.
+#[cfg(feature="serde1")]
+impl<'de, R> Deserialize<'de> for BlockRng64<R>
+    where R: BlockRngCore + Deserialize<'de>, R::Results: Deserialize<'de>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D)
+        -> Result<Self, D::Error>
+    {
+        #[derive(Deserialize)]
+        #[serde(rename = "BlockRng64")]
+        struct State<R: BlockRngCore> {
+            results: R::Results,
+            index: usize,
+            #[serde(default = "unknown_len")]
+            len: usize,
+            half_used: bool,
+            core: R,
+        }
+
+        let state = State::<R>::deserialize(deserializer)?;
+        let len = deserialized_len(state.len, state.results.as_ref().len())?;
+        Ok(BlockRng64 {
+            results: state.results,
+            index: state.index,
+            len,
+            half_used: state.half_used,
+            core: state.core,
+        })
+    }
+}
+
 // Custom Debug implementation that does not expose the contents of `results`.
 impl<R: BlockRngCore + fmt::Debug> fmt::Debug for BlockRng64<R> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
@@ -349,6 +587,7 @@ impl<R: BlockRngCore> BlockRng64<R> {
         BlockRng64 {
             core,
             index: results_empty.as_ref().len(),
+            len: results_empty.as_ref().len(),
             half_used: false,
             results: results_empty,
         }
@@ -356,7 +595,7 @@ impl<R: BlockRngCore> BlockRng64<R> {
 
     /// Get the index into the result buffer.
     ///
-    /// If this is equal to or larger than the size of the result buffer then
+    /// If this is equal to or larger than the number of valid results then
     /// the buffer is "empty" and `generate()` must be called to produce new
     /// results.
     pub fn index(&self) -> usize {
@@ -366,18 +605,110 @@ impl<R: BlockRngCore> BlockRng64<R> {
     /// Reset the number of available results.
     /// This will force a new set of results to be generated on next use.
     pub fn reset(&mut self) {
-        self.index = self.results.as_ref().len();
+        self.index = self.len;
         self.half_used = false;
     }
 
     /// Generate a new set of results immediately, setting the index to the
     /// given value.
+    ///
+    /// If the core returns fewer than `index` results, the buffer is empty.
     pub fn generate_and_set(&mut self, index: usize) {
         assert!(index < self.results.as_ref().len());
-        self.core.generate(&mut self.results);
+        let len = self.core.generate_partial(&mut self.results);
+        self.len = checked_len(len, self.results.as_ref().len());
         self.index = index;
         self.half_used = false;
     }
+
+    /// The buffered results which have not been consumed yet, in the order
+    /// in which they will be used.
+    ///
+    /// This does not include the other half of a result of which
+    /// `next_u32` used only half.
+    pub fn remaining_results(&self) -> &[R::Item] {
+        if self.index < self.len {
+            &self.results.as_ref()[self.index..self.len]
+        } else {
+            &[]
+        }
+    }
+
+    /// Discard the next `n` results, as if they were consumed, and the rest
+    /// of any half-used result.
+    ///
+    /// Buffered results are skipped without generating anything; new blocks
+    /// are only generated if `n` is larger than the number of remaining
+    /// results.
+    pub fn discard(&mut self, mut n: usize) {
+        self.half_used = false;
+        loop {
+            let remaining = self.len.saturating_sub(self.index);
+            if n <= remaining {
+                self.index += n;
+                return;
+            }
+            n -= remaining;
+            self.generate_and_set(0);
+        }
+    }
+}
+
+impl<R: BlockRngCore<Item=u64>> BlockRng64<R> {
+    /// The length in bytes of the buffer state written by
+    /// `write_buffer_state`: the index and `half_used` flag as `u32`,
+    /// followed by the result buffer.
+    pub fn buffer_state_len() -> usize {
+        8 + 8 * R::Results::default().as_ref().len()
+    }
+
+    /// Write the index, the `half_used` flag and the result buffer, for
+    /// implementing [`StateBytes`](../state/trait.StateBytes.html).
+    ///
+    /// A partial block is written as a full block with the remaining results
+    /// at the end, so the format does not depend on the number of valid
+    /// results.
+    pub fn write_buffer_state(&self, w: &mut StateWriter) {
+        let results = self.results.as_ref();
+        if self.len == results.len() {
+            w.write_u32(self.index as u32);
+            w.write_u32(self.half_used as u32);
+            w.write_u64s(results);
+        } else {
+            let remaining = self.remaining_results();
+            let index = results.len() - remaining.len();
+            w.write_u32(index as u32);
+            w.write_u32(self.half_used as u32);
+            // Keep a half-used result just before the remaining results
+            let prefix = index - self.half_used as usize;
+            w.write_u64s(&results[..prefix]);
+            if self.half_used {
+                w.write_u64(results[self.index - 1]);
+            }
+            w.write_u64s(remaining);
+        }
+    }
+
+    /// Restore the index, the `half_used` flag and the result buffer written
+    /// by `write_buffer_state`.
+    ///
+    /// Returns an error of kind `ErrorKind::Unavailable` if the index is
+    /// larger than the result buffer, or the flag is not 0 or 1.
+    pub fn read_buffer_state(&mut self, r: &mut StateReader)
+        -> Result<(), Error>
+    {
+        let index = r.read_u32() as usize;
+        let half_used = r.read_u32();
+        if index > self.results.as_ref().len() || half_used > 1 {
+            return Err(Error::new(ErrorKind::Unavailable,
+                                  "state bytes: invalid buffer index"));
+        }
+        r.read_u64s(self.results.as_mut());
+        self.index = index;
+        self.len = self.results.as_ref().len();
+        self.half_used = half_used == 1;
+        Ok(())
+    }
 }
 
 impl<R: BlockRngCore<Item=u64>> RngCore for BlockRng64<R>
@@ -386,11 +717,9 @@ where <R as BlockRngCore>::Results: AsRef<[u64]> + AsMut<[u64]>
     #[inline(always)]
     fn next_u32(&mut self) -> u32 {
         let mut index = self.index * 2 - self.half_used as usize;
-        if index >= self.results.as_ref().len() * 2 {
-            self.core.generate(&mut self.results);
-            self.index = 0;
+        if index >= self.len * 2 {
             // `self.half_used` is by definition `false`
-            self.half_used = false;
+            self.generate_and_set(0);
             index = 0;
         }
 
@@ -399,8 +728,9 @@ where <R as BlockRngCore>::Results: AsRef<[u64]> + AsMut<[u64]>
 
         // Index as if this is a u32 slice.
         unsafe {
-            let results =
-                &*(self.results.as_ref() as *const [u64] as *const [u32]);
+            let results = self.results.as_ref();
+            let results = slice::from_raw_parts(results.as_ptr() as *const u32,
+                                                results.len() * 2);
             if cfg!(target_endian = "little") {
                 *results.get_unchecked(index)
             } else {
@@ -411,9 +741,8 @@ where <R as BlockRngCore>::Results: AsRef<[u64]> + AsMut<[u64]>
 
     #[inline(always)]
     fn next_u64(&mut self) -> u64 {
-        if self.index >= self.results.as_ref().len() {
-            self.core.generate(&mut self.results);
-            self.index = 0;
+        if self.index >= self.len {
+            self.generate_and_set(0);
         }
 
         let value = self.results.as_ref()[self.index];
@@ -431,35 +760,33 @@ where <R as BlockRngCore>::Results: AsRef<[u64]> + AsMut<[u64]>
         self.half_used = false;
 
         // Continue filling from the current set of results
-        if self.index < self.results.as_ref().len() {
+        if self.index < self.len {
             let (consumed_u64, filled_u8) =
-                fill_via_u64_chunks(&self.results.as_ref()[self.index..],
+                fill_via_u64_chunks(&self.results.as_ref()[self.index..self.len],
                                     dest);
 
             self.index += consumed_u64;
             filled += filled_u8;
         }
 
-        let len_remainder =
-            (dest.len() - filled) % (self.results.as_ref().len() * 8);
-        let end_direct = dest.len() - len_remainder;
-
-        while filled < end_direct {
+        let block_len = self.results.as_ref().len();
+        while dest.len() - filled >= block_len * 8 {
             let dest_u64: &mut R::Results = unsafe {
                 ::core::mem::transmute(dest[filled..].as_mut_ptr())
             };
-            self.core.generate(dest_u64);
-            filled += self.results.as_ref().len() * 8;
-            self.index = self.results.as_ref().len();
+            let len = self.core.generate_partial(dest_u64);
+            filled += checked_len(len, block_len) * 8;
+            self.index = self.len;
         }
 
-        if len_remainder > 0 {
-            self.core.generate(&mut self.results);
-            let (consumed_u64, _) =
-                fill_via_u64_chunks(&mut self.results.as_ref(),
+        while filled < dest.len() {
+            self.generate_and_set(0);
+            let (consumed_u64, filled_u8) =
+                fill_via_u64_chunks(&self.results.as_ref()[..self.len],
                                     &mut dest[filled..]);
 
             self.index = consumed_u64;
+            filled += filled_u8;
         }
     }
 
@@ -468,13 +795,12 @@ where <R as BlockRngCore>::Results: AsRef<[u64]> + AsMut<[u64]>
         let mut read_len = 0;
         self.half_used = false;
         while read_len < dest.len() {
-            if self.index as usize >= self.results.as_ref().len() {
-                self.core.generate(&mut self.results);
-                self.index = 0;
+            if self.index >= self.len {
+                self.generate_and_set(0);
             }
 
             let (consumed_u64, filled_u8) =
-                fill_via_u64_chunks(&self.results.as_ref()[self.index as usize..],
+                fill_via_u64_chunks(&self.results.as_ref()[self.index..self.len],
                                     &mut dest[read_len..]);
 
             self.index += consumed_u64;
@@ -485,6 +811,44 @@ where <R as BlockRngCore>::Results: AsRef<[u64]> + AsMut<[u64]>
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         Ok(self.fill_bytes(dest))
     }
+
+    // Copy words from the result buffer, and generate whole blocks directly
+    // into `dest` where the layout of the results allows it. This gives the
+    // same values as `fill_bytes`, without the conversion through bytes.
+    fn fill_u64(&mut self, dest: &mut [u64]) {
+        let block_len = self.results.as_ref().len();
+        let mut filled = 0;
+        self.half_used = false;
+
+        // Continue filling from the current set of results
+        if self.index < self.len {
+            let n = min(self.len - self.index, dest.len());
+            dest[..n].copy_from_slice(
+                &self.results.as_ref()[self.index..self.index + n]);
+            self.index += n;
+            filled = n;
+        }
+
+        let direct = mem::size_of::<R::Results>() == block_len * 8 &&
+                     mem::align_of::<R::Results>() <= mem::align_of::<u64>();
+        while direct && dest.len() - filled >= block_len {
+            let dest_results: &mut R::Results = unsafe {
+                &mut *(dest[filled..].as_mut_ptr() as *mut R::Results)
+            };
+            let len = self.core.generate_partial(dest_results);
+            filled += checked_len(len, block_len);
+            self.index = self.len;
+        }
+
+        while filled < dest.len() {
+            self.generate_and_set(0);
+            let n = min(self.len, dest.len() - filled);
+            dest[filled..filled + n].copy_from_slice(
+                &self.results.as_ref()[..n]);
+            self.index = n;
+            filled += n;
+        }
+    }
 }
 
 impl<R: BlockRngCore + SeedableRng> SeedableRng for BlockRng64<R> {
@@ -500,3 +864,204 @@ impl<R: BlockRngCore + SeedableRng> SeedableRng for BlockRng64<R> {
 }
 
 impl<R: BlockRngCore + CryptoRng> CryptoRng for BlockRng<R> {}
+
+// With the `zeroize` feature, wipe the buffered results on drop. The core is
+// responsible for wiping its own state.
+#[cfg(feature="zeroize")]
+impl<R: BlockRngCore + ?Sized> Drop for BlockRng<R> {
+    fn drop(&mut self) {
+        unsafe { ptr::write_volatile(&mut self.results, R::Results::default()); }
+        atomic::compiler_fence(atomic::Ordering::SeqCst);
+    }
+}
+
+#[cfg(feature="zeroize")]
+impl<R: BlockRngCore + ?Sized> Drop for BlockRng64<R> {
+    fn drop(&mut self) {
+        unsafe { ptr::write_volatile(&mut self.results, R::Results::default()); }
+        atomic::compiler_fence(atomic::Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use RngCore;
+    use super::{BlockRngCore, BlockRng, BlockRng64};
+    use state::{StateReader, StateWriter};
+
+    // Counts upwards from zero, in blocks of 1 to 8 results with
+    // `generate_partial`.
+    macro_rules! partial_core {
+        ($name:ident, $ty:ty) => {
+            #[derive(Clone)]
+            #[cfg_attr(feature="serde1", derive(Serialize, Deserialize))]
+            struct $name { counter: $ty, n: usize }
+
+            impl BlockRngCore for $name {
+                type Item = $ty;
+                type Results = [$ty; 8];
+
+                fn generate(&mut self, results: &mut Self::Results) {
+                    for x in results.iter_mut() {
+                        *x = self.counter;
+                        self.counter += 1;
+                    }
+                }
+
+                fn generate_partial(&mut self, results: &mut Self::Results)
+                    -> usize
+                {
+                    self.n = self.n % 8 + 1;
+                    for x in results[..self.n].iter_mut() {
+                        *x = self.counter;
+                        self.counter += 1;
+                    }
+                    self.n
+                }
+            }
+        }
+    }
+
+    partial_core!(PartialCore, u32);
+    partial_core!(PartialCore64, u64);
+
+    #[cfg(all(feature="serde1", feature="std"))]
+    #[test]
+    fn test_block_rng_serde() {
+        use bincode;
+
+        // Stop in the middle of a partial block
+        let mut rng = BlockRng::new(PartialCore { counter: 0, n: 0 });
+        for _ in 0..4 {
+            rng.next_u32();
+        }
+        assert_eq!(rng.remaining_results(), &[4, 5]);
+
+        let buf = bincode::serialize(&rng).expect("Could not serialize");
+        let mut deserialized: BlockRng<PartialCore> =
+            bincode::deserialize(&buf).expect("Could not deserialize");
+        assert_eq!(deserialized.remaining_results(), &[4, 5]);
+        for _ in 0..20 {
+            assert_eq!(rng.next_u32(), deserialized.next_u32());
+        }
+
+        // More valid results than the buffer holds: the number of valid
+        // results follows the 8 results and the index.
+        let mut buf = bincode::serialize(&rng).expect("Could not serialize");
+        buf[8 * 4 + 8] = 9;
+        let result: Result<BlockRng<PartialCore>, _> = bincode::deserialize(&buf);
+        assert!(result.is_err());
+
+        let mut rng = BlockRng64::new(PartialCore64 { counter: 0, n: 0 });
+        for _ in 0..7 {
+            rng.next_u32();
+        }
+
+        let buf = bincode::serialize(&rng).expect("Could not serialize");
+        let mut deserialized: BlockRng64<PartialCore64> =
+            bincode::deserialize(&buf).expect("Could not deserialize");
+        for _ in 0..20 {
+            assert_eq!(rng.next_u32(), deserialized.next_u32());
+        }
+
+        let mut buf = bincode::serialize(&rng).expect("Could not serialize");
+        buf[8 * 8 + 8] = 9;
+        let result: Result<BlockRng64<PartialCore64>, _> = bincode::deserialize(&buf);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_block_rng_partial() {
+        let mut rng = BlockRng::new(PartialCore { counter: 0, n: 0 });
+        for i in 0..50 {
+            assert_eq!(rng.next_u32(), i);
+        }
+        for i in 0..50 {
+            let x = 50 + 2 * i as u32;
+            assert_eq!(rng.next_u64(), (u64::from(x + 1) << 32) | u64::from(x));
+        }
+
+        let mut words = [0u32; 100];
+        rng.fill_u32(&mut words);
+        for (i, &x) in words.iter().enumerate() {
+            assert_eq!(x, 150 + i as u32);
+        }
+
+        let mut bytes = [0u8; 400];
+        rng.fill_bytes(&mut bytes);
+        for (i, chunk) in bytes.chunks(4).enumerate() {
+            let x = chunk.iter().rev().fold(0, |x, &b| (x << 8) | u32::from(b));
+            assert_eq!(x, 250 + i as u32);
+        }
+        assert_eq!(rng.next_u32(), 350);
+    }
+
+    #[test]
+    fn test_block_rng_remaining_discard() {
+        let mut rng = BlockRng::new(PartialCore { counter: 0, n: 0 });
+        assert_eq!(rng.remaining_results(), &[]);
+        assert_eq!(rng.next_u32(), 0);
+        assert_eq!(rng.remaining_results(), &[]);
+        assert_eq!(rng.next_u32(), 1);
+        assert_eq!(rng.remaining_results(), &[2]);
+
+        // Skips the buffered 2, then the block [3, 4, 5] and 6.
+        rng.discard(5);
+        assert_eq!(rng.remaining_results(), &[7, 8, 9]);
+        rng.discard(0);
+        rng.discard(1);
+        assert_eq!(rng.remaining_results(), &[8, 9]);
+        assert_eq!(rng.next_u32(), 8);
+
+        rng.reset();
+        assert_eq!(rng.remaining_results(), &[]);
+        assert_eq!(rng.next_u32(), 10);
+    }
+
+    #[test]
+    fn test_block_rng64_partial() {
+        let mut rng = BlockRng64::new(PartialCore64 { counter: 0, n: 0 });
+        for i in 0..50 {
+            assert_eq!(rng.next_u64(), i);
+        }
+        // The last result of the block [46, 50], in two halves
+        assert_eq!(rng.next_u32(), 50);
+        assert_eq!(rng.remaining_results(), &[]);
+        assert_eq!(rng.next_u32(), 0);
+
+        // Discards the rest of the half-used 51, and 52
+        assert_eq!(rng.next_u32(), 51);
+        assert_eq!(rng.remaining_results(), &[52, 53, 54, 55, 56]);
+        rng.discard(1);
+        assert_eq!(rng.remaining_results(), &[53, 54, 55, 56]);
+        assert_eq!(rng.next_u64(), 53);
+
+        let mut words = [0u64; 100];
+        rng.fill_u64(&mut words);
+        for (i, &x) in words.iter().enumerate() {
+            assert_eq!(x, 54 + i as u64);
+        }
+        assert_eq!(rng.next_u64(), 154);
+    }
+
+    #[test]
+    fn test_block_rng_partial_buffer_state() {
+        let mut rng = BlockRng::new(PartialCore { counter: 0, n: 0 });
+        for _ in 0..4 {
+            rng.next_u32();
+        }
+        assert_eq!(rng.remaining_results(), &[4, 5]);
+
+        let mut buf = [0u8; 36];
+        assert_eq!(BlockRng::<PartialCore>::buffer_state_len(), buf.len());
+        rng.write_buffer_state(&mut StateWriter::new(&mut buf));
+
+        let mut rng2 = BlockRng::new(rng.core.clone());
+        rng2.read_buffer_state(&mut StateReader::new(&buf)).unwrap();
+        assert_eq!(rng2.index(), 6);
+        assert_eq!(rng2.remaining_results(), &[4, 5]);
+        for _ in 0..20 {
+            assert_eq!(rng.next_u32(), rng2.next_u32());
+        }
+    }
+}
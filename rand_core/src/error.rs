@@ -11,6 +11,7 @@
 //! Error types
 
 use core::fmt;
+#[cfg(rust_1_28)] use core::num::NonZeroU32;
 
 #[cfg(feature="std")]
 use std::error::Error as stdError;
@@ -83,28 +84,47 @@ impl ErrorKind {
 /// 
 /// This is a relatively simple error type, designed for compatibility with and
 /// without the Rust `std` library. It embeds a "kind" code, a message (static
-/// string only), and an optional chained cause (`std` only). The `kind` and
-/// `msg` fields can be accessed directly; cause can be accessed via
+/// string only), an optional numeric error code and an optional chained cause
+/// (`std` only). The `kind` and `msg` fields can be accessed directly; the
+/// code via `raw_os_error`, `custom_code` or `code`, and the cause via
 /// `std::error::Error::cause` or `Error::take_cause`. Construction can only be
-/// done via `Error::new` or `Error::with_cause`.
+/// done via `Error::new`, `Error::with_cause`, `Error::from_os_error`,
+/// `Error::with_custom_code`, or from a `std::io::Error`.
+///
+/// The numeric code is available without `std`, so that sources like
+/// hardware generators can report why they failed. Codes below
+/// [`CUSTOM_START`] are OS error codes (`errno` on Unix-like platforms),
+/// codes from `CUSTOM_START` are custom codes chosen by the generator.
+///
+/// [`CUSTOM_START`]: #associatedconstant.CUSTOM_START
 #[derive(Debug)]
 pub struct Error {
     /// The error kind
     pub kind: ErrorKind,
     /// The error message
     pub msg: &'static str,
+    // OS or custom error code; 0 if there is none
+    code: u32,
     #[cfg(feature="std")]
     cause: Option<Box<stdError + Send + Sync>>,
 }
 
 impl Error {
+    /// The first error code for custom errors; smaller codes are OS error
+    /// codes.
+    pub const CUSTOM_START: u32 = 1 << 31;
+
     /// Create a new instance, with specified kind and a message.
     pub fn new(kind: ErrorKind, msg: &'static str) -> Self {
+        Error::with_code(kind, msg, 0)
+    }
+
+    fn with_code(kind: ErrorKind, msg: &'static str, code: u32) -> Self {
         #[cfg(feature="std")] {
-            Error { kind, msg, cause: None }
+            Error { kind, msg, code, cause: None }
         }
         #[cfg(not(feature="std"))] {
-            Error { kind, msg }
+            Error { kind, msg, code }
         }
     }
     
@@ -116,12 +136,13 @@ impl Error {
     /// If not targetting `std` (i.e. `no_std`), this function is replaced by
     /// another with the same prototype, except that there are no bounds on the
     /// type `E` (because both `Box` and `stdError` are unavailable), and the
-    /// `cause` is ignored.
+    /// `cause` is ignored. Use `from_os_error` or `with_custom_code` to keep
+    /// the reason of the failure in `no_std` mode.
     #[cfg(feature="std")]
     pub fn with_cause<E>(kind: ErrorKind, msg: &'static str, cause: E) -> Self
         where E: Into<Box<stdError + Send + Sync>>
     {
-        Error { kind, msg, cause: Some(cause.into()) }
+        Error { kind, msg, code: 0, cause: Some(cause.into()) }
     }
     
     /// Create a new instance, with specified kind, message, and a
@@ -130,7 +151,69 @@ impl Error {
     /// In `no_std` mode the *cause* is ignored.
     #[cfg(not(feature="std"))]
     pub fn with_cause<E>(kind: ErrorKind, msg: &'static str, _cause: E) -> Self {
-        Error { kind, msg }
+        Error { kind, msg, code: 0 }
+    }
+
+    /// Create a new instance, with specified kind, message, and an OS error
+    /// code (`errno` on Unix-like platforms).
+    ///
+    /// A code which is not positive is ignored.
+    pub fn from_os_error(kind: ErrorKind, msg: &'static str, errno: i32)
+        -> Self
+    {
+        Error::with_code(kind, msg, if errno > 0 { errno as u32 } else { 0 })
+    }
+
+    /// Create a new instance, with specified kind, message, and a custom
+    /// error code. This is stored as `CUSTOM_START + code`.
+    ///
+    /// # Panics
+    ///
+    /// If `code` is not smaller than `CUSTOM_START`.
+    pub fn with_custom_code(kind: ErrorKind, msg: &'static str, code: u32)
+        -> Self
+    {
+        assert!(code < Error::CUSTOM_START, "custom error code too large");
+        Error::with_code(kind, msg, Error::CUSTOM_START + code)
+    }
+
+    /// The OS error code, if any.
+    ///
+    /// With `std` this also returns the code of a chained `std::io::Error`
+    /// cause.
+    pub fn raw_os_error(&self) -> Option<i32> {
+        if self.code != 0 && self.code < Error::CUSTOM_START {
+            return Some(self.code as i32);
+        }
+        #[cfg(feature="std")] {
+            if let Some(ref cause) = self.cause {
+                if let Some(err) = cause.downcast_ref::<io::Error>() {
+                    return err.raw_os_error();
+                }
+            }
+        }
+        None
+    }
+
+    /// The custom error code, if any, as passed to `with_custom_code`.
+    pub fn custom_code(&self) -> Option<u32> {
+        if self.code >= Error::CUSTOM_START {
+            Some(self.code - Error::CUSTOM_START)
+        } else {
+            None
+        }
+    }
+
+    /// The numeric error code, if any: an OS error code below
+    /// `CUSTOM_START`, or a custom code from `CUSTOM_START`.
+    ///
+    /// Note: this function is only available with Rust 1.28 or later.
+    #[cfg(rust_1_28)]
+    pub fn code(&self) -> Option<NonZeroU32> {
+        match self.raw_os_error() {
+            Some(errno) => NonZeroU32::new(errno as u32),
+            None => NonZeroU32::new(self.code),
+        }
     }
     
     /// Take the cause, if any. This allows the embedded cause to be extracted.
@@ -143,13 +226,19 @@ impl Error {
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}", self.msg, self.kind.description())?;
+        if let Some(errno) = self.raw_os_error() {
+            write!(f, ", OS error {}", errno)?;
+        } else if let Some(code) = self.custom_code() {
+            write!(f, ", error code {}", code)?;
+        }
+        write!(f, ")")?;
         #[cfg(feature="std")] {
             if let Some(ref cause) = self.cause {
-                return write!(f, "{} ({}); cause: {}",
-                        self.msg, self.kind.description(), cause);
+                write!(f, "; cause: {}", cause)?;
             }
         }
-        write!(f, "{} ({})", self.msg, self.kind.description())
+        Ok(())
     }
 }
 
@@ -164,10 +253,16 @@ impl stdError for Error {
     }
 }
 
+/// Errors with an OS error code are converted with
+/// `io::Error::from_raw_os_error`, preserving the code; other errors are
+/// wrapped.
 #[cfg(feature="std")]
 impl From<Error> for io::Error {
     fn from(error: Error) -> Self {
         use std::io::ErrorKind::*;
+        if let Some(errno) = error.raw_os_error() {
+            return io::Error::from_raw_os_error(errno);
+        }
         match error.kind {
             ErrorKind::Unavailable => io::Error::new(NotFound, error),
             ErrorKind::Unexpected |
@@ -177,3 +272,81 @@ impl From<Error> for io::Error {
         }
     }
 }
+
+/// The kind is `Transient` for `Interrupted`, `NotReady` for `WouldBlock`
+/// and `Unavailable` otherwise. The OS error code is preserved and the
+/// `io::Error` is kept as cause.
+#[cfg(feature="std")]
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        let kind = match error.kind() {
+            io::ErrorKind::Interrupted => ErrorKind::Transient,
+            io::ErrorKind::WouldBlock => ErrorKind::NotReady,
+            _ => ErrorKind::Unavailable,
+        };
+        let code = match error.raw_os_error() {
+            Some(errno) if errno > 0 => errno as u32,
+            _ => 0,
+        };
+        Error { kind, msg: "I/O error", code, cause: Some(Box::new(error)) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Error, ErrorKind};
+
+    #[test]
+    fn test_error_codes() {
+        let err = Error::new(ErrorKind::Unexpected, "failure");
+        assert_eq!(err.raw_os_error(), None);
+        assert_eq!(err.custom_code(), None);
+
+        let err = Error::from_os_error(ErrorKind::Unavailable, "failure", 2);
+        assert_eq!(err.raw_os_error(), Some(2));
+        assert_eq!(err.custom_code(), None);
+
+        let err = Error::with_custom_code(ErrorKind::NotReady, "failure", 7);
+        assert_eq!(err.raw_os_error(), None);
+        assert_eq!(err.custom_code(), Some(7));
+    }
+
+    #[cfg(rust_1_28)]
+    #[test]
+    fn test_error_code() {
+        let err = Error::from_os_error(ErrorKind::Unavailable, "failure", 2);
+        assert_eq!(err.code().map(|c| c.get()), Some(2));
+        let err = Error::with_custom_code(ErrorKind::Unavailable, "failure", 7);
+        assert_eq!(err.code().map(|c| c.get()), Some(Error::CUSTOM_START + 7));
+        assert!(Error::new(ErrorKind::Unavailable, "failure").code().is_none());
+    }
+
+    #[cfg(feature="std")]
+    #[test]
+    fn test_error_display() {
+        let err = Error::from_os_error(ErrorKind::Unavailable, "failure", 2);
+        assert_eq!(err.to_string(),
+                   "failure (permanently unavailable, OS error 2)");
+        let err = Error::with_custom_code(ErrorKind::NotReady, "failure", 7);
+        assert_eq!(err.to_string(), "failure (not ready yet, error code 7)");
+    }
+
+    #[cfg(feature="std")]
+    #[test]
+    fn test_io_error_conversion() {
+        use std::io;
+
+        let err = Error::from(io::Error::from_raw_os_error(11));
+        assert_eq!(err.raw_os_error(), Some(11));
+        let err = io::Error::from(err);
+        assert_eq!(err.raw_os_error(), Some(11));
+
+        let err = Error::with_cause(ErrorKind::Unavailable, "failure",
+                                    io::Error::from_raw_os_error(5));
+        assert_eq!(err.raw_os_error(), Some(5));
+
+        let err = io::Error::from(Error::new(ErrorKind::NotReady, "failure"));
+        assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+        assert_eq!(err.raw_os_error(), None);
+    }
+}
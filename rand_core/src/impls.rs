@@ -24,6 +24,8 @@ use core::ptr::copy_nonoverlapping;
 use core::slice;
 use core::cmp::min;
 use core::mem::size_of;
+#[cfg(feature="zeroize")] use core::ptr;
+#[cfg(feature="zeroize")] use core::sync::atomic;
 use RngCore;
 
 
@@ -164,4 +166,39 @@ pub fn next_u64_via_fill<R: RngCore + ?Sized>(rng: &mut R) -> u64 {
     impl_uint_from_fill!(rng, u64, 8)
 }
 
+/// Implement `fill_u32` via `fill_bytes`, little-endian order.
+pub fn fill_u32_via_fill<R: RngCore + ?Sized>(rng: &mut R, dest: &mut [u32]) {
+    unsafe {
+        let ptr = dest.as_mut_ptr() as *mut u8;
+        rng.fill_bytes(slice::from_raw_parts_mut(ptr, dest.len() * 4));
+    }
+    for x in dest.iter_mut() {
+        *x = u32::from_le(*x);
+    }
+}
+
+/// Implement `fill_u64` via `fill_bytes`, little-endian order.
+pub fn fill_u64_via_fill<R: RngCore + ?Sized>(rng: &mut R, dest: &mut [u64]) {
+    unsafe {
+        let ptr = dest.as_mut_ptr() as *mut u8;
+        rng.fill_bytes(slice::from_raw_parts_mut(ptr, dest.len() * 8));
+    }
+    for x in dest.iter_mut() {
+        *x = u64::from_le(*x);
+    }
+}
+
+/// Overwrite `dest` with default values (zero for integers), using volatile
+/// writes which the compiler may not optimise away.
+///
+/// This is used to wipe the state of cryptographic generators on drop when
+/// the `zeroize` feature is enabled.
+#[cfg(feature="zeroize")]
+pub fn zeroize<T: Copy + Default>(dest: &mut [T]) {
+    for x in dest.iter_mut() {
+        unsafe { ptr::write_volatile(x, T::default()); }
+    }
+    atomic::compiler_fence(atomic::Ordering::SeqCst);
+}
+
 // TODO: implement tests for the above
@@ -17,14 +17,18 @@
 //! [`RngCore`] is the core trait implemented by algorithmic pseudo-random number
 //! generators and external random-number sources.
 //! 
-//! [`SeedableRng`] is an extension trait for construction from fixed seeds and
-//! other random number generators.
+//! [`SeedableRng`] is an extension trait for construction from fixed seeds,
+//! `u64` values and other random number generators.
 //! 
 //! [`Error`] is provided for error-handling. It is safe to use in `no_std`
 //! environments.
 //! 
 //! The [`impls`] and [`le`] sub-modules include a few small functions to assist
-//! implementation of [`RngCore`].
+//! implementation of [`RngCore`]. The [`state`] sub-module defines a binary
+//! format for the state of generators.
+//!
+//! With the `futures` feature, [`AsyncRngCore`] allows waiting for sources of
+//! entropy which are not ready yet without blocking the thread.
 //! 
 //! [rand]: https://crates.io/crates/rand
 //! [`RngCore`]: trait.RngCore.html
@@ -32,6 +36,8 @@
 //! [`Error`]: struct.Error.html
 //! [`impls`]: impls/index.html
 //! [`le`]: le/index.html
+//! [`state`]: state/index.html
+//! [`AsyncRngCore`]: async_rng/trait.AsyncRngCore.html
 
 #![doc(html_logo_url = "https://www.rust-lang.org/logos/rust-logo-128x128-blk.png",
        html_favicon_url = "https://www.rust-lang.org/favicon.ico",
@@ -48,20 +54,28 @@
 #[cfg(all(feature = "alloc", not(feature="std")))] extern crate alloc;
 #[cfg(feature="serde1")] extern crate serde;
 #[cfg(feature="serde1")] #[macro_use] extern crate serde_derive;
+#[cfg(feature="futures")] #[macro_use] extern crate futures;
+
+// To test serialization we need bincode and the standard library
+#[cfg(all(feature="serde1", feature="std", test))] extern crate bincode;
 
 
 use core::default::Default;
 use core::convert::AsMut;
+use core::mem;
 
 #[cfg(all(feature="alloc", not(feature="std")))] use alloc::boxed::Box;
 
 pub use error::{ErrorKind, Error};
+#[cfg(feature="futures")] pub use async_rng::AsyncRngCore;
 
 
 mod error;
 pub mod block;
 pub mod impls;
 pub mod le;
+pub mod state;
+#[cfg(feature="futures")] pub mod async_rng;
 
 
 /// The core of a random number generator.
@@ -190,6 +204,29 @@ pub trait RngCore {
     /// 
     /// [`fill_bytes`]: trait.RngCore.html#method.fill_bytes
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error>;
+
+    /// Fill `dest` with random `u32` values.
+    ///
+    /// The result must be the same as filling the bytes of `dest` with
+    /// [`fill_bytes`] and reading them in little-endian order, which is what
+    /// the default implementation does. Block-based generators override this
+    /// to copy words from their result buffer directly.
+    ///
+    /// [`fill_bytes`]: trait.RngCore.html#tymethod.fill_bytes
+    fn fill_u32(&mut self, dest: &mut [u32]) {
+        impls::fill_u32_via_fill(self, dest)
+    }
+
+    /// Fill `dest` with random `u64` values.
+    ///
+    /// Like [`fill_u32`], the result must be the same as filling the bytes of
+    /// `dest` with [`fill_bytes`] and reading them in little-endian order.
+    ///
+    /// [`fill_u32`]: trait.RngCore.html#method.fill_u32
+    /// [`fill_bytes`]: trait.RngCore.html#tymethod.fill_bytes
+    fn fill_u64(&mut self, dest: &mut [u64]) {
+        impls::fill_u64_via_fill(self, dest)
+    }
 }
 
 /// A marker trait used to indicate that an [`RngCore`] or [`BlockRngCore`]
@@ -217,6 +254,39 @@ pub trait RngCore {
 /// [`BlockRngCore`]: ../rand_core/block/trait.BlockRngCore.html
 pub trait CryptoRng {}
 
+/// An object-safe combination of [`RngCore`] and [`CryptoRng`].
+///
+/// `CryptoRng` is a marker trait, so `RngCore + CryptoRng` cannot be used as
+/// a trait object. This trait is automatically implemented for every type
+/// implementing both, and can be used instead where a type-erased
+/// cryptographically secure generator is needed, e.g. `&mut CryptoRngCore`
+/// or `Box<CryptoRngCore>`. Since references and boxes of a `CryptoRngCore`
+/// implement `RngCore` and `CryptoRng` they also implement `CryptoRngCore`.
+///
+/// ```
+/// # #![allow(unknown_lints, bare_trait_objects, dead_code)]
+/// use rand_core::CryptoRngCore;
+///
+/// fn generate_key(rng: &mut CryptoRngCore) -> [u8; 32] {
+///     let mut key = [0u8; 32];
+///     rng.fill_bytes(&mut key);
+///     key
+/// }
+/// ```
+///
+/// [`RngCore`]: trait.RngCore.html
+/// [`CryptoRng`]: trait.CryptoRng.html
+pub trait CryptoRngCore: RngCore + CryptoRng {
+    /// Upcast to an `RngCore` trait object.
+    fn as_rngcore(&mut self) -> &mut RngCore;
+}
+
+impl<T: RngCore + CryptoRng> CryptoRngCore for T {
+    fn as_rngcore(&mut self) -> &mut RngCore {
+        self
+    }
+}
+
 /// A random number generator that can be explicitly seeded.
 ///
 /// This trait encapsulates the low-level functionality common to all
@@ -298,6 +368,37 @@ pub trait SeedableRng: Sized {
     /// seed"). This is assuming only a small number of values must be rejected.
     fn from_seed(seed: Self::Seed) -> Self;
 
+    /// Create a new PRNG using a `u64` seed.
+    ///
+    /// This is a convenience-wrapper around `from_seed` to allow construction
+    /// of any `SeedableRng` from a simple `u64` value, e.g. a seed given on
+    /// the command line. It is designed such that low Hamming Weight numbers
+    /// like 0 and 1 can be used and should still result in good, independent
+    /// seeds to the PRNG which is returned.
+    ///
+    /// The `u64` is expanded to a full `Seed` with the SplitMix64 generator
+    /// (also available as `rand_xoshiro::SplitMix64`): consecutive outputs
+    /// are written into the seed in little-endian byte order. The result is
+    /// thus portable and reproducible, as long as `from_seed` is.
+    ///
+    /// This is not suitable for cryptography, as should be clear given that
+    /// the input size is only 64 bits.
+    fn seed_from_u64(mut state: u64) -> Self {
+        // SplitMix64, see http://xoshiro.di.unimi.it/splitmix64.c
+        const PHI: u64 = 0x9e3779b97f4a7c15;
+        let mut seed = Self::Seed::default();
+        for chunk in seed.as_mut().chunks_mut(8) {
+            state = state.wrapping_add(PHI);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+            z = z ^ (z >> 31);
+            let bytes: [u8; 8] = unsafe { mem::transmute(z.to_le()) };
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+        Self::from_seed(seed)
+    }
+
     /// Create a new PRNG seeded from another `Rng`.
     ///
     /// This is the recommended way to initialize PRNGs with fresh entropy. The
@@ -337,6 +438,93 @@ pub trait SeedableRng: Sized {
     }
 }
 
+/// A random number generator that can derive new generators of the same type
+/// from itself.
+///
+/// This allows creating generators for a tree of tasks deterministically from
+/// a single root generator, where each task derives the generators of its
+/// child tasks from its own generator:
+///
+/// - [`split`] derives a child from the output of this generator, advancing
+///   it. Repeated calls give different children.
+/// - [`fork`] derives the child with number `index` from the current state of
+///   this generator, without modifying it. This allows creating the children
+///   in any order, or recreating a child.
+///
+/// Implementations must guarantee:
+///
+/// - Reproducibility: the child only depends on the state of the parent and,
+///   for `fork`, the index; independent of the platform or the Rust version.
+///   Implementations document whether `fork` also depends on the position of
+///   the parent in its output stream.
+/// - Independence: the output of a child is independent of the output of the
+///   parent and of other children with a different index or from a different
+///   `split`, to the degree the algorithm allows. For a [`CryptoRng`] the
+///   output of the parent and its other children should give no information
+///   on the output of a child. For other generators the output streams are
+///   only statistically independent, and may overlap with a small
+///   probability.
+///
+/// [`split`]: trait.SplittableRng.html#tymethod.split
+/// [`fork`]: trait.SplittableRng.html#tymethod.fork
+/// [`CryptoRng`]: trait.CryptoRng.html
+pub trait SplittableRng: RngCore + Sized {
+    /// Derive a new child generator, advancing this generator.
+    fn split(&mut self) -> Self;
+
+    /// Derive the child generator with number `index`, without modifying
+    /// this generator.
+    fn fork(&self, index: u64) -> Self;
+}
+
+/// A random number generator that supports random access to its output stream.
+///
+/// The position is the number of *steps* of the generator from the start of
+/// its output stream. What a step is depends on the generator, but it is
+/// usually the generation of one word of its native output size; e.g. one
+/// `u32` for `ChaChaRng` or the PCG generators with 32-bit output. Positions
+/// wrap around at the end of the period of the generator.
+///
+/// Where the stream starts also depends on the generator. For most generators
+/// it is the state after seeding, so a newly constructed generator is at
+/// position 0. Generators that can compute the position from their state,
+/// such as the PCG generators, may instead count from a fixed state of the
+/// stream, so that they need not store the position. Implementations document
+/// their step size and where their stream starts.
+///
+/// This allows checkpointing a generator with [`position`] and later
+/// restoring it with [`seek`] on a generator constructed with the same seed,
+/// and skipping over output with [`advance`], without generating the output
+/// in between.
+///
+/// Note: this trait is currently only available with Rust 1.26 or later.
+///
+/// [`position`]: trait.SeekableRng.html#tymethod.position
+/// [`seek`]: trait.SeekableRng.html#tymethod.seek
+/// [`advance`]: trait.SeekableRng.html#method.advance
+#[cfg(rust_1_26)]
+pub trait SeekableRng: RngCore {
+    /// Get the current position, in steps from the start of the stream.
+    fn position(&self) -> u128;
+
+    /// Set the current position, in steps from the start of the stream.
+    ///
+    /// After seeking the generator produces the same output as a generator
+    /// constructed with the same seed when it was at position `pos`.
+    fn seek(&mut self, pos: u128);
+
+    /// Advance the generator by `delta` steps.
+    ///
+    /// The default implementation uses [`position`] and [`seek`].
+    ///
+    /// [`position`]: trait.SeekableRng.html#tymethod.position
+    /// [`seek`]: trait.SeekableRng.html#tymethod.seek
+    fn advance(&mut self, delta: u128) {
+        let pos = self.position().wrapping_add(delta);
+        self.seek(pos);
+    }
+}
+
 // Implement `RngCore` for references to an `RngCore`.
 // Force inlining all functions, so that it is up to the `RngCore`
 // implementation and the optimizer to decide on inlining.
@@ -360,6 +548,16 @@ impl<'a, R: RngCore + ?Sized> RngCore for &'a mut R {
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         (**self).try_fill_bytes(dest)
     }
+
+    #[inline(always)]
+    fn fill_u32(&mut self, dest: &mut [u32]) {
+        (**self).fill_u32(dest)
+    }
+
+    #[inline(always)]
+    fn fill_u64(&mut self, dest: &mut [u64]) {
+        (**self).fill_u64(dest)
+    }
 }
 
 // Implement `RngCore` for boxed references to an `RngCore`.
@@ -386,6 +584,16 @@ impl<R: RngCore + ?Sized> RngCore for Box<R> {
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         (**self).try_fill_bytes(dest)
     }
+
+    #[inline(always)]
+    fn fill_u32(&mut self, dest: &mut [u32]) {
+        (**self).fill_u32(dest)
+    }
+
+    #[inline(always)]
+    fn fill_u64(&mut self, dest: &mut [u64]) {
+        (**self).fill_u64(dest)
+    }
 }
 
 #[cfg(feature="std")]
@@ -402,3 +610,79 @@ impl<'a, R: CryptoRng + ?Sized> CryptoRng for &'a mut R {}
 // Implement `CryptoRng` for boxed references to an `CryptoRng`.
 #[cfg(feature="alloc")]
 impl<R: CryptoRng + ?Sized> CryptoRng for Box<R> {}
+
+// Implement `SeekableRng` for references to a `SeekableRng`.
+#[cfg(rust_1_26)]
+impl<'a, R: SeekableRng + ?Sized> SeekableRng for &'a mut R {
+    fn position(&self) -> u128 {
+        (**self).position()
+    }
+
+    fn seek(&mut self, pos: u128) {
+        (**self).seek(pos)
+    }
+
+    fn advance(&mut self, delta: u128) {
+        (**self).advance(delta)
+    }
+}
+
+// Implement `SeekableRng` for boxed references to a `SeekableRng`.
+#[cfg(all(feature="alloc", rust_1_26))]
+impl<R: SeekableRng + ?Sized> SeekableRng for Box<R> {
+    fn position(&self) -> u128 {
+        (**self).position()
+    }
+
+    fn seek(&mut self, pos: u128) {
+        (**self).seek(pos)
+    }
+
+    fn advance(&mut self, delta: u128) {
+        (**self).advance(delta)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_seed_from_u64() {
+        struct SeedableNum(u64);
+        impl SeedableRng for SeedableNum {
+            type Seed = [u8; 8];
+            fn from_seed(seed: Self::Seed) -> Self {
+                let mut x = [0u64; 1];
+                le::read_u64_into(&seed, &mut x);
+                SeedableNum(x[0])
+            }
+        }
+
+        // Reference values of SplitMix64 seeded with 0 and 1
+        assert_eq!(SeedableNum::seed_from_u64(0).0, 0xe220a8397b1dcdaf);
+        assert_eq!(SeedableNum::seed_from_u64(1).0, 0x910a2dec89025cc1);
+
+        const N: usize = 8;
+        const SEEDS: [u64; N] = [0u64, 1, 2, 3, 4, 8, 16, !0];
+        let mut results = [0u64; N];
+        for (i, seed) in SEEDS.iter().enumerate() {
+            let SeedableNum(x) = SeedableNum::seed_from_u64(*seed);
+            results[i] = x;
+        }
+
+        for (i1, r1) in results.iter().enumerate() {
+            let weight = r1.count_ones();
+            // This is the binomial distribution B(64, 0.5), so chance of
+            // weight < 20 is binocdf(19, 64, 0.5) = 7.8e-4, and same for
+            // weight > 44.
+            assert!(weight >= 20 && weight <= 44);
+
+            for (i2, r2) in results.iter().enumerate() {
+                if i1 == i2 { continue; }
+                let diff_weight = (r1 ^ r2).count_ones();
+                assert!(diff_weight >= 20);
+            }
+        }
+    }
+}
@@ -0,0 +1,291 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A versioned binary format for the state of a generator.
+//!
+//! Unlike serialization with Serde, which depends on the chosen data format,
+//! the [`StateBytes`] format is fixed, so it can be read by implementations in
+//! other languages. A state consists of a header of [`HEADER_LEN`] bytes,
+//! followed by the state of the generator:
+//!
+//! | Offset | Length | Content                                              |
+//! |--------|--------|------------------------------------------------------|
+//! | 0      | 8      | Algorithm identifier, ASCII padded with zero bytes   |
+//! | 8      | 4      | Format version, `u32`                                |
+//! | 12     | varies | Generator state, documented by each implementation   |
+//!
+//! All integers are stored in little-endian byte order.
+//!
+//! Generators implement [`StateBytes::state_len`], [`write_state`] and
+//! [`read_state`] for the part after the header, usually with the help of
+//! [`StateWriter`] and [`StateReader`]. Block-based generators can store the
+//! position in their result buffer with [`BlockRng::write_buffer_state`].
+//!
+//! ```
+//! use rand_core::{Error, ErrorKind};
+//! use rand_core::state::{StateBytes, StateReader, StateWriter};
+//!
+//! struct CountingRng(u64);
+//!
+//! impl StateBytes for CountingRng {
+//!     const ALGORITHM_ID: [u8; 8] = *b"Counting";
+//!     const FORMAT_VERSION: u32 = 1;
+//!
+//!     fn state_len() -> usize { 8 }
+//!
+//!     fn write_state(&self, state: &mut [u8]) {
+//!         StateWriter::new(state).write_u64(self.0);
+//!     }
+//!
+//!     fn read_state(state: &[u8]) -> Result<Self, Error> {
+//!         Ok(CountingRng(StateReader::new(state).read_u64()))
+//!     }
+//! }
+//!
+//! let mut buf = [0u8; 20];
+//! CountingRng(42).write_state_bytes(&mut buf);
+//! assert_eq!(CountingRng::from_state_bytes(&buf).unwrap().0, 42);
+//!
+//! buf[8] = 2; // unknown format version
+//! let err = CountingRng::from_state_bytes(&buf).err().unwrap();
+//! assert_eq!(err.kind, ErrorKind::Unavailable);
+//! ```
+//!
+//! [`StateBytes`]: trait.StateBytes.html
+//! [`StateBytes::state_len`]: trait.StateBytes.html#tymethod.state_len
+//! [`write_state`]: trait.StateBytes.html#tymethod.write_state
+//! [`read_state`]: trait.StateBytes.html#tymethod.read_state
+//! [`HEADER_LEN`]: constant.HEADER_LEN.html
+//! [`StateWriter`]: struct.StateWriter.html
+//! [`StateReader`]: struct.StateReader.html
+//! [`BlockRng::write_buffer_state`]: ../block/struct.BlockRng.html#method.write_buffer_state
+
+use {Error, ErrorKind};
+#[cfg(all(feature="alloc", not(feature="std")))] use alloc::vec::Vec;
+
+/// The length of the header with the algorithm identifier and format
+/// version.
+pub const HEADER_LEN: usize = 12;
+
+/// Conversion of the state of a generator to and from a fixed binary format.
+///
+/// See the [module documentation](index.html) for the format.
+pub trait StateBytes: Sized {
+    /// The algorithm identifier: up to 8 ASCII characters, padded with zero
+    /// bytes.
+    const ALGORITHM_ID: [u8; 8];
+
+    /// The version of the state format. This changes when the layout of the
+    /// state changes.
+    const FORMAT_VERSION: u32;
+
+    /// The length of the state after the header, in bytes.
+    fn state_len() -> usize;
+
+    /// Write the state to `state`, which is `state_len()` bytes long.
+    fn write_state(&self, state: &mut [u8]);
+
+    /// Restore a generator from `state`, which is `state_len()` bytes long.
+    ///
+    /// Returns an error of kind `ErrorKind::Unavailable` if the state is not
+    /// valid for this generator.
+    fn read_state(state: &[u8]) -> Result<Self, Error>;
+
+    /// The length of the state including the header, in bytes.
+    fn state_bytes_len() -> usize {
+        HEADER_LEN + Self::state_len()
+    }
+
+    /// Write the header and state to `dest`.
+    ///
+    /// # Panics
+    ///
+    /// If the length of `dest` is not `state_bytes_len()`.
+    fn write_state_bytes(&self, dest: &mut [u8]) {
+        assert_eq!(dest.len(), Self::state_bytes_len());
+        let (header, state) = dest.split_at_mut(HEADER_LEN);
+        let mut w = StateWriter::new(header);
+        w.write_bytes(&Self::ALGORITHM_ID);
+        w.write_u32(Self::FORMAT_VERSION);
+        self.write_state(state);
+    }
+
+    /// Return the header and state as a new `Vec`.
+    #[cfg(feature="alloc")]
+    fn to_state_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.resize(Self::state_bytes_len(), 0);
+        self.write_state_bytes(&mut bytes);
+        bytes
+    }
+
+    /// Restore a generator from a header and state.
+    ///
+    /// Returns an error of kind `ErrorKind::Unavailable` if the algorithm
+    /// identifier, the format version or the length does not match, or the
+    /// state is not valid.
+    fn from_state_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < HEADER_LEN || &bytes[..8] != &Self::ALGORITHM_ID[..] {
+            return Err(Error::new(ErrorKind::Unavailable,
+                                  "state bytes: algorithm mismatch"));
+        }
+        let (header, state) = bytes.split_at(HEADER_LEN);
+        if StateReader::new(&header[8..]).read_u32() != Self::FORMAT_VERSION {
+            return Err(Error::new(ErrorKind::Unavailable,
+                                  "state bytes: unsupported format version"));
+        }
+        if state.len() != Self::state_len() {
+            return Err(Error::new(ErrorKind::Unavailable,
+                                  "state bytes: wrong length"));
+        }
+        Self::read_state(state)
+    }
+}
+
+/// Writes little-endian integers to a byte slice, for implementing
+/// [`StateBytes::write_state`].
+///
+/// All methods panic if the slice is too short.
+///
+/// [`StateBytes::write_state`]: trait.StateBytes.html#tymethod.write_state
+#[derive(Debug)]
+pub struct StateWriter<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+}
+
+impl<'a> StateWriter<'a> {
+    /// Create a writer starting at the beginning of `buf`.
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        StateWriter { buf, pos: 0 }
+    }
+
+    /// Write bytes.
+    pub fn write_bytes(&mut self, src: &[u8]) {
+        self.buf[self.pos..self.pos + src.len()].copy_from_slice(src);
+        self.pos += src.len();
+    }
+
+    /// Write a `u32`.
+    pub fn write_u32(&mut self, x: u32) {
+        let mut bytes = [0u8; 4];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = (x >> (8 * i)) as u8;
+        }
+        self.write_bytes(&bytes);
+    }
+
+    /// Write a `u64`.
+    pub fn write_u64(&mut self, x: u64) {
+        self.write_u32(x as u32);
+        self.write_u32((x >> 32) as u32);
+    }
+
+    /// Write a slice of `u32`.
+    pub fn write_u32s(&mut self, src: &[u32]) {
+        for &x in src {
+            self.write_u32(x);
+        }
+    }
+
+    /// Write a slice of `u64`.
+    pub fn write_u64s(&mut self, src: &[u64]) {
+        for &x in src {
+            self.write_u64(x);
+        }
+    }
+}
+
+/// Reads little-endian integers from a byte slice, for implementing
+/// [`StateBytes::read_state`].
+///
+/// All methods panic if the slice is too short.
+///
+/// [`StateBytes::read_state`]: trait.StateBytes.html#tymethod.read_state
+#[derive(Debug)]
+pub struct StateReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> StateReader<'a> {
+    /// Create a reader starting at the beginning of `buf`.
+    pub fn new(buf: &'a [u8]) -> Self {
+        StateReader { buf, pos: 0 }
+    }
+
+    /// Read bytes into `dest`.
+    pub fn read_bytes(&mut self, dest: &mut [u8]) {
+        dest.copy_from_slice(&self.buf[self.pos..self.pos + dest.len()]);
+        self.pos += dest.len();
+    }
+
+    /// Read a `u32`.
+    pub fn read_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.read_bytes(&mut bytes);
+        bytes.iter().rev().fold(0, |x, &b| (x << 8) | u32::from(b))
+    }
+
+    /// Read a `u64`.
+    pub fn read_u64(&mut self) -> u64 {
+        let lo = u64::from(self.read_u32());
+        let hi = u64::from(self.read_u32());
+        (hi << 32) | lo
+    }
+
+    /// Read a slice of `u32`.
+    pub fn read_u32s(&mut self, dest: &mut [u32]) {
+        for x in dest.iter_mut() {
+            *x = self.read_u32();
+        }
+    }
+
+    /// Read a slice of `u64`.
+    pub fn read_u64s(&mut self, dest: &mut [u64]) {
+        for x in dest.iter_mut() {
+            *x = self.read_u64();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{StateReader, StateWriter};
+
+    #[test]
+    fn test_state_writer_reader() {
+        let mut buf = [0u8; 28];
+        {
+            let mut w = StateWriter::new(&mut buf);
+            w.write_u32(0x04030201);
+            w.write_u64(0x0c0b0a0908070605);
+            w.write_u32s(&[0x100f0e0d]);
+            w.write_u64s(&[0x1817161514131211]);
+            w.write_bytes(&[0x19, 0x1a, 0x1b, 0x1c]);
+        }
+        for (i, &b) in buf.iter().enumerate() {
+            assert_eq!(b, i as u8 + 1);
+        }
+
+        let mut r = StateReader::new(&buf);
+        assert_eq!(r.read_u32(), 0x04030201);
+        assert_eq!(r.read_u64(), 0x0c0b0a0908070605);
+        let mut x = [0u32; 1];
+        r.read_u32s(&mut x);
+        assert_eq!(x, [0x100f0e0d]);
+        let mut y = [0u64; 1];
+        r.read_u64s(&mut y);
+        assert_eq!(y, [0x1817161514131211]);
+        let mut z = [0u8; 4];
+        r.read_bytes(&mut z);
+        assert_eq!(z, [0x19, 0x1a, 0x1b, 0x1c]);
+    }
+}
@@ -12,8 +12,11 @@
 
 use core::{fmt, slice};
 use core::num::Wrapping as w;
-use rand_core::{RngCore, SeedableRng, Error, le};
+use rand_core::{RngCore, SeedableRng, SplittableRng, Error, le};
 use rand_core::block::{BlockRngCore, BlockRng};
+use rand_core::state::{StateBytes, StateReader, StateWriter};
+#[cfg(feature="zeroize")] use core::ptr;
+#[cfg(feature="zeroize")] use rand_core::impls;
 use isaac_array::IsaacArray;
 
 #[allow(non_camel_case_types)]
@@ -112,6 +115,10 @@ impl RngCore for IsaacRng {
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         self.0.try_fill_bytes(dest)
     }
+
+    fn fill_u32(&mut self, dest: &mut [u32]) {
+        self.0.fill_u32(dest)
+    }
 }
 
 impl SeedableRng for IsaacRng {
@@ -126,10 +133,79 @@ impl SeedableRng for IsaacRng {
     }
 }
 
+/// The state is the memory as 256 `u32`, the variables `a`, `b` and `c` as
+/// `u32`, the index into the result buffer as `u32`, and the
+/// result buffer as 256 `u32`: 2064 bytes.
+impl StateBytes for IsaacRng {
+    const ALGORITHM_ID: [u8; 8] = *b"ISAAC\0\0\0";
+    const FORMAT_VERSION: u32 = 1;
+
+    fn state_len() -> usize {
+        4 * (RAND_SIZE + 3) + BlockRng::<IsaacCore>::buffer_state_len()
+    }
+
+    fn write_state(&self, state: &mut [u8]) {
+        let core = &self.0.core;
+        let mut writer = StateWriter::new(state);
+        for x in core.mem.iter() {
+            writer.write_u32(x.0);
+        }
+        writer.write_u32(core.a.0);
+        writer.write_u32(core.b.0);
+        writer.write_u32(core.c.0);
+        self.0.write_buffer_state(&mut writer);
+    }
+
+    fn read_state(state: &[u8]) -> Result<Self, Error> {
+        let mut r = StateReader::new(state);
+        let mut core = IsaacCore {
+            mem: [w(0); RAND_SIZE],
+            a: w(0),
+            b: w(0),
+            c: w(0),
+        };
+        for x in core.mem.iter_mut() {
+            *x = w(r.read_u32());
+        }
+        core.a = w(r.read_u32());
+        core.b = w(r.read_u32());
+        core.c = w(r.read_u32());
+        let mut rng = BlockRng::new(core);
+        rng.read_buffer_state(&mut r)?;
+        Ok(IsaacRng(rng))
+    }
+}
+
+/// `split` seeds the child with the next 1024 bytes of output, like
+/// `from_rng`.
+///
+/// `fork` initializes the child with the internal state of this generator,
+/// combined with `index`, as seed. The child depends on the current state of
+/// this generator, which changes as it generates output.
+impl SplittableRng for IsaacRng {
+    fn split(&mut self) -> Self {
+        IsaacRng::from_rng(self).unwrap()
+    }
+
+    fn fork(&self, index: u64) -> Self {
+        let core = &self.0.core;
+        let mut mem = core.mem;
+        mem[0] ^= w(index as u32);
+        mem[1] ^= w((index >> 32) as u32);
+        mem[2] += core.a;
+        mem[3] += core.b;
+        mem[4] += core.c;
+        IsaacRng(BlockRng::new(IsaacCore::init(mem, 2)))
+    }
+}
+
 impl IsaacRng {
     /// Create an ISAAC random number generator using an `u64` as seed.
     /// If `seed == 0` this will produce the same stream of random numbers as
     /// the reference implementation when used unseeded.
+    ///
+    /// Unlike `SeedableRng::seed_from_u64`, this uses the `u64` directly as
+    /// the key instead of expanding it into a full seed.
     pub fn new_from_u64(seed: u64) -> Self {
         IsaacRng(BlockRng::new(IsaacCore::new_from_u64(seed)))
     }
@@ -153,6 +229,18 @@ impl fmt::Debug for IsaacCore {
     }
 }
 
+#[cfg(feature="zeroize")]
+impl Drop for IsaacCore {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::write_volatile(&mut self.a, w(0u32));
+            ptr::write_volatile(&mut self.b, w(0u32));
+            ptr::write_volatile(&mut self.c, w(0u32));
+        }
+        impls::zeroize(&mut self.mem);
+    }
+}
+
 impl BlockRngCore for IsaacCore {
     type Item = u32;
     type Results = IsaacArray<Self::Item>;
@@ -452,6 +540,27 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_isaac_split_fork() {
+        use rand_core::SplittableRng;
+
+        let seed = [1,0,0,0, 23,0,0,0, 200,1,0,0, 210,30,0,0,
+                     57,48,0,0, 0,0,0,0, 0,0,0,0, 0,0,0,0];
+        let mut rng = IsaacRng::from_seed(seed);
+        let mut fork1 = rng.fork(1);
+        let mut fork1_again = IsaacRng::from_seed(seed).fork(1);
+        let mut fork2 = rng.fork(2);
+        for _ in 0..16 {
+            assert_eq!(fork1.next_u32(), fork1_again.next_u32());
+        }
+        assert!(fork1.next_u32() != fork2.next_u32());
+
+        let mut child1 = rng.split();
+        let mut child2 = rng.split();
+        assert!(child1.next_u32() != child2.next_u32());
+        assert!(rng.next_u32() != child1.next_u32());
+    }
+
     #[test]
     #[cfg(feature="serde1")]
     fn test_isaac_serde() {
@@ -474,4 +583,20 @@ mod test {
             assert_eq!(rng.next_u32(), deserialized.next_u32());
         }
     }
+
+    #[test]
+    fn test_isaac_state_bytes() {
+        use rand_core::state::StateBytes;
+
+        let mut rng = IsaacRng::new_from_u64(0x55);
+        rng.next_u32();
+
+        let mut bytes = [0u8; 12 + 2064];
+        assert_eq!(IsaacRng::state_bytes_len(), bytes.len());
+        rng.write_state_bytes(&mut bytes);
+        let mut restored = IsaacRng::from_state_bytes(&bytes).unwrap();
+        for _ in 0..1024 {
+            assert_eq!(rng.next_u32(), restored.next_u32());
+        }
+    }
 }
@@ -12,8 +12,11 @@
 
 use core::{fmt, slice};
 use core::num::Wrapping as w;
-use rand_core::{RngCore, SeedableRng, Error, le};
+use rand_core::{RngCore, SeedableRng, SplittableRng, Error, le};
 use rand_core::block::{BlockRngCore, BlockRng64};
+use rand_core::state::{StateBytes, StateReader, StateWriter};
+#[cfg(feature="zeroize")] use core::ptr;
+#[cfg(feature="zeroize")] use rand_core::impls;
 use isaac_array::IsaacArray;
 
 #[allow(non_camel_case_types)]
@@ -102,6 +105,10 @@ impl RngCore for Isaac64Rng {
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         self.0.try_fill_bytes(dest)
     }
+
+    fn fill_u64(&mut self, dest: &mut [u64]) {
+        self.0.fill_u64(dest)
+    }
 }
 
 impl SeedableRng for Isaac64Rng {
@@ -116,10 +123,78 @@ impl SeedableRng for Isaac64Rng {
     }
 }
 
+/// The state is the memory as 256 `u64`, the variables `a`, `b` and `c` as
+/// `u64`, the index into the result buffer and the `half_used` flag as `u32`, and the
+/// result buffer as 256 `u64`: 4128 bytes.
+impl StateBytes for Isaac64Rng {
+    const ALGORITHM_ID: [u8; 8] = *b"ISAAC-64";
+    const FORMAT_VERSION: u32 = 1;
+
+    fn state_len() -> usize {
+        8 * (RAND_SIZE + 3) + BlockRng64::<Isaac64Core>::buffer_state_len()
+    }
+
+    fn write_state(&self, state: &mut [u8]) {
+        let core = &self.0.core;
+        let mut writer = StateWriter::new(state);
+        for x in core.mem.iter() {
+            writer.write_u64(x.0);
+        }
+        writer.write_u64(core.a.0);
+        writer.write_u64(core.b.0);
+        writer.write_u64(core.c.0);
+        self.0.write_buffer_state(&mut writer);
+    }
+
+    fn read_state(state: &[u8]) -> Result<Self, Error> {
+        let mut r = StateReader::new(state);
+        let mut core = Isaac64Core {
+            mem: [w(0); RAND_SIZE],
+            a: w(0),
+            b: w(0),
+            c: w(0),
+        };
+        for x in core.mem.iter_mut() {
+            *x = w(r.read_u64());
+        }
+        core.a = w(r.read_u64());
+        core.b = w(r.read_u64());
+        core.c = w(r.read_u64());
+        let mut rng = BlockRng64::new(core);
+        rng.read_buffer_state(&mut r)?;
+        Ok(Isaac64Rng(rng))
+    }
+}
+
+/// `split` seeds the child with the next 2048 bytes of output, like
+/// `from_rng`.
+///
+/// `fork` initializes the child with the internal state of this generator,
+/// combined with `index`, as seed. The child depends on the current state of
+/// this generator, which changes as it generates output.
+impl SplittableRng for Isaac64Rng {
+    fn split(&mut self) -> Self {
+        Isaac64Rng::from_rng(self).unwrap()
+    }
+
+    fn fork(&self, index: u64) -> Self {
+        let core = &self.0.core;
+        let mut mem = core.mem;
+        mem[0] ^= w(index);
+        mem[1] += core.a;
+        mem[2] += core.b;
+        mem[3] += core.c;
+        Isaac64Rng(BlockRng64::new(Isaac64Core::init(mem, 2)))
+    }
+}
+
 impl Isaac64Rng {
     /// Create an ISAAC-64 random number generator using an `u64` as seed.
     /// If `seed == 0` this will produce the same stream of random numbers as
     /// the reference implementation when used unseeded.
+    ///
+    /// Unlike `SeedableRng::seed_from_u64`, this uses the `u64` directly as
+    /// the key instead of expanding it into a full seed.
     pub fn new_from_u64(seed: u64) -> Self {
         Isaac64Rng(BlockRng64::new(Isaac64Core::new_from_u64(seed)))
     }
@@ -143,6 +218,18 @@ impl fmt::Debug for Isaac64Core {
     }
 }
 
+#[cfg(feature="zeroize")]
+impl Drop for Isaac64Core {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::write_volatile(&mut self.a, w(0u64));
+            ptr::write_volatile(&mut self.b, w(0u64));
+            ptr::write_volatile(&mut self.c, w(0u64));
+        }
+        impls::zeroize(&mut self.mem);
+    }
+}
+
 impl BlockRngCore for Isaac64Core {
     type Item = u64;
     type Results = IsaacArray<Self::Item>;
@@ -444,6 +531,27 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_isaac64_split_fork() {
+        use rand_core::SplittableRng;
+
+        let seed = [1,0,0,0, 0,0,0,0, 23,0,0,0, 0,0,0,0,
+                    200,1,0,0, 0,0,0,0, 210,30,0,0, 0,0,0,0];
+        let mut rng = Isaac64Rng::from_seed(seed);
+        let mut fork1 = rng.fork(1);
+        let mut fork1_again = Isaac64Rng::from_seed(seed).fork(1);
+        let mut fork2 = rng.fork(2);
+        for _ in 0..16 {
+            assert_eq!(fork1.next_u64(), fork1_again.next_u64());
+        }
+        assert!(fork1.next_u64() != fork2.next_u64());
+
+        let mut child1 = rng.split();
+        let mut child2 = rng.split();
+        assert!(child1.next_u64() != child2.next_u64());
+        assert!(rng.next_u64() != child1.next_u64());
+    }
+
     #[test]
     #[cfg(feature="serde1")]
     fn test_isaac64_serde() {
@@ -466,4 +574,20 @@ mod test {
             assert_eq!(rng.next_u64(), deserialized.next_u64());
         }
     }
+
+    #[test]
+    fn test_isaac64_state_bytes() {
+        use rand_core::state::StateBytes;
+
+        let mut rng = Isaac64Rng::new_from_u64(0x55);
+        rng.next_u64();
+
+        let mut bytes = [0u8; 12 + 4128];
+        assert_eq!(Isaac64Rng::state_bytes_len(), bytes.len());
+        rng.write_state_bytes(&mut bytes);
+        let mut restored = Isaac64Rng::from_state_bytes(&bytes).unwrap();
+        for _ in 0..1024 {
+            assert_eq!(rng.next_u64(), restored.next_u64());
+        }
+    }
 }
@@ -0,0 +1,68 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Serde helpers for the state arrays.
+
+// Arrays with more than 32 elements do not implement `Serialize` or
+// `Deserialize`, hence this macro generates a module for use with
+// `#[serde(with = "...")]` for an array of a fixed type and length.
+macro_rules! array_serde {
+    ($name:ident, $ty:ty, $len:expr) => {
+        pub(crate) mod $name {
+            use serde::{Deserializer, Serializer};
+            use serde::de::{self, SeqAccess, Visitor};
+            use serde::ser::SerializeTuple;
+            use core::fmt;
+
+            pub fn serialize<S>(arr: &[$ty; $len], ser: S)
+                -> Result<S::Ok, S::Error>
+            where S: Serializer
+            {
+                let mut seq = ser.serialize_tuple($len)?;
+                for e in arr.iter() {
+                    seq.serialize_element(e)?;
+                }
+                seq.end()
+            }
+
+            pub fn deserialize<'de, D>(de: D) -> Result<[$ty; $len], D::Error>
+            where D: Deserializer<'de>
+            {
+                struct ArrayVisitor;
+
+                impl<'de> Visitor<'de> for ArrayVisitor {
+                    type Value = [$ty; $len];
+
+                    fn expecting(&self, formatter: &mut fmt::Formatter)
+                        -> fmt::Result
+                    {
+                        formatter.write_str("Mersenne Twister state array")
+                    }
+
+                    fn visit_seq<A>(self, mut seq: A)
+                        -> Result<[$ty; $len], A::Error>
+                    where A: SeqAccess<'de>
+                    {
+                        let mut out = [0; $len];
+                        for i in 0..$len {
+                            match seq.next_element()? {
+                                Some(val) => out[i] = val,
+                                None => return Err(de::Error::invalid_length(i, &self)),
+                            };
+                        }
+                        Ok(out)
+                    }
+                }
+
+                de.deserialize_tuple($len, ArrayVisitor)
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The Mersenne Twister random number generators.
+//!
+//! This crate implements MT19937 and MT19937-64, designed by Makoto Matsumoto
+//! and Takuji Nishimura[^1]. Neither is suitable for cryptographic purposes.
+//!
+//! The Mersenne Twister is one of the most widely used PRNGs, but uses a
+//! large state and is slow compared to modern alternatives. Use it when output
+//! must match other software exactly; otherwise prefer one of the generators
+//! in the `rand_pcg` or `rand_xoshiro` crates.
+//!
+//! Both generators support the seeding procedures of the reference
+//! implementation, `init_genrand` ([`Mt19937::new`]) and `init_by_array`
+//! ([`Mt19937::new_by_array`]), so that sequences match:
+//!
+//! - C++'s `std::mt19937` and `std::mt19937_64`, which use `init_genrand`,
+//! - NumPy's legacy `RandomState`, which uses `init_genrand` for integer
+//!   seeds and `init_by_array` for array seeds,
+//! - Python's `random` module, which uses `init_by_array` with the seed split
+//!   into 32-bit words, least significant word first.
+//!
+//! ```
+//! # extern crate rand_core;
+//! # extern crate rand_mt;
+//! # fn main() {
+//! use rand_core::RngCore;
+//! use rand_mt::Mt19937;
+//!
+//! // Same as `std::mt19937 rng(5489);` in C++
+//! let mut rng = Mt19937::new(5489);
+//! assert_eq!(rng.next_u32(), 3499211612);
+//! # }
+//! ```
+//!
+//! [^1]: M. Matsumoto and T. Nishimura, [*Mersenne Twister: a
+//!       623-dimensionally equidistributed uniform pseudo-random number
+//!       generator*](http://www.math.sci.hiroshima-u.ac.jp/~m-mat/MT/ARTICLES/mt.pdf),
+//!       ACM Transactions on Modeling and Computer Simulation, 1998.
+//!
+//! [`Mt19937::new`]: struct.Mt19937.html#method.new
+//! [`Mt19937::new_by_array`]: struct.Mt19937.html#method.new_by_array
+
+#![doc(html_logo_url = "https://www.rust-lang.org/logos/rust-logo-128x128-blk.png",
+       html_favicon_url = "https://www.rust-lang.org/favicon.ico",
+       html_root_url = "https://docs.rs/rand_mt/0.1.0")]
+
+#![deny(missing_docs)]
+#![deny(missing_debug_implementations)]
+#![doc(test(attr(allow(unused_variables), deny(warnings))))]
+
+#![cfg_attr(not(all(feature="serde1", test)), no_std)]
+
+extern crate rand_core;
+
+#[cfg(feature="serde1")] extern crate serde;
+#[cfg(feature="serde1")] #[macro_use] extern crate serde_derive;
+
+// To test serialization we need bincode and the standard library
+#[cfg(all(feature="serde1", test))] extern crate bincode;
+#[cfg(all(feature="serde1", test))] extern crate std as core;
+
+#[cfg(feature="serde1")]
+#[macro_use]
+mod array_serde;
+mod mt19937;
+mod mt19937_64;
+
+pub use self::mt19937::Mt19937;
+pub use self::mt19937_64::Mt19937_64;
@@ -0,0 +1,244 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The MT19937 random number generator
+
+use core::fmt;
+use rand_core::{RngCore, SeedableRng, Error, le, impls};
+
+const N: usize = 624;
+const M: usize = 397;
+const MATRIX_A: u32 = 0x9908b0df;
+const UPPER_MASK: u32 = 0x80000000;
+const LOWER_MASK: u32 = 0x7fffffff;
+
+#[cfg(feature="serde1")]
+array_serde!(state_serde, u32, 624);
+
+/// The MT19937 random number generator, the 32-bit Mersenne Twister.
+///
+/// MT19937 is not suitable for cryptographic purposes: its state can be
+/// reconstructed from 624 consecutive outputs. It has a period of
+/// 2<sup>19937</sup> - 1 and is 623-dimensionally equidistributed, but its
+/// 2.5 KiB state is large and it fails some statistical tests (linear
+/// complexity) in TestU01.
+///
+/// The algorithm used here is translated from [the `mt19937ar.c` reference
+/// source code](http://www.math.sci.hiroshima-u.ac.jp/~m-mat/MT/MT2002/emt19937ar.html)
+/// by Makoto Matsumoto and Takuji Nishimura, and the output of
+/// [`next_u32`] is identical to its `genrand_int32`. [`next_u64`] combines
+/// two consecutive outputs, the first as the low half.
+///
+/// [`new`] and [`new_by_array`] use the seeding procedures of the reference
+/// implementation. `SeedableRng::from_seed` uses `init_by_array` with the
+/// 32-byte seed read as eight little-endian `u32` words.
+///
+/// [`next_u32`]: #method.next_u32
+/// [`next_u64`]: #method.next_u64
+/// [`new`]: #method.new
+/// [`new_by_array`]: #method.new_by_array
+#[derive(Clone)]
+#[cfg_attr(feature="serde1", derive(Serialize,Deserialize))]
+pub struct Mt19937 {
+    #[cfg_attr(feature="serde1", serde(with="state_serde"))]
+    mt: [u32; N],
+    index: usize,
+}
+
+impl Mt19937 {
+    /// Create a new `Mt19937`, seeded with `init_genrand(seed)`.
+    ///
+    /// This is the seeding procedure of C++'s `std::mt19937` and of NumPy's
+    /// `RandomState` for integer seeds. The default seed of the reference
+    /// implementation and of `std::mt19937` is 5489.
+    pub fn new(seed: u32) -> Self {
+        let mut mt = [0u32; N];
+        mt[0] = seed;
+        for i in 1..N {
+            mt[i] = 1812433253u32
+                .wrapping_mul(mt[i-1] ^ (mt[i-1] >> 30))
+                .wrapping_add(i as u32);
+        }
+        Mt19937 { mt, index: N }
+    }
+
+    /// Create a new `Mt19937`, seeded with `init_by_array(key)`.
+    ///
+    /// This is the seeding procedure used by NumPy's `RandomState` for array
+    /// seeds and by Python's `random` module.
+    ///
+    /// # Panics
+    ///
+    /// If `key` is empty.
+    pub fn new_by_array(key: &[u32]) -> Self {
+        assert!(!key.is_empty(), "Mt19937::new_by_array: empty key");
+        let mut rng = Mt19937::new(19650218);
+        {
+            let mt = &mut rng.mt;
+            let mut i = 1;
+            let mut j = 0;
+            for _ in 0..N.max(key.len()) {
+                mt[i] = (mt[i] ^ (mt[i-1] ^ (mt[i-1] >> 30)).wrapping_mul(1664525))
+                    .wrapping_add(key[j])
+                    .wrapping_add(j as u32);
+                i += 1;
+                j += 1;
+                if i >= N { mt[0] = mt[N-1]; i = 1; }
+                if j >= key.len() { j = 0; }
+            }
+            for _ in 0..N-1 {
+                mt[i] = (mt[i] ^ (mt[i-1] ^ (mt[i-1] >> 30)).wrapping_mul(1566083941))
+                    .wrapping_sub(i as u32);
+                i += 1;
+                if i >= N { mt[0] = mt[N-1]; i = 1; }
+            }
+            // MSB is 1, assuring a non-zero initial state
+            mt[0] = 0x80000000;
+        }
+        rng
+    }
+
+    // Generate the next N words of state.
+    fn twist(&mut self) {
+        let mt = &mut self.mt;
+        for i in 0..N {
+            let y = (mt[i] & UPPER_MASK) | (mt[(i + 1) % N] & LOWER_MASK);
+            let mag = if y & 1 == 0 { 0 } else { MATRIX_A };
+            mt[i] = mt[(i + M) % N] ^ (y >> 1) ^ mag;
+        }
+        self.index = 0;
+    }
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Mt19937 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Mt19937 {{}}")
+    }
+}
+
+impl SeedableRng for Mt19937 {
+    type Seed = [u8; 32];
+
+    /// Create a new `Mt19937`, seeded with `init_by_array` using the seed as
+    /// eight little-endian `u32` words.
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut key = [0u32; 8];
+        le::read_u32_into(&seed, &mut key);
+        Mt19937::new_by_array(&key)
+    }
+}
+
+impl RngCore for Mt19937 {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        if self.index >= N {
+            self.twist();
+        }
+        let mut y = self.mt[self.index];
+        self.index += 1;
+
+        // Tempering
+        y ^= y >> 11;
+        y ^= (y << 7) & 0x9d2c5680;
+        y ^= (y << 15) & 0xefc60000;
+        y ^ (y >> 18)
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_u32(self)
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::rand_core::{RngCore, SeedableRng};
+    use super::Mt19937;
+
+    #[test]
+    fn test_mt19937_true_values() {
+        // Values from `mt19937ar.out` of the reference implementation.
+        let mut rng = Mt19937::new_by_array(&[0x123, 0x234, 0x345, 0x456]);
+        let mut results = [0u32; 10];
+        for i in results.iter_mut() { *i = rng.next_u32(); }
+        let expected: [u32; 10] = [
+            1067595299, 955945823, 477289528, 4107218783, 4228976476,
+            3344332714, 3355579695, 227628506, 810200273, 2591290167];
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_mt19937_init_genrand() {
+        // The C++ standard requires the 10000th consecutive invocation of a
+        // default-constructed `std::mt19937` to produce 4123659995.
+        let mut rng = Mt19937::new(5489);
+        assert_eq!(rng.next_u32(), 3499211612);
+        for _ in 1..9999 { rng.next_u32(); }
+        assert_eq!(rng.next_u32(), 4123659995);
+    }
+
+    #[test]
+    fn test_mt19937_from_seed() {
+        let seed = [0x23, 0x01, 0, 0, 0x34, 0x02, 0, 0,
+                    0x45, 0x03, 0, 0, 0x56, 0x04, 0, 0,
+                    1, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 4, 0, 0, 0];
+        let mut rng1 = Mt19937::from_seed(seed);
+        let mut rng2 = Mt19937::new_by_array(
+            &[0x123, 0x234, 0x345, 0x456, 1, 2, 3, 4]);
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u32(), rng2.next_u32());
+        }
+    }
+
+    #[test]
+    fn test_mt19937_clone() {
+        let mut rng1 = Mt19937::new(42);
+        let mut rng2 = rng1.clone();
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+    }
+
+    #[cfg(feature="serde1")]
+    #[test]
+    fn test_mt19937_serde() {
+        use bincode;
+        use std::io::{BufWriter, BufReader};
+
+        let mut rng = Mt19937::new(42);
+        rng.next_u32();
+
+        let buf: Vec<u8> = Vec::new();
+        let mut buf = BufWriter::new(buf);
+        bincode::serialize_into(&mut buf, &rng).expect("Could not serialize");
+
+        let buf = buf.into_inner().unwrap();
+        let mut read = BufReader::new(&buf[..]);
+        let mut deserialized: Mt19937 =
+            bincode::deserialize_from(&mut read).expect("Could not deserialize");
+
+        assert_eq!(&rng.mt[..], &deserialized.mt[..]);
+        assert_eq!(rng.index, deserialized.index);
+        for _ in 0..1000 {
+            assert_eq!(rng.next_u32(), deserialized.next_u32());
+        }
+    }
+}
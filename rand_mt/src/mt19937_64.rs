@@ -0,0 +1,245 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The MT19937-64 random number generator
+
+use core::fmt;
+use rand_core::{RngCore, SeedableRng, Error, le, impls};
+
+const NN: usize = 312;
+const MM: usize = 156;
+const MATRIX_A: u64 = 0xB5026F5AA96619E9;
+const UPPER_MASK: u64 = 0xFFFFFFFF80000000; // most significant 33 bits
+const LOWER_MASK: u64 = 0x7FFFFFFF; // least significant 31 bits
+
+#[cfg(feature="serde1")]
+array_serde!(state_serde, u64, 312);
+
+/// The MT19937-64 random number generator, the 64-bit Mersenne Twister.
+///
+/// MT19937-64 is not suitable for cryptographic purposes: its state can be
+/// reconstructed from 312 consecutive outputs. It has a period of
+/// 2<sup>19937</sup> - 1 and is 311-dimensionally equidistributed for 64-bit
+/// output, but its 2.5 KiB state is large and it fails some statistical tests
+/// (linear complexity) in TestU01.
+///
+/// The algorithm used here is translated from [the `mt19937-64.c` reference
+/// source code](http://www.math.sci.hiroshima-u.ac.jp/~m-mat/MT/emt64.html)
+/// by Takuji Nishimura and Makoto Matsumoto, and the output of [`next_u64`]
+/// is identical to its `genrand64_int64`. [`next_u32`] returns the upper half
+/// of a 64-bit output.
+///
+/// [`new`] and [`new_by_array`] use the seeding procedures of the reference
+/// implementation. `SeedableRng::from_seed` uses `init_by_array64` with the
+/// 32-byte seed read as four little-endian `u64` words.
+///
+/// [`next_u32`]: #method.next_u32
+/// [`next_u64`]: #method.next_u64
+/// [`new`]: #method.new
+/// [`new_by_array`]: #method.new_by_array
+#[derive(Clone)]
+#[cfg_attr(feature="serde1", derive(Serialize,Deserialize))]
+pub struct Mt19937_64 {
+    #[cfg_attr(feature="serde1", serde(with="state_serde"))]
+    mt: [u64; NN],
+    index: usize,
+}
+
+impl Mt19937_64 {
+    /// Create a new `Mt19937_64`, seeded with `init_genrand64(seed)`.
+    ///
+    /// This is the seeding procedure of C++'s `std::mt19937_64`. The default
+    /// seed of the reference implementation and of `std::mt19937_64` is 5489.
+    pub fn new(seed: u64) -> Self {
+        let mut mt = [0u64; NN];
+        mt[0] = seed;
+        for i in 1..NN {
+            mt[i] = 6364136223846793005u64
+                .wrapping_mul(mt[i-1] ^ (mt[i-1] >> 62))
+                .wrapping_add(i as u64);
+        }
+        Mt19937_64 { mt, index: NN }
+    }
+
+    /// Create a new `Mt19937_64`, seeded with `init_by_array64(key)`.
+    ///
+    /// # Panics
+    ///
+    /// If `key` is empty.
+    pub fn new_by_array(key: &[u64]) -> Self {
+        assert!(!key.is_empty(), "Mt19937_64::new_by_array: empty key");
+        let mut rng = Mt19937_64::new(19650218);
+        {
+            let mt = &mut rng.mt;
+            let mut i = 1;
+            let mut j = 0;
+            for _ in 0..NN.max(key.len()) {
+                mt[i] = (mt[i] ^ (mt[i-1] ^ (mt[i-1] >> 62))
+                                 .wrapping_mul(3935559000370003845))
+                    .wrapping_add(key[j])
+                    .wrapping_add(j as u64);
+                i += 1;
+                j += 1;
+                if i >= NN { mt[0] = mt[NN-1]; i = 1; }
+                if j >= key.len() { j = 0; }
+            }
+            for _ in 0..NN-1 {
+                mt[i] = (mt[i] ^ (mt[i-1] ^ (mt[i-1] >> 62))
+                                 .wrapping_mul(2862933555777941757))
+                    .wrapping_sub(i as u64);
+                i += 1;
+                if i >= NN { mt[0] = mt[NN-1]; i = 1; }
+            }
+            // MSB is 1, assuring a non-zero initial state
+            mt[0] = 1 << 63;
+        }
+        rng
+    }
+
+    // Generate the next NN words of state.
+    fn twist(&mut self) {
+        let mt = &mut self.mt;
+        for i in 0..NN {
+            let x = (mt[i] & UPPER_MASK) | (mt[(i + 1) % NN] & LOWER_MASK);
+            let mag = if x & 1 == 0 { 0 } else { MATRIX_A };
+            mt[i] = mt[(i + MM) % NN] ^ (x >> 1) ^ mag;
+        }
+        self.index = 0;
+    }
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Mt19937_64 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Mt19937_64 {{}}")
+    }
+}
+
+impl SeedableRng for Mt19937_64 {
+    type Seed = [u8; 32];
+
+    /// Create a new `Mt19937_64`, seeded with `init_by_array64` using the seed
+    /// as four little-endian `u64` words.
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut key = [0u64; 4];
+        le::read_u64_into(&seed, &mut key);
+        Mt19937_64::new_by_array(&key)
+    }
+}
+
+impl RngCore for Mt19937_64 {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        if self.index >= NN {
+            self.twist();
+        }
+        let mut x = self.mt[self.index];
+        self.index += 1;
+
+        // Tempering
+        x ^= (x >> 29) & 0x5555555555555555;
+        x ^= (x << 17) & 0x71D67FFFEDA60000;
+        x ^= (x << 37) & 0xFFF7EEE000000000;
+        x ^ (x >> 43)
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::rand_core::{RngCore, SeedableRng};
+    use super::Mt19937_64;
+
+    #[test]
+    fn test_mt19937_64_true_values() {
+        // Values from `mt19937-64.out` of the reference implementation.
+        let mut rng = Mt19937_64::new_by_array(
+            &[0x12345, 0x23456, 0x34567, 0x45678]);
+        let mut results = [0u64; 5];
+        for i in results.iter_mut() { *i = rng.next_u64(); }
+        let expected: [u64; 5] = [
+            7266447313870364031, 4946485549665804864, 16945909448695747420,
+            16394063075524226720, 4873882236456199058];
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_mt19937_64_init_genrand() {
+        // The C++ standard requires the 10000th consecutive invocation of a
+        // default-constructed `std::mt19937_64` to produce
+        // 9981545732273789042.
+        let mut rng = Mt19937_64::new(5489);
+        assert_eq!(rng.next_u64(), 14514284786278117030);
+        for _ in 1..9999 { rng.next_u64(); }
+        assert_eq!(rng.next_u64(), 9981545732273789042);
+    }
+
+    #[test]
+    fn test_mt19937_64_from_seed() {
+        let seed = [0x45, 0x23, 0x01, 0, 0, 0, 0, 0,
+                    0x56, 0x34, 0x02, 0, 0, 0, 0, 0,
+                    0x67, 0x45, 0x03, 0, 0, 0, 0, 0,
+                    0x78, 0x56, 0x04, 0, 0, 0, 0, 0];
+        let mut rng1 = Mt19937_64::from_seed(seed);
+        let mut rng2 = Mt19937_64::new_by_array(
+            &[0x12345, 0x23456, 0x34567, 0x45678]);
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_mt19937_64_clone() {
+        let mut rng1 = Mt19937_64::new(42);
+        let mut rng2 = rng1.clone();
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+    }
+
+    #[cfg(feature="serde1")]
+    #[test]
+    fn test_mt19937_64_serde() {
+        use bincode;
+        use std::io::{BufWriter, BufReader};
+
+        let mut rng = Mt19937_64::new(42);
+        rng.next_u64();
+
+        let buf: Vec<u8> = Vec::new();
+        let mut buf = BufWriter::new(buf);
+        bincode::serialize_into(&mut buf, &rng).expect("Could not serialize");
+
+        let buf = buf.into_inner().unwrap();
+        let mut read = BufReader::new(&buf[..]);
+        let mut deserialized: Mt19937_64 =
+            bincode::deserialize_from(&mut read).expect("Could not deserialize");
+
+        assert_eq!(&rng.mt[..], &deserialized.mt[..]);
+        assert_eq!(rng.index, deserialized.index);
+        for _ in 0..1000 {
+            assert_eq!(rng.next_u64(), deserialized.next_u64());
+        }
+    }
+}
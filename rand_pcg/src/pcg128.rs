@@ -0,0 +1,454 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! PCG random number generators with 128-bit state
+
+// This is the default multiplier used by PCG for 128-bit state.
+const MULTIPLIER: u128 = 0x2360_ED05_1FC6_5DA4_4385_DF64_9FCC_F645;
+
+use core::fmt;
+use rand_core::{RngCore, SeedableRng, SeekableRng, Error, le, impls};
+
+/// A PCG random number generator (XSL RR 128/64 (LCG) variant).
+///
+/// Permuted Congruential Generator with 128-bit state, internal Linear
+/// Congruential Generator, and 64-bit output via "xorshift low (bits),
+/// random rotation" output function.
+///
+/// This is a 128-bit LCG with explicitly chosen stream with the PCG-XSL-RR
+/// output function. This combination is the standard `pcg64`.
+///
+/// Despite the name, this implementation uses 32 bytes (256 bit) space
+/// comprising 128 bits of state and 128 bits stream selector. These are both
+/// set by `SeedableRng`, using a 256-bit seed.
+///
+/// This implements `SeekableRng`. A step is the generation of one `u64` (or
+/// `u32`), and the period is 2<sup>128</sup> steps. The position is computed
+/// from the state: it is the number of steps from the LCG state 0 of the
+/// stream, so a newly seeded generator is at an arbitrary position.
+///
+/// Note: this type is only available with Rust 1.26 or later.
+#[derive(Clone)]
+#[cfg_attr(feature="serde1", derive(Serialize,Deserialize))]
+pub struct Lcg128Xsl64 {
+    state: u128,
+    increment: u128,
+}
+
+/// `Lcg128Xsl64` is also officially known as `pcg64`.
+pub type Pcg64 = Lcg128Xsl64;
+
+impl Lcg128Xsl64 {
+    /// Construct an instance compatible with PCG seed and stream.
+    ///
+    /// Note that PCG specifies default values for both parameters:
+    ///
+    /// - `state = 0xcafef00dd15ea5e5`
+    /// - `stream = 0xa02bdbf7bb3c0a7ac28fa16a64abf96`
+    pub fn new(state: u128, stream: u128) -> Self {
+        // The increment must be odd, hence we discard one bit:
+        let increment = (stream << 1) | 1;
+        Lcg128Xsl64::from_state_incr(state, increment)
+    }
+
+    #[inline]
+    fn from_state_incr(state: u128, increment: u128) -> Self {
+        let mut pcg = Lcg128Xsl64 { state, increment };
+        // Move away from inital value:
+        pcg.state = pcg.state.wrapping_add(pcg.increment);
+        pcg.step();
+        pcg
+    }
+
+    #[inline]
+    fn step(&mut self) {
+        // prepare the LCG for the next round
+        self.state = self.state
+            .wrapping_mul(MULTIPLIER)
+            .wrapping_add(self.increment);
+    }
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Lcg128Xsl64 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Lcg128Xsl64 {{}}")
+    }
+}
+
+/// We use a single 255-bit seed to initialise the state and select a stream.
+/// One `seed` bit (lowest bit of `seed[16]`) is ignored.
+impl SeedableRng for Lcg128Xsl64 {
+    type Seed = [u8; 32];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut seed_u64 = [0u64; 4];
+        le::read_u64_into(&seed, &mut seed_u64);
+        let state = u128::from(seed_u64[0]) | (u128::from(seed_u64[1]) << 64);
+        let incr = u128::from(seed_u64[2]) | (u128::from(seed_u64[3]) << 64);
+
+        // The increment must be odd, hence we discard one bit:
+        Lcg128Xsl64::from_state_incr(state, incr | 1)
+    }
+}
+
+impl RngCore for Lcg128Xsl64 {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.step();
+        output_xsl_rr(self.state)
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+
+/// A PCG random number generator (XSL RR 128/64 (MCG) variant).
+///
+/// Permuted Congruential Generator with 128-bit state, internal Multiplicative
+/// Congruential Generator, and 64-bit output via "xorshift low (bits),
+/// random rotation" output function.
+///
+/// This is a 128-bit MCG with the PCG-XSL-RR output function, also known as
+/// `pcg64_fast`.
+/// Note that compared to the standard `pcg64` (128-bit LCG with PCG-XSL-RR
+/// output function), this RNG is faster, also has a long cycle, and still has
+/// good performance on statistical tests.
+///
+/// This implements `SeekableRng`. A step is the generation of one `u64` (or
+/// `u32`), and the period is 2<sup>126</sup> steps. The position is computed
+/// from the state: it is the number of steps from the MCG state 1 or 3 (the
+/// state modulo 4 never changes), so a newly seeded generator is at an
+/// arbitrary position.
+///
+/// Note: this type is only available with Rust 1.26 or later.
+#[derive(Clone)]
+#[cfg_attr(feature="serde1", derive(Serialize,Deserialize))]
+pub struct Mcg128Xsl64 {
+    state: u128,
+}
+
+/// A friendly name for `Mcg128Xsl64` (also known as `pcg64_fast`).
+pub type Pcg64Mcg = Mcg128Xsl64;
+
+impl Mcg128Xsl64 {
+    /// Construct an instance compatible with PCG seed.
+    ///
+    /// Note that PCG specifies a default value for the parameter:
+    ///
+    /// - `state = 0xcafef00dd15ea5e5`
+    pub fn new(state: u128) -> Self {
+        // Force low bit to 1, as in C version (C++ uses `state | 3` instead).
+        Mcg128Xsl64 { state: state | 1 }
+    }
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Mcg128Xsl64 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Mcg128Xsl64 {{}}")
+    }
+}
+
+/// We use a single 126-bit seed to initialise the state and select a stream.
+/// Two `seed` bits (lowest order of last byte) are ignored.
+impl SeedableRng for Mcg128Xsl64 {
+    type Seed = [u8; 16];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        // Read as if a little-endian u128 value:
+        let mut seed_u64 = [0u64; 2];
+        le::read_u64_into(&seed, &mut seed_u64);
+        let state = u128::from(seed_u64[0]) | (u128::from(seed_u64[1]) << 64);
+        Mcg128Xsl64::new(state)
+    }
+}
+
+impl RngCore for Mcg128Xsl64 {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_mul(MULTIPLIER);
+        output_xsl_rr(self.state)
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+// Output function XSL RR ("xorshift low (bits), random rotation").
+// Constants are for 128-bit state, 64-bit output.
+#[inline(always)]
+fn output_xsl_rr(state: u128) -> u64 {
+    const XSHIFT: u32 = 64;     // (128 - 64 + 64) / 2
+    const ROTATE: u32 = 122;    // 128 - 6
+
+    let rot = (state >> ROTATE) as u32;
+    let xsl = ((state >> XSHIFT) as u64) ^ (state as u64);
+    xsl.rotate_right(rot)
+}
+
+impl SeekableRng for Lcg128Xsl64 {
+    fn position(&self) -> u128 {
+        lcg_distance(0, self.state, self.increment)
+    }
+
+    fn seek(&mut self, pos: u128) {
+        self.state = advance_lcg(0, self.increment, pos);
+    }
+
+    fn advance(&mut self, delta: u128) {
+        self.state = advance_lcg(self.state, self.increment, delta);
+    }
+}
+
+impl SeekableRng for Mcg128Xsl64 {
+    fn position(&self) -> u128 {
+        lcg_distance(self.state & 3, self.state, 0)
+    }
+
+    /// Seek to position `pos` modulo 2<sup>126</sup>.
+    fn seek(&mut self, pos: u128) {
+        self.state = advance_lcg(self.state & 3, 0, pos);
+    }
+
+    fn advance(&mut self, delta: u128) {
+        self.state = advance_lcg(self.state, 0, delta);
+    }
+}
+
+// Advance the LCG with state `state` by `delta` steps in O(log(delta)) time,
+// using the algorithm of F. Brown, "Random Number Generation with Arbitrary
+// Stride", 1994. An increment of 0 gives the MCG.
+fn advance_lcg(state: u128, increment: u128, delta: u128) -> u128 {
+    let mut acc_mult: u128 = 1;
+    let mut acc_plus: u128 = 0;
+    let mut cur_mult = MULTIPLIER;
+    let mut cur_plus = increment;
+    let mut delta = delta;
+    while delta > 0 {
+        if delta & 1 != 0 {
+            acc_mult = acc_mult.wrapping_mul(cur_mult);
+            acc_plus = acc_plus.wrapping_mul(cur_mult).wrapping_add(cur_plus);
+        }
+        cur_plus = cur_mult.wrapping_add(1).wrapping_mul(cur_plus);
+        cur_mult = cur_mult.wrapping_mul(cur_mult);
+        delta >>= 1;
+    }
+    acc_mult.wrapping_mul(state).wrapping_add(acc_plus)
+}
+
+// The number of steps from state `from` to state `to` of the LCG, found one
+// bit at a time: bit `i` of the state only depends on the lowest `i + 1` bits
+// of the number of steps. For the MCG (an increment of 0) the lowest two bits
+// of the state are fixed, and bit `i + 2` of the state depends on the lowest
+// `i + 1` bits of the number of steps.
+fn lcg_distance(from: u128, to: u128, increment: u128) -> u128 {
+    let mut state = from;
+    let mut cur_mult = MULTIPLIER;
+    let mut cur_plus = increment;
+    let mut distance = 0;
+    let mut bit = if increment == 0 { 4 } else { 1 };
+    while state != to {
+        if (state ^ to) & bit != 0 {
+            state = state.wrapping_mul(cur_mult).wrapping_add(cur_plus);
+            distance |= bit;
+        }
+        cur_plus = cur_mult.wrapping_add(1).wrapping_mul(cur_plus);
+        cur_mult = cur_mult.wrapping_mul(cur_mult);
+        bit <<= 1;
+    }
+    if increment == 0 { distance >> 2 } else { distance }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::rand_core::{RngCore, SeedableRng};
+    use super::{Lcg128Xsl64, Mcg128Xsl64};
+
+    #[test]
+    fn test_lcg128xsl64_construction() {
+        // Test that various construction techniques produce a working RNG.
+        let seed = [1,2,3,4, 5,6,7,8, 9,10,11,12, 13,14,15,16,
+                    17,18,19,20, 21,22,23,24, 25,26,27,28, 29,30,31,32];
+        let mut rng1 = Lcg128Xsl64::from_seed(seed);
+        assert_eq!(rng1.next_u64(), 8740028313290271629);
+
+        let mut rng2 = Lcg128Xsl64::from_rng(&mut rng1).unwrap();
+        assert_eq!(rng2.next_u64(), 1922280315005786345);
+    }
+
+    #[test]
+    fn test_lcg128xsl64_true_values() {
+        // Numbers copied from official test suite (C version).
+        let mut rng = Lcg128Xsl64::new(42, 54);
+
+        let mut results = [0u64; 6];
+        for i in results.iter_mut() { *i = rng.next_u64(); }
+        let expected: [u64; 6] = [0x86b1da1d72062b68, 0x1304aa46c9853d39,
+                0xa3670e9e0dd50358, 0xf9090e529a7dae00, 0xc85b9fd837996f2c,
+                0x606121f8e3919196];
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_mcg128xsl64_construction() {
+        // Test that various construction techniques produce a working RNG.
+        let seed = [1,2,3,4, 5,6,7,8, 9,10,11,12, 13,14,15,16];
+        let mut rng1 = Mcg128Xsl64::from_seed(seed);
+        assert_eq!(rng1.next_u64(), 7071994460355047496);
+
+        let mut rng2 = Mcg128Xsl64::from_rng(&mut rng1).unwrap();
+        assert_eq!(rng2.next_u64(), 12300796107712034932);
+    }
+
+    #[test]
+    fn test_mcg128xsl64_true_values() {
+        // Numbers copied from official test suite (C version).
+        let mut rng = Mcg128Xsl64::new(42);
+
+        let mut results = [0u64; 6];
+        for i in results.iter_mut() { *i = rng.next_u64(); }
+        let expected: [u64; 6] = [0x63b4a3a813ce700a, 0x382954200617ab24,
+                0xa7fd85ae3fe950ce, 0xd715286aa2887737, 0x60c92fee2e59f32c,
+                0x84c4e96beff30017];
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_pcg128_clone() {
+        let mut rng1 = Lcg128Xsl64::new(42, 54);
+        let mut rng2 = rng1.clone();
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+
+        let mut rng1 = Mcg128Xsl64::new(42);
+        let mut rng2 = rng1.clone();
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_pcg128_seek() {
+        use ::rand_core::SeekableRng;
+
+        let mut rng1 = Lcg128Xsl64::new(42, 54);
+        let mut rng2 = rng1.clone();
+        let start = rng1.position();
+        for _ in 0..1000 {
+            rng1.next_u64();
+        }
+        assert_eq!(rng1.position(), start.wrapping_add(1000));
+        rng2.advance(1000);
+        assert_eq!(rng1.next_u64(), rng2.next_u64());
+        rng1.seek(start.wrapping_add(3));
+        rng2.advance(!0 - 997); // wraps around to 3
+        assert_eq!(rng2.position(), start.wrapping_add(3));
+        assert_eq!(rng1.next_u64(), rng2.next_u64());
+
+        // Restore a checkpoint on a generator with the same stream
+        let mut rng3 = Lcg128Xsl64::new(7, 54);
+        rng3.seek(rng1.position());
+        assert_eq!(rng1.next_u64(), rng3.next_u64());
+
+        let period = 1 << 126;
+        let mut rng1 = Mcg128Xsl64::new(42);
+        let mut rng2 = rng1.clone();
+        let start = rng1.position();
+        assert!(start < period);
+        for _ in 0..1000 {
+            rng1.next_u64();
+        }
+        assert_eq!(rng1.position(), (start + 1000) % period);
+        rng2.seek(start + 1000);
+        assert_eq!(rng1.next_u64(), rng2.next_u64());
+        rng1.seek(period + 5);
+        rng2.seek(5);
+        assert_eq!(rng1.position(), 5);
+        assert_eq!(rng1.next_u64(), rng2.next_u64());
+
+        // Both classes of states modulo 4
+        for &state in &[0x1234_5678, 0x1234_567b] {
+            let mut rng1 = Mcg128Xsl64::new(state);
+            let mut rng2 = Mcg128Xsl64::new(state);
+            rng1.advance(0x3_0000_0000_0000_0000_0042);
+            let pos = rng1.position();
+            rng2.seek(rng2.position() + 0x3_0000_0000_0000_0000_0042);
+            assert_eq!(rng2.position(), pos);
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+    }
+
+    #[cfg(feature="serde1")]
+    #[test]
+    fn test_pcg128_serde() {
+        use bincode;
+        use std::io::{BufWriter, BufReader};
+
+        let mut rng = Lcg128Xsl64::new(42, 54);
+
+        let buf: Vec<u8> = Vec::new();
+        let mut buf = BufWriter::new(buf);
+        bincode::serialize_into(&mut buf, &rng).expect("Could not serialize");
+
+        let buf = buf.into_inner().unwrap();
+        let mut read = BufReader::new(&buf[..]);
+        let mut deserialized: Lcg128Xsl64 = bincode::deserialize_from(&mut read)
+            .expect("Could not deserialize");
+
+        assert_eq!(rng.state, deserialized.state);
+        assert_eq!(rng.increment, deserialized.increment);
+        for _ in 0..16 {
+            assert_eq!(rng.next_u64(), deserialized.next_u64());
+        }
+
+        let mut rng = Mcg128Xsl64::new(42);
+
+        let buf: Vec<u8> = Vec::new();
+        let mut buf = BufWriter::new(buf);
+        bincode::serialize_into(&mut buf, &rng).expect("Could not serialize");
+
+        let buf = buf.into_inner().unwrap();
+        let mut read = BufReader::new(&buf[..]);
+        let mut deserialized: Mcg128Xsl64 = bincode::deserialize_from(&mut read)
+            .expect("Could not deserialize");
+
+        assert_eq!(rng.state, deserialized.state);
+        for _ in 0..16 {
+            assert_eq!(rng.next_u64(), deserialized.next_u64());
+        }
+    }
+}
@@ -0,0 +1,284 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! PCG random number generators with 64-bit state
+
+use core::fmt;
+use rand_core::{RngCore, SeedableRng, Error, le, impls};
+#[cfg(rust_1_26)] use rand_core::SeekableRng;
+
+// This is the default multiplier used by PCG for 64-bit state.
+const MULTIPLIER: u64 = 6364136223846793005;
+
+/// A PCG random number generator (XSH RR 64/32 (LCG) variant).
+///
+/// Permuted Congruential Generator with 64-bit state, internal Linear
+/// Congruential Generator, and 32-bit output via "xorshift high (bits),
+/// random rotation" output function.
+///
+/// This is a 64-bit LCG with explicitly chosen stream with the PCG-XSH-RR
+/// output function. This combination is the standard `pcg32`.
+///
+/// Despite the name, this implementation uses 16 bytes (128 bit) space
+/// comprising 64 bits of state and 64 bits stream selector. These are both set
+/// by `SeedableRng`, using a 128-bit seed.
+///
+/// With Rust 1.26 or later this implements `SeekableRng`. A step is the
+/// generation of one `u32`, and the period is 2<sup>64</sup> steps. The
+/// position is computed from the state: it is the number of steps from the
+/// LCG state 0 of the stream, so a newly seeded generator is at an arbitrary
+/// position.
+#[derive(Clone)]
+#[cfg_attr(feature="serde1", derive(Serialize,Deserialize))]
+pub struct Lcg64Xsh32 {
+    state: u64,
+    increment: u64,
+}
+
+/// `Lcg64Xsh32` is also officially known as `pcg32`.
+pub type Pcg32 = Lcg64Xsh32;
+
+impl Lcg64Xsh32 {
+    /// Construct an instance compatible with PCG seed and stream.
+    ///
+    /// Note that PCG specifies default values for both parameters:
+    ///
+    /// - `state = 0xcafef00dd15ea5e5`
+    /// - `stream = 0xa02bdbf7bb3c0a7`
+    // Note: stream is 1442695040888963407u64 >> 1
+    pub fn new(state: u64, stream: u64) -> Self {
+        // The increment must be odd, hence we discard one bit:
+        let increment = (stream << 1) | 1;
+        Lcg64Xsh32::from_state_incr(state, increment)
+    }
+
+    #[inline]
+    fn from_state_incr(state: u64, increment: u64) -> Self {
+        let mut pcg = Lcg64Xsh32 { state, increment };
+        // Move away from inital value:
+        pcg.state = pcg.state.wrapping_add(pcg.increment);
+        pcg.step();
+        pcg
+    }
+
+    #[inline]
+    fn step(&mut self) {
+        // prepare the LCG for the next round
+        self.state = self.state
+            .wrapping_mul(MULTIPLIER)
+            .wrapping_add(self.increment);
+    }
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Lcg64Xsh32 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Lcg64Xsh32 {{}}")
+    }
+}
+
+/// We use a single 127-bit seed to initialise the state and select a stream.
+/// One `seed` bit (lowest bit of `seed[8]`) is ignored.
+impl SeedableRng for Lcg64Xsh32 {
+    type Seed = [u8; 16];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut seed_u64 = [0u64; 2];
+        le::read_u64_into(&seed, &mut seed_u64);
+
+        // The increment must be odd, hence we discard one bit:
+        Lcg64Xsh32::from_state_incr(seed_u64[0], seed_u64[1] | 1)
+    }
+}
+
+impl RngCore for Lcg64Xsh32 {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        let state = self.state;
+        self.step();
+
+        // Output function XSH RR: xorshift high (bits), followed by a random
+        // rotate. Constants are for 64-bit state, 32-bit output.
+        const ROTATE: u32 = 59; // 64 - 5
+        const XSHIFT: u32 = 18; // (5 + 32) / 2
+        const SPARE: u32 = 27;  // 64 - 32 - 5
+
+        let rot = (state >> ROTATE) as u32;
+        let xsh = (((state >> XSHIFT) ^ state) >> SPARE) as u32;
+        xsh.rotate_right(rot)
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_u32(self)
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+#[cfg(rust_1_26)]
+impl SeekableRng for Lcg64Xsh32 {
+    fn position(&self) -> u128 {
+        u128::from(lcg_distance(0, self.state, self.increment))
+    }
+
+    /// Seek to position `pos` modulo 2<sup>64</sup>.
+    fn seek(&mut self, pos: u128) {
+        self.state = advance_lcg(0, self.increment, pos as u64);
+    }
+
+    fn advance(&mut self, delta: u128) {
+        self.state = advance_lcg(self.state, self.increment, delta as u64);
+    }
+}
+
+// Advance the LCG with state `state` by `delta` steps in O(log(delta)) time,
+// using the algorithm of F. Brown, "Random Number Generation with Arbitrary
+// Stride", 1994.
+#[cfg(rust_1_26)]
+fn advance_lcg(state: u64, increment: u64, delta: u64) -> u64 {
+    let mut acc_mult: u64 = 1;
+    let mut acc_plus: u64 = 0;
+    let mut cur_mult = MULTIPLIER;
+    let mut cur_plus = increment;
+    let mut delta = delta;
+    while delta > 0 {
+        if delta & 1 != 0 {
+            acc_mult = acc_mult.wrapping_mul(cur_mult);
+            acc_plus = acc_plus.wrapping_mul(cur_mult).wrapping_add(cur_plus);
+        }
+        cur_plus = cur_mult.wrapping_add(1).wrapping_mul(cur_plus);
+        cur_mult = cur_mult.wrapping_mul(cur_mult);
+        delta >>= 1;
+    }
+    acc_mult.wrapping_mul(state).wrapping_add(acc_plus)
+}
+
+// The number of steps from state `from` to state `to` of the LCG, found one
+// bit at a time: bit `i` of the state only depends on the lowest `i + 1` bits
+// of the number of steps.
+#[cfg(rust_1_26)]
+fn lcg_distance(from: u64, to: u64, increment: u64) -> u64 {
+    let mut state = from;
+    let mut cur_mult = MULTIPLIER;
+    let mut cur_plus = increment;
+    let mut distance = 0;
+    let mut bit = 1;
+    while state != to {
+        if (state ^ to) & bit != 0 {
+            state = state.wrapping_mul(cur_mult).wrapping_add(cur_plus);
+            distance |= bit;
+        }
+        cur_plus = cur_mult.wrapping_add(1).wrapping_mul(cur_plus);
+        cur_mult = cur_mult.wrapping_mul(cur_mult);
+        bit <<= 1;
+    }
+    distance
+}
+
+#[cfg(test)]
+mod tests {
+    use ::rand_core::{RngCore, SeedableRng};
+    use super::Lcg64Xsh32;
+
+    #[test]
+    fn test_lcg64xsh32_construction() {
+        // Test that various construction techniques produce a working RNG.
+        let seed = [1,2,3,4, 5,6,7,8, 9,10,11,12, 13,14,15,16];
+        let mut rng1 = Lcg64Xsh32::from_seed(seed);
+        assert_eq!(rng1.next_u64(), 1204678643940597513);
+
+        let mut rng2 = Lcg64Xsh32::from_rng(&mut rng1).unwrap();
+        assert_eq!(rng2.next_u64(), 12384929573776311845);
+    }
+
+    #[test]
+    fn test_lcg64xsh32_true_values() {
+        // Numbers copied from official test suite.
+        let mut rng = Lcg64Xsh32::new(42, 54);
+
+        let mut results = [0u32; 6];
+        for i in results.iter_mut() { *i = rng.next_u32(); }
+        let expected: [u32; 6] = [0xa15c02b7, 0x7b47f409, 0xba1d3330,
+                0x83d2f293, 0xbfa4784b, 0xcbed606e];
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_lcg64xsh32_clone() {
+        let mut rng1 = Lcg64Xsh32::new(42, 54);
+        let mut rng2 = rng1.clone();
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+    }
+
+    #[cfg(rust_1_26)]
+    #[test]
+    fn test_lcg64xsh32_seek() {
+        use ::rand_core::SeekableRng;
+
+        let mut rng1 = Lcg64Xsh32::new(42, 54);
+        let mut rng2 = rng1.clone();
+        let start = rng1.position();
+        for _ in 0..1000 {
+            rng1.next_u32();
+        }
+        assert_eq!(rng1.position(), (start + 1000) % (1 << 64));
+        rng2.advance(1000);
+        assert_eq!(rng1.next_u64(), rng2.next_u64());
+
+        // Seek backwards, and around the period
+        rng1.seek(start + 3);
+        let mut rng3 = Lcg64Xsh32::new(42, 54);
+        rng3.next_u32();
+        rng3.advance((1 << 64) + 2);
+        assert_eq!(rng3.position(), (start + 3) % (1 << 64));
+        assert_eq!(rng1.next_u64(), rng3.next_u64());
+
+        // Restore a checkpoint on a generator with the same stream
+        let mut rng4 = Lcg64Xsh32::new(7, 54);
+        rng4.seek(rng1.position());
+        assert_eq!(rng1.next_u64(), rng4.next_u64());
+    }
+
+    #[cfg(feature="serde1")]
+    #[test]
+    fn test_lcg64xsh32_serde() {
+        use bincode;
+        use std::io::{BufWriter, BufReader};
+
+        let mut rng = Lcg64Xsh32::new(42, 54);
+
+        let buf: Vec<u8> = Vec::new();
+        let mut buf = BufWriter::new(buf);
+        bincode::serialize_into(&mut buf, &rng).expect("Could not serialize");
+
+        let buf = buf.into_inner().unwrap();
+        let mut read = BufReader::new(&buf[..]);
+        let mut deserialized: Lcg64Xsh32 = bincode::deserialize_from(&mut read)
+            .expect("Could not deserialize");
+
+        assert_eq!(rng.state, deserialized.state);
+        assert_eq!(rng.increment, deserialized.increment);
+
+        for _ in 0..16 {
+            assert_eq!(rng.next_u64(), deserialized.next_u64());
+        }
+    }
+}
@@ -32,3 +32,4 @@ extern crate rand_core;
 mod xorshift;
 
 pub use self::xorshift::XorShiftRng;
+#[cfg(rust_1_26)] pub use self::xorshift::SeekableXorShiftRng;
@@ -12,7 +12,9 @@
 
 use core::num::Wrapping as w;
 use core::{fmt, slice};
-use rand_core::{RngCore, SeedableRng, Error, impls, le};
+use rand_core::{RngCore, SeedableRng, Error, ErrorKind, impls, le};
+use rand_core::state::{StateBytes, StateReader, StateWriter};
+#[cfg(rust_1_26)] use rand_core::SeekableRng;
 
 /// An Xorshift random number generator.
 ///
@@ -20,9 +22,17 @@ use rand_core::{RngCore, SeedableRng, Error, impls, le};
 /// but is very fast. If you do not know for sure that it fits your
 /// requirements, use a more secure one such as `StdRng` or `OsRng`.
 ///
+/// With Rust 1.26 or later [`advance`] jumps ahead in the output stream,
+/// using the characteristic polynomial of the generator. The state does not
+/// record its position; [`SeekableXorShiftRng`] keeps track of it and
+/// implements `SeekableRng`.
+///
 /// [^1]: Marsaglia, George (July 2003).
 ///       ["Xorshift RNGs"](https://www.jstatsoft.org/v08/i14/paper).
 ///       *Journal of Statistical Software*. Vol. 8 (Issue 14).
+///
+/// [`advance`]: #method.advance
+/// [`SeekableXorShiftRng`]: struct.SeekableXorShiftRng.html
 #[derive(Clone)]
 #[cfg_attr(feature="serde1", derive(Serialize,Deserialize))]
 pub struct XorShiftRng {
@@ -32,16 +42,13 @@ pub struct XorShiftRng {
     w: w<u32>,
 }
 
-// Custom Debug implementation that does not expose the internal state
-impl fmt::Debug for XorShiftRng {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "XorShiftRng {{}}")
+impl XorShiftRng {
+    fn from_u32s(s: [u32; 4]) -> Self {
+        XorShiftRng { x: w(s[0]), y: w(s[1]), z: w(s[2]), w: w(s[3]) }
     }
-}
 
-impl RngCore for XorShiftRng {
     #[inline]
-    fn next_u32(&mut self) -> u32 {
+    fn step(&mut self) {
         let x = self.x;
         let t = x ^ (x << 11);
         self.x = self.y;
@@ -49,6 +56,71 @@ impl RngCore for XorShiftRng {
         self.z = self.w;
         let w_ = self.w;
         self.w = w_ ^ (w_ >> 19) ^ (t ^ (t >> 8));
+    }
+
+    /// Advance the generator by `delta` steps, where a step is the
+    /// generation of one `u32`. The period is 2<sup>128</sup> - 1 steps.
+    ///
+    /// This takes about as long as generating a few thousand values,
+    /// independent of `delta`.
+    ///
+    /// Note: this method is only available with Rust 1.26 or later.
+    #[cfg(rust_1_26)]
+    pub fn advance(&mut self, delta: u128) {
+        // By the Cayley-Hamilton theorem the transition matrix T satisfies
+        // its characteristic polynomial p, so T^delta = r(T) with
+        // r(x) = x^delta mod p(x).
+        // The characteristic polynomial, without the x^128 term.
+        const POLY: u128 = 0x1_0046_d8b3_f985_d65f_fd3c_8001;
+
+        fn mul_mod(a: u128, b: u128) -> u128 {
+            let mut r = 0;
+            for i in (0..128).rev() {
+                let carry = r >> 127;
+                r <<= 1;
+                if carry != 0 { r ^= POLY; }
+                if (b >> i) & 1 != 0 { r ^= a; }
+            }
+            r
+        }
+
+        let mut r = 1;
+        let mut base = 2; // x
+        let mut delta = delta;
+        while delta > 0 {
+            if delta & 1 != 0 { r = mul_mod(r, base); }
+            base = mul_mod(base, base);
+            delta >>= 1;
+        }
+
+        let mut acc = [w(0u32); 4];
+        for i in 0..128 {
+            if (r >> i) & 1 != 0 {
+                acc[0] ^= self.x;
+                acc[1] ^= self.y;
+                acc[2] ^= self.z;
+                acc[3] ^= self.w;
+            }
+            self.step();
+        }
+        self.x = acc[0];
+        self.y = acc[1];
+        self.z = acc[2];
+        self.w = acc[3];
+    }
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for XorShiftRng {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "XorShiftRng {{}}")
+    }
+}
+
+impl RngCore for XorShiftRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.step();
         self.w.0
     }
 
@@ -67,6 +139,32 @@ impl RngCore for XorShiftRng {
     }
 }
 
+/// The state is `x`, `y`, `z` and `w` as `u32`: 16 bytes.
+impl StateBytes for XorShiftRng {
+    const ALGORITHM_ID: [u8; 8] = *b"XorShift";
+    const FORMAT_VERSION: u32 = 1;
+
+    fn state_len() -> usize {
+        16
+    }
+
+    fn write_state(&self, state: &mut [u8]) {
+        let mut writer = StateWriter::new(state);
+        writer.write_u32s(&[self.x.0, self.y.0, self.z.0, self.w.0]);
+    }
+
+    fn read_state(state: &[u8]) -> Result<Self, Error> {
+        let mut reader = StateReader::new(state);
+        let mut s = [0u32; 4];
+        reader.read_u32s(&mut s);
+        if s == [0; 4] {
+            return Err(Error::new(ErrorKind::Unavailable,
+                                  "state bytes: all-zero XorShift state"));
+        }
+        Ok(XorShiftRng::from_u32s(s))
+    }
+}
+
 impl SeedableRng for XorShiftRng {
     type Seed = [u8; 16];
 
@@ -81,12 +179,7 @@ impl SeedableRng for XorShiftRng {
             seed_u32 = [0xBAD_5EED, 0xBAD_5EED, 0xBAD_5EED, 0xBAD_5EED];
         }
 
-        XorShiftRng {
-            x: w(seed_u32[0]),
-            y: w(seed_u32[1]),
-            z: w(seed_u32[2]),
-            w: w(seed_u32[3]),
-        }
+        XorShiftRng::from_u32s(seed_u32)
     }
 
     fn from_rng<R: RngCore>(mut rng: R) -> Result<Self, Error> {
@@ -101,12 +194,139 @@ impl SeedableRng for XorShiftRng {
             if !seed_u32.iter().all(|&x| x == 0) { break; }
         }
 
-        Ok(XorShiftRng {
-            x: w(seed_u32[0]),
-            y: w(seed_u32[1]),
-            z: w(seed_u32[2]),
-            w: w(seed_u32[3]),
-        })
+        Ok(XorShiftRng::from_u32s(seed_u32))
+    }
+}
+
+/// An [`XorShiftRng`] that keeps track of its position in the output stream,
+/// and implements `SeekableRng`.
+///
+/// A step is the generation of one `u32`, and the period is
+/// 2<sup>128</sup> - 1 steps. The position counts the steps since
+/// construction, so it is 0 for a newly seeded generator. Counting the steps
+/// makes this generator a little slower than `XorShiftRng`, and its state is
+/// 32 bytes instead of 16.
+///
+/// Note: this type is only available with Rust 1.26 or later.
+///
+/// [`XorShiftRng`]: struct.XorShiftRng.html
+#[cfg(rust_1_26)]
+#[derive(Clone)]
+#[cfg_attr(feature="serde1", derive(Serialize,Deserialize))]
+pub struct SeekableXorShiftRng {
+    rng: XorShiftRng,
+    // Number of steps since construction, as a little-endian 128-bit number
+    steps: [u64; 2],
+}
+
+#[cfg(rust_1_26)]
+impl SeekableXorShiftRng {
+    fn from_rng_at_start(rng: XorShiftRng) -> Self {
+        SeekableXorShiftRng { rng, steps: [0; 2] }
+    }
+}
+
+// Custom Debug implementation that does not expose the internal state
+#[cfg(rust_1_26)]
+impl fmt::Debug for SeekableXorShiftRng {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SeekableXorShiftRng {{}}")
+    }
+}
+
+#[cfg(rust_1_26)]
+impl RngCore for SeekableXorShiftRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.steps[0] = self.steps[0].wrapping_add(1);
+        if self.steps[0] == 0 {
+            self.steps[1] = self.steps[1].wrapping_add(1);
+        }
+        self.rng.next_u32()
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_u32(self)
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+/// The state is that of `XorShiftRng`, followed by the number of steps since
+/// construction as two `u64`, least significant first: 32 bytes.
+#[cfg(rust_1_26)]
+impl StateBytes for SeekableXorShiftRng {
+    const ALGORITHM_ID: [u8; 8] = *b"XorShSek";
+    const FORMAT_VERSION: u32 = 1;
+
+    fn state_len() -> usize {
+        32
+    }
+
+    fn write_state(&self, state: &mut [u8]) {
+        self.rng.write_state(&mut state[..16]);
+        StateWriter::new(&mut state[16..]).write_u64s(&self.steps);
+    }
+
+    fn read_state(state: &[u8]) -> Result<Self, Error> {
+        let mut rng = Self::from_rng_at_start(XorShiftRng::read_state(&state[..16])?);
+        StateReader::new(&state[16..]).read_u64s(&mut rng.steps);
+        Ok(rng)
+    }
+}
+
+#[cfg(rust_1_26)]
+impl SeedableRng for SeekableXorShiftRng {
+    type Seed = <XorShiftRng as SeedableRng>::Seed;
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self::from_rng_at_start(XorShiftRng::from_seed(seed))
+    }
+
+    fn from_rng<R: RngCore>(rng: R) -> Result<Self, Error> {
+        XorShiftRng::from_rng(rng).map(Self::from_rng_at_start)
+    }
+}
+
+#[cfg(rust_1_26)]
+const PERIOD: u128 = !0;
+
+#[cfg(rust_1_26)]
+impl SeekableRng for SeekableXorShiftRng {
+    fn position(&self) -> u128 {
+        u128::from(self.steps[0]) | (u128::from(self.steps[1]) << 64)
+    }
+
+    /// Seek to position `pos` modulo the period.
+    fn seek(&mut self, pos: u128) {
+        let pos = pos % PERIOD;
+        let current = self.position();
+        let delta = if pos >= current {
+            pos - current
+        } else {
+            PERIOD - (current - pos)
+        };
+        self.advance(delta);
+    }
+
+    fn advance(&mut self, delta: u128) {
+        let delta = delta % PERIOD;
+        self.rng.advance(delta);
+        let current = self.position();
+        let pos = if current >= PERIOD - delta {
+            current - (PERIOD - delta)
+        } else {
+            current + delta
+        };
+        self.steps = [pos as u64, (pos >> 64) as u64];
     }
 }
 
@@ -114,6 +334,7 @@ impl SeedableRng for XorShiftRng {
 mod tests {
     use ::rand_core::{RngCore, SeedableRng};
     use super::XorShiftRng;
+    #[cfg(rust_1_26)] use super::SeekableXorShiftRng;
 
     #[test]
     fn test_xorshift_construction() {
@@ -178,6 +399,64 @@ mod tests {
         }
     }
 
+    #[cfg(rust_1_26)]
+    #[test]
+    fn test_xorshift_advance() {
+        let seed = [1,2,3,4, 5,6,7,8, 9,10,11,12, 13,14,15,16];
+        let mut rng1 = XorShiftRng::from_seed(seed);
+        let mut rng2 = rng1.clone();
+        for _ in 0..1000 {
+            rng1.next_u32();
+        }
+        rng2.advance(1000);
+        assert_eq!(rng1.next_u64(), rng2.next_u64());
+
+        // The period is 2^128 - 1
+        let mut rng3 = XorShiftRng::from_seed(seed);
+        rng3.advance(!0);
+        let mut rng4 = XorShiftRng::from_seed(seed);
+        assert_eq!(rng3.next_u64(), rng4.next_u64());
+    }
+
+    #[cfg(rust_1_26)]
+    #[test]
+    fn test_xorshift_seek() {
+        use rand_core::SeekableRng;
+
+        let seed = [1,2,3,4, 5,6,7,8, 9,10,11,12, 13,14,15,16];
+        let mut rng1 = SeekableXorShiftRng::from_seed(seed);
+        let mut rng2 = rng1.clone();
+        assert_eq!(rng1.position(), 0);
+        for _ in 0..1000 {
+            rng1.next_u32();
+        }
+        assert_eq!(rng1.position(), 1000);
+
+        rng2.seek(1000);
+        assert_eq!(rng2.position(), 1000);
+        assert_eq!(rng1.next_u64(), rng2.next_u64());
+
+        rng1.advance(0x1234_5678_9abc_def0_1234);
+        let pos = rng1.position();
+        rng2.seek(pos);
+        assert_eq!(rng1.next_u64(), rng2.next_u64());
+
+        // Seek backwards, wrapping around the period
+        let mut rng3 = XorShiftRng::from_seed(seed);
+        rng2.seek(1);
+        rng3.next_u32();
+        assert_eq!(rng2.next_u64(), rng3.next_u64());
+
+        // The period is 2^128 - 1
+        let mut rng4 = SeekableXorShiftRng::from_seed(seed);
+        rng4.advance(!0 - 1);
+        assert_eq!(rng4.position(), !0 - 1);
+        rng4.advance(1);
+        assert_eq!(rng4.position(), 0);
+        let mut rng5 = XorShiftRng::from_seed(seed);
+        assert_eq!(rng4.next_u64(), rng5.next_u64());
+    }
+
     #[cfg(feature="serde1")]
     #[test]
     fn test_xorshift_serde() {
@@ -204,4 +483,62 @@ mod tests {
             assert_eq!(rng.next_u64(), deserialized.next_u64());
         }
     }
+
+    #[cfg(feature="serde1")]
+    #[test]
+    fn test_xorshift_serde_0_1_0() {
+        use bincode;
+
+        // A state serialized by version 0.1.0, followed by another value
+        let buf = [1,0,0,0, 2,0,0,0, 3,0,0,0, 4,0,0,0, 0xef,0xbe,0xad,0xde];
+        let (mut deserialized, next): (XorShiftRng, u32) =
+            bincode::deserialize(&buf).expect("Could not deserialize");
+        assert_eq!(next, 0xdeadbeef);
+
+        let mut rng = XorShiftRng::from_seed([1,0,0,0, 2,0,0,0, 3,0,0,0, 4,0,0,0]);
+        for _ in 0..16 {
+            assert_eq!(rng.next_u64(), deserialized.next_u64());
+        }
+        assert_eq!(bincode::serialize(&rng).unwrap().len(), 16);
+    }
+
+    #[test]
+    fn test_xorshift_state_bytes() {
+        use rand_core::state::StateBytes;
+        use rand_core::ErrorKind;
+
+        let mut rng = XorShiftRng::from_seed([1; 16]);
+        rng.next_u32();
+
+        let mut bytes = [0u8; 28];
+        rng.write_state_bytes(&mut bytes);
+        let mut restored = XorShiftRng::from_state_bytes(&bytes).unwrap();
+        for _ in 0..100 {
+            assert_eq!(rng.next_u32(), restored.next_u32());
+        }
+
+        for b in bytes[12..28].iter_mut() {
+            *b = 0;
+        }
+        let err = XorShiftRng::from_state_bytes(&bytes).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Unavailable);
+    }
+
+    #[cfg(rust_1_26)]
+    #[test]
+    fn test_seekable_xorshift_state_bytes() {
+        use rand_core::state::StateBytes;
+        use rand_core::SeekableRng;
+
+        let mut rng = SeekableXorShiftRng::from_seed([1; 16]);
+        rng.advance(0x1_0000_0000_0000_0005);
+
+        let mut bytes = [0u8; 44];
+        rng.write_state_bytes(&mut bytes);
+        let mut restored = SeekableXorShiftRng::from_state_bytes(&bytes).unwrap();
+        assert_eq!(restored.position(), 0x1_0000_0000_0000_0005);
+        for _ in 0..100 {
+            assert_eq!(rng.next_u32(), restored.next_u32());
+        }
+    }
 }
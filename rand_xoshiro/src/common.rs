@@ -0,0 +1,47 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Helpers shared by all generators
+
+/// Jump ahead using the given polynomial.
+///
+/// The jump polynomial is given as a list of words, least significant bit
+/// first. For every set bit the current state is added (xor-ed) into an
+/// accumulator, after which the generator is stepped once; the accumulator
+/// becomes the new state. This is the procedure used by the reference
+/// implementations, so the polynomials can be copied from there.
+///
+/// `$self` must have a field `s`, an array of `$ty` holding the state.
+macro_rules! impl_jump {
+    (u32, $self:expr, [$($jump:expr),*]) => {
+        impl_jump!(@inner u32, next_u32, $self, [$($jump),*])
+    };
+    (u64, $self:expr, [$($jump:expr),*]) => {
+        impl_jump!(@inner u64, next_u64, $self, [$($jump),*])
+    };
+    (@inner $ty:ident, $next:ident, $self:expr, [$($jump:expr),*]) => {{
+        const JUMP: &'static [$ty] = &[$($jump),*];
+        let mut s = $self.s;
+        for x in s.iter_mut() {
+            *x = 0;
+        }
+        for &j in JUMP {
+            for b in 0..(::core::mem::size_of::<$ty>() * 8) {
+                if j & (1 << b) != 0 {
+                    for (x, y) in s.iter_mut().zip($self.s.iter()) {
+                        *x ^= *y;
+                    }
+                }
+                $self.$next();
+            }
+        }
+        $self.s = s;
+    }};
+}
@@ -0,0 +1,107 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The xoshiro and xoroshiro random number generators.
+//!
+//! This crate implements a selection of the generators designed by David
+//! Blackman and Sebastiano Vigna[^1]. None of them are suitable for
+//! cryptographic purposes.
+//!
+//! | name | state | output | jump | long jump |
+//! |------|-------|--------|------|-----------|
+//! | [`Xoshiro256StarStar`] | 256 bits | `u64` | 2<sup>128</sup> | 2<sup>192</sup> |
+//! | [`Xoshiro256Plus`] | 256 bits | `u64` | 2<sup>128</sup> | 2<sup>192</sup> |
+//! | [`Xoroshiro128StarStar`] | 128 bits | `u64` | 2<sup>64</sup> | 2<sup>96</sup> |
+//! | [`Xoshiro128StarStar`] | 128 bits | `u32` | 2<sup>64</sup> | 2<sup>96</sup> |
+//! | [`Xoshiro128PlusPlus`] | 128 bits | `u32` | 2<sup>64</sup> | 2<sup>96</sup> |
+//! | [`Xoroshiro64Star`] | 64 bits | `u32` | 2<sup>32</sup> | 2<sup>48</sup> |
+//!
+//! The `**` and `++` scramblers produce output of full quality. The `+` and
+//! `*` scramblers are slightly faster, but the lowest bits of their output
+//! have low linear complexity; they are recommended for generating floating
+//! point numbers, which only use the upper bits.
+//!
+//! The crate also provides [`SplitMix64`], a fast generator with 64 bits of
+//! state, which the authors recommend for seeding the other generators. It is
+//! the algorithm used by `SeedableRng::seed_from_u64`.
+//!
+//! # Parallel streams
+//!
+//! Every generator provides `jump` and `long_jump` methods, which advance the
+//! state by the number of steps given in the table above, without generating
+//! the intermediate output. Starting from a single seeded generator, `jump`
+//! can be used to hand out up to 2<sup>64</sup> non-overlapping sub-streams
+//! (for the 256-bit generators), each long enough for any practical
+//! computation; `long_jump` can be used to create a few distant starting
+//! points, each of which can again be split with `jump`:
+//!
+//! ```
+//! # extern crate rand_core;
+//! # extern crate rand_xoshiro;
+//! # fn main() {
+//! use rand_core::SeedableRng;
+//! use rand_xoshiro::Xoshiro256StarStar;
+//!
+//! let mut rng = Xoshiro256StarStar::from_seed([42; 32]);
+//! let mut streams = Vec::new();
+//! for _ in 0..8 {
+//!     streams.push(rng.clone());
+//!     rng.jump();
+//! }
+//! # }
+//! ```
+//!
+//! [^1]: D. Blackman and S. Vigna, [*Scrambled Linear Pseudorandom Number
+//!       Generators*](http://vigna.di.unimi.it/ftp/papers/ScrambledLinear.pdf)
+//!
+//! [`Xoshiro256StarStar`]: struct.Xoshiro256StarStar.html
+//! [`Xoshiro256Plus`]: struct.Xoshiro256Plus.html
+//! [`Xoroshiro128StarStar`]: struct.Xoroshiro128StarStar.html
+//! [`Xoshiro128StarStar`]: struct.Xoshiro128StarStar.html
+//! [`Xoshiro128PlusPlus`]: struct.Xoshiro128PlusPlus.html
+//! [`Xoroshiro64Star`]: struct.Xoroshiro64Star.html
+//! [`SplitMix64`]: struct.SplitMix64.html
+
+#![doc(html_logo_url = "https://www.rust-lang.org/logos/rust-logo-128x128-blk.png",
+       html_favicon_url = "https://www.rust-lang.org/favicon.ico",
+       html_root_url = "https://docs.rs/rand_xoshiro/0.1.0")]
+
+#![deny(missing_docs)]
+#![deny(missing_debug_implementations)]
+#![doc(test(attr(allow(unused_variables), deny(warnings))))]
+
+#![cfg_attr(not(all(feature="serde1", test)), no_std)]
+
+extern crate rand_core;
+
+#[cfg(feature="serde1")] extern crate serde;
+#[cfg(feature="serde1")] #[macro_use] extern crate serde_derive;
+
+// To test serialization we need bincode and the standard library
+#[cfg(all(feature="serde1", test))] extern crate bincode;
+#[cfg(all(feature="serde1", test))] extern crate std as core;
+
+#[macro_use]
+mod common;
+mod xoshiro128starstar;
+mod xoshiro128plusplus;
+mod xoshiro256starstar;
+mod xoshiro256plus;
+mod xoroshiro128starstar;
+mod xoroshiro64star;
+mod splitmix64;
+
+pub use self::xoshiro128starstar::Xoshiro128StarStar;
+pub use self::xoshiro128plusplus::Xoshiro128PlusPlus;
+pub use self::xoshiro256starstar::Xoshiro256StarStar;
+pub use self::xoshiro256plus::Xoshiro256Plus;
+pub use self::xoroshiro128starstar::Xoroshiro128StarStar;
+pub use self::xoroshiro64star::Xoroshiro64Star;
+pub use self::splitmix64::SplitMix64;
@@ -0,0 +1,146 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The splitmix64 random number generator
+
+use core::fmt;
+use rand_core::{RngCore, SeedableRng, Error, le, impls};
+
+/// A splitmix64 random number generator.
+///
+/// The splitmix64 algorithm is not suitable for cryptographic purposes, but is
+/// very fast and has a 64-bit state. Unlike the xoshiro generators, any
+/// state, including 0, is valid. Its main use is the expansion of a single
+/// `u64` into a larger seed, which is how `SeedableRng::seed_from_u64` is
+/// implemented; the authors of xoshiro recommend seeding their generators
+/// this way.
+///
+/// The period is 2<sup>64</sup>.
+///
+/// The algorithm used here is translated from [the `splitmix64.c`
+/// reference source code](http://xoshiro.di.unimi.it/splitmix64.c) by
+/// Sebastiano Vigna.
+#[derive(Clone)]
+#[cfg_attr(feature="serde1", derive(Serialize,Deserialize))]
+pub struct SplitMix64 {
+    x: u64,
+}
+
+const PHI: u64 = 0x9e3779b97f4a7c15;
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for SplitMix64 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SplitMix64 {{}}")
+    }
+}
+
+impl SeedableRng for SplitMix64 {
+    type Seed = [u8; 8];
+
+    /// Create a new `SplitMix64`.
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut state = [0u64; 1];
+        le::read_u64_into(&seed, &mut state);
+        SplitMix64 { x: state[0] }
+    }
+
+    /// Seed a `SplitMix64` from a `u64`.
+    ///
+    /// Unlike for other generators, this uses the `u64` directly as state, so
+    /// the output matches the reference implementation initialised with
+    /// `seed`.
+    fn seed_from_u64(seed: u64) -> Self {
+        SplitMix64 { x: seed }
+    }
+}
+
+impl RngCore for SplitMix64 {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.x = self.x.wrapping_add(PHI);
+        let mut z = self.x;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::rand_core::{RngCore, SeedableRng};
+    use super::SplitMix64;
+
+    #[test]
+    fn test_splitmix64_true_values() {
+        // Values generated with the reference implementation.
+        let mut rng = SplitMix64::seed_from_u64(1477776061723855037);
+        let mut results = [0u64; 4];
+        for i in results.iter_mut() { *i = rng.next_u64(); }
+        let expected: [u64; 4] = [
+            1985237415132408290, 2979275885539914483, 13511426838097143398,
+            8488337342461049707];
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_splitmix64_seed_from_u64() {
+        // `SeedableRng::seed_from_u64` expands the seed using splitmix64,
+        // writing its output in little-endian order.
+        let mut rng = SplitMix64::seed_from_u64(42);
+        let mut expected = [0u8; 32];
+        rng.fill_bytes(&mut expected);
+
+        struct Seed32([u8; 32]);
+        impl SeedableRng for Seed32 {
+            type Seed = [u8; 32];
+            fn from_seed(seed: Self::Seed) -> Self { Seed32(seed) }
+        }
+        assert_eq!(Seed32::seed_from_u64(42).0, expected);
+    }
+
+    #[cfg(feature="serde1")]
+    #[test]
+    fn test_splitmix64_serde() {
+        use bincode;
+        use std::io::{BufWriter, BufReader};
+
+        let mut rng = SplitMix64::seed_from_u64(7);
+
+        let buf: Vec<u8> = Vec::new();
+        let mut buf = BufWriter::new(buf);
+        bincode::serialize_into(&mut buf, &rng).expect("Could not serialize");
+
+        let buf = buf.into_inner().unwrap();
+        let mut read = BufReader::new(&buf[..]);
+        let mut deserialized: SplitMix64 =
+            bincode::deserialize_from(&mut read).expect("Could not deserialize");
+
+        assert_eq!(rng.x, deserialized.x);
+        for _ in 0..16 {
+            assert_eq!(rng.next_u64(), deserialized.next_u64());
+        }
+    }
+}
@@ -0,0 +1,191 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The xoroshiro128** random number generator
+
+use core::fmt;
+use rand_core::{RngCore, SeedableRng, Error, le, impls};
+
+/// A xoroshiro128** random number generator.
+///
+/// The xoroshiro128** algorithm is not suitable for cryptographic purposes,
+/// but is very fast and has excellent statistical properties. Its state is
+/// half the size of [`Xoshiro256StarStar`], which makes it a good choice when
+/// many generators are needed at once, but the shorter period makes it less
+/// suitable for massively parallel computations.
+///
+/// The state is 128 bits, the period is 2<sup>128</sup> - 1. [`jump`] and
+/// [`long_jump`] advance the state by 2<sup>64</sup> and 2<sup>96</sup>
+/// steps respectively.
+///
+/// The algorithm used here is translated from [the `xoroshiro128starstar.c`
+/// reference source code](http://xoshiro.di.unimi.it/xoroshiro128starstar.c) by
+/// David Blackman and Sebastiano Vigna.
+///
+/// [`jump`]: #method.jump
+/// [`long_jump`]: #method.long_jump
+/// [`Xoshiro256StarStar`]: struct.Xoshiro256StarStar.html
+#[derive(Clone)]
+#[cfg_attr(feature="serde1", derive(Serialize,Deserialize))]
+pub struct Xoroshiro128StarStar {
+    s: [u64; 2],
+}
+
+impl Xoroshiro128StarStar {
+    /// Jump forward, equivalently to 2<sup>64</sup> calls to `next_u64()`.
+    ///
+    /// This can be used to generate 2<sup>64</sup> non-overlapping
+    /// subsequences for parallel computations.
+    pub fn jump(&mut self) {
+        impl_jump!(u64, self, [
+            0xdf900294d8f554a5, 0x170865df4b3201fc
+        ]);
+    }
+
+    /// Jump forward, equivalently to 2<sup>96</sup> calls to `next_u64()`.
+    ///
+    /// This can be used to generate 2<sup>32</sup> starting points, from each
+    /// of which `jump()` will generate 2<sup>32</sup> non-overlapping
+    /// subsequences for parallel distributed computations.
+    pub fn long_jump(&mut self) {
+        impl_jump!(u64, self, [
+            0xd2a98b26625eee7b, 0xdddf9b1090aa7ac1
+        ]);
+    }
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Xoroshiro128StarStar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Xoroshiro128StarStar {{}}")
+    }
+}
+
+impl SeedableRng for Xoroshiro128StarStar {
+    type Seed = [u8; 16];
+
+    /// Create a new `Xoroshiro128StarStar`. If `seed` is entirely 0, it will be
+    /// mapped to a different seed.
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut s = [0u64; 2];
+        le::read_u64_into(&seed, &mut s);
+
+        // An all-zero state is a fixed point of the generator; we cannot
+        // return an error, hence we seed from 0 via SplitMix64 instead, the
+        // seeding method recommended by the authors of this generator.
+        if s.iter().all(|&x| x == 0) {
+            return Self::seed_from_u64(0);
+        }
+
+        Xoroshiro128StarStar { s }
+    }
+}
+
+impl RngCore for Xoroshiro128StarStar {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let r = self.s[0].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+
+        let s0 = self.s[0];
+        let s1 = self.s[1] ^ s0;
+        self.s[0] = s0.rotate_left(24) ^ s1 ^ (s1 << 16);
+        self.s[1] = s1.rotate_left(37);
+
+        r
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::rand_core::{RngCore, SeedableRng};
+    use super::Xoroshiro128StarStar;
+
+    #[test]
+    fn test_xoroshiro128starstar_true_values() {
+        // Values generated with the reference implementation.
+        let mut rng = Xoroshiro128StarStar { s: [1, 2] };
+        let mut results = [0u64; 10];
+        for i in results.iter_mut() { *i = rng.next_u64(); }
+        let expected: [u64; 10] = [
+            5760, 97769243520, 9706862127477703552, 9223447511460779954,
+            8358291023205304566, 15695619998649302768, 8517900938696309774,
+            16586480348202605369, 6959129367028440372, 16822147227405758281];
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_xoroshiro128starstar_jump() {
+        // Values generated with the reference implementation.
+        let mut rng = Xoroshiro128StarStar { s: [1, 2] };
+        rng.jump();
+        assert_eq!(rng.s, [7420758724034209717, 9442990532527272306]);
+
+        let mut rng = Xoroshiro128StarStar { s: [1, 2] };
+        rng.long_jump();
+        assert_eq!(rng.s, [4387707342976528954, 3072119776036644419]);
+    }
+
+    #[test]
+    fn test_xoroshiro128starstar_zero_seed() {
+        // An all-zero seed must not produce an all-zero state.
+        let mut rng = Xoroshiro128StarStar::from_seed([0; 16]);
+        let a = rng.next_u64();
+        let b = rng.next_u64();
+        assert!(a != 0);
+        assert!(b != a);
+    }
+
+    #[test]
+    fn test_xoroshiro128starstar_clone() {
+        let mut rng1 = Xoroshiro128StarStar::from_seed([7; 16]);
+        let mut rng2 = rng1.clone();
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+    }
+
+    #[cfg(feature="serde1")]
+    #[test]
+    fn test_xoroshiro128starstar_serde() {
+        use bincode;
+        use std::io::{BufWriter, BufReader};
+
+        let mut rng = Xoroshiro128StarStar::from_seed([7; 16]);
+
+        let buf: Vec<u8> = Vec::new();
+        let mut buf = BufWriter::new(buf);
+        bincode::serialize_into(&mut buf, &rng).expect("Could not serialize");
+
+        let buf = buf.into_inner().unwrap();
+        let mut read = BufReader::new(&buf[..]);
+        let mut deserialized: Xoroshiro128StarStar =
+            bincode::deserialize_from(&mut read).expect("Could not deserialize");
+
+        assert_eq!(rng.s, deserialized.s);
+        for _ in 0..16 {
+            assert_eq!(rng.next_u64(), deserialized.next_u64());
+        }
+    }
+}
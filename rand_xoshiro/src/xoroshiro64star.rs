@@ -0,0 +1,193 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The xoroshiro64* random number generator
+
+use core::fmt;
+use rand_core::{RngCore, SeedableRng, Error, le, impls};
+
+/// A xoroshiro64* random number generator.
+///
+/// The xoroshiro64* algorithm is not suitable for cryptographic purposes, but
+/// is very fast and uses only 64 bits of state. The lowest bits of its
+/// output have low linear complexity; it is recommended for generating 32-bit
+/// floating point numbers. The short period makes it unsuitable for
+/// generating many parallel streams.
+///
+/// The state is 64 bits, the period is 2<sup>64</sup> - 1. [`jump`] and
+/// [`long_jump`] advance the state by 2<sup>32</sup> and 2<sup>48</sup>
+/// steps respectively.
+///
+/// The reference implementation does not provide jump functions for this
+/// generator; the jump polynomials used here were computed from its
+/// characteristic polynomial in the same way as for the other generators.
+///
+/// The algorithm used here is translated from [the `xoroshiro64star.c`
+/// reference source code](http://xoshiro.di.unimi.it/xoroshiro64star.c) by
+/// David Blackman and Sebastiano Vigna.
+///
+/// [`jump`]: #method.jump
+/// [`long_jump`]: #method.long_jump
+#[derive(Clone)]
+#[cfg_attr(feature="serde1", derive(Serialize,Deserialize))]
+pub struct Xoroshiro64Star {
+    s: [u32; 2],
+}
+
+impl Xoroshiro64Star {
+    /// Jump forward, equivalently to 2<sup>32</sup> calls to `next_u32()`.
+    ///
+    /// This can be used to generate 2<sup>32</sup> non-overlapping
+    /// subsequences for parallel computations.
+    pub fn jump(&mut self) {
+        impl_jump!(u32, self, [
+            0x77fcd1a0, 0x4cbf99bd
+        ]);
+    }
+
+    /// Jump forward, equivalently to 2<sup>48</sup> calls to `next_u32()`.
+    ///
+    /// This can be used to generate 2<sup>16</sup> starting points, from each
+    /// of which `jump()` will generate 2<sup>16</sup> non-overlapping
+    /// subsequences for parallel distributed computations.
+    pub fn long_jump(&mut self) {
+        impl_jump!(u32, self, [
+            0x3f1f8b95, 0xb4e7e463
+        ]);
+    }
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Xoroshiro64Star {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Xoroshiro64Star {{}}")
+    }
+}
+
+impl SeedableRng for Xoroshiro64Star {
+    type Seed = [u8; 8];
+
+    /// Create a new `Xoroshiro64Star`. If `seed` is entirely 0, it will be
+    /// mapped to a different seed.
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut s = [0u32; 2];
+        le::read_u32_into(&seed, &mut s);
+
+        // An all-zero state is a fixed point of the generator; we cannot
+        // return an error, hence we seed from 0 via SplitMix64 instead, the
+        // seeding method recommended by the authors of this generator.
+        if s.iter().all(|&x| x == 0) {
+            return Self::seed_from_u64(0);
+        }
+
+        Xoroshiro64Star { s }
+    }
+}
+
+impl RngCore for Xoroshiro64Star {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        let r = self.s[0].wrapping_mul(0x9E3779BB);
+
+        let s0 = self.s[0];
+        let s1 = self.s[1] ^ s0;
+        self.s[0] = s0.rotate_left(26) ^ s1 ^ (s1 << 9);
+        self.s[1] = s1.rotate_left(13);
+
+        r
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_u32(self)
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::rand_core::{RngCore, SeedableRng};
+    use super::Xoroshiro64Star;
+
+    #[test]
+    fn test_xoroshiro64star_true_values() {
+        // Values generated with the reference implementation.
+        let mut rng = Xoroshiro64Star { s: [1, 2] };
+        let mut results = [0u32; 10];
+        for i in results.iter_mut() { *i = rng.next_u32(); }
+        let expected: [u32; 10] = [
+            2654435771, 327208753, 4063491769, 4259754937, 261922412,
+            168123673, 552743735, 1672597395, 1031040050, 2755315674];
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_xoroshiro64star_jump() {
+        // Values generated with the reference implementation.
+        let mut rng = Xoroshiro64Star { s: [1, 2] };
+        rng.jump();
+        assert_eq!(rng.s, [3370103944, 2537896034]);
+
+        let mut rng = Xoroshiro64Star { s: [1, 2] };
+        rng.long_jump();
+        assert_eq!(rng.s, [879734759, 2063398418]);
+    }
+
+    #[test]
+    fn test_xoroshiro64star_zero_seed() {
+        // An all-zero seed must not produce an all-zero state.
+        let mut rng = Xoroshiro64Star::from_seed([0; 8]);
+        let a = rng.next_u64();
+        let b = rng.next_u64();
+        assert!(a != 0);
+        assert!(b != a);
+    }
+
+    #[test]
+    fn test_xoroshiro64star_clone() {
+        let mut rng1 = Xoroshiro64Star::from_seed([7; 8]);
+        let mut rng2 = rng1.clone();
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+    }
+
+    #[cfg(feature="serde1")]
+    #[test]
+    fn test_xoroshiro64star_serde() {
+        use bincode;
+        use std::io::{BufWriter, BufReader};
+
+        let mut rng = Xoroshiro64Star::from_seed([7; 8]);
+
+        let buf: Vec<u8> = Vec::new();
+        let mut buf = BufWriter::new(buf);
+        bincode::serialize_into(&mut buf, &rng).expect("Could not serialize");
+
+        let buf = buf.into_inner().unwrap();
+        let mut read = BufReader::new(&buf[..]);
+        let mut deserialized: Xoroshiro64Star =
+            bincode::deserialize_from(&mut read).expect("Could not deserialize");
+
+        assert_eq!(rng.s, deserialized.s);
+        for _ in 0..16 {
+            assert_eq!(rng.next_u64(), deserialized.next_u64());
+        }
+    }
+}
@@ -0,0 +1,193 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The xoshiro128** random number generator
+
+use core::fmt;
+use rand_core::{RngCore, SeedableRng, Error, le, impls};
+
+/// A xoshiro128** random number generator.
+///
+/// The xoshiro128** algorithm is not suitable for cryptographic purposes, but
+/// is very fast and has excellent statistical properties. It is the
+/// recommended all-purpose generator of this crate for 32-bit output, in
+/// particular on 32-bit platforms.
+///
+/// The state is 128 bits, the period is 2<sup>128</sup> - 1. [`jump`] and
+/// [`long_jump`] advance the state by 2<sup>64</sup> and 2<sup>96</sup>
+/// steps respectively.
+///
+/// The algorithm used here is translated from [the `xoshiro128starstar.c`
+/// reference source code](http://xoshiro.di.unimi.it/xoshiro128starstar.c) by
+/// David Blackman and Sebastiano Vigna.
+///
+/// [`jump`]: #method.jump
+/// [`long_jump`]: #method.long_jump
+#[derive(Clone)]
+#[cfg_attr(feature="serde1", derive(Serialize,Deserialize))]
+pub struct Xoshiro128StarStar {
+    s: [u32; 4],
+}
+
+impl Xoshiro128StarStar {
+    /// Jump forward, equivalently to 2<sup>64</sup> calls to `next_u32()`.
+    ///
+    /// This can be used to generate 2<sup>64</sup> non-overlapping
+    /// subsequences for parallel computations.
+    pub fn jump(&mut self) {
+        impl_jump!(u32, self, [
+            0x8764000b, 0xf542d2d3,
+            0x6fa035c3, 0x77f2db5b
+        ]);
+    }
+
+    /// Jump forward, equivalently to 2<sup>96</sup> calls to `next_u32()`.
+    ///
+    /// This can be used to generate 2<sup>32</sup> starting points, from each
+    /// of which `jump()` will generate 2<sup>32</sup> non-overlapping
+    /// subsequences for parallel distributed computations.
+    pub fn long_jump(&mut self) {
+        impl_jump!(u32, self, [
+            0xb523952e, 0x0b6f099f,
+            0xccf5a0ef, 0x1c580662
+        ]);
+    }
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Xoshiro128StarStar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Xoshiro128StarStar {{}}")
+    }
+}
+
+impl SeedableRng for Xoshiro128StarStar {
+    type Seed = [u8; 16];
+
+    /// Create a new `Xoshiro128StarStar`. If `seed` is entirely 0, it will be
+    /// mapped to a different seed.
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut s = [0u32; 4];
+        le::read_u32_into(&seed, &mut s);
+
+        // An all-zero state is a fixed point of the generator; we cannot
+        // return an error, hence we seed from 0 via SplitMix64 instead, the
+        // seeding method recommended by the authors of this generator.
+        if s.iter().all(|&x| x == 0) {
+            return Self::seed_from_u64(0);
+        }
+
+        Xoshiro128StarStar { s }
+    }
+}
+
+impl RngCore for Xoshiro128StarStar {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        let result = self.s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+
+        let t = self.s[1] << 9;
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(11);
+
+        result
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_u32(self)
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::rand_core::{RngCore, SeedableRng};
+    use super::Xoshiro128StarStar;
+
+    #[test]
+    fn test_xoshiro128starstar_true_values() {
+        // Values generated with the reference implementation.
+        let mut rng = Xoshiro128StarStar { s: [1, 2, 3, 4] };
+        let mut results = [0u32; 10];
+        for i in results.iter_mut() { *i = rng.next_u32(); }
+        let expected: [u32; 10] = [
+            11520, 0, 5927040, 70819200, 2031721883, 1637235492, 1287239034,
+            3734860849, 3729100597, 4258142804];
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_xoshiro128starstar_jump() {
+        // Values generated with the reference implementation.
+        let mut rng = Xoshiro128StarStar { s: [1, 2, 3, 4] };
+        rng.jump();
+        assert_eq!(rng.s, [2843103750, 2038079848, 1533207345, 44816753]);
+
+        let mut rng = Xoshiro128StarStar { s: [1, 2, 3, 4] };
+        rng.long_jump();
+        assert_eq!(rng.s, [1611968294, 2125834322, 966769569, 3193880526]);
+    }
+
+    #[test]
+    fn test_xoshiro128starstar_zero_seed() {
+        // An all-zero seed must not produce an all-zero state.
+        let mut rng = Xoshiro128StarStar::from_seed([0; 16]);
+        let a = rng.next_u64();
+        let b = rng.next_u64();
+        assert!(a != 0);
+        assert!(b != a);
+    }
+
+    #[test]
+    fn test_xoshiro128starstar_clone() {
+        let mut rng1 = Xoshiro128StarStar::from_seed([7; 16]);
+        let mut rng2 = rng1.clone();
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+    }
+
+    #[cfg(feature="serde1")]
+    #[test]
+    fn test_xoshiro128starstar_serde() {
+        use bincode;
+        use std::io::{BufWriter, BufReader};
+
+        let mut rng = Xoshiro128StarStar::from_seed([7; 16]);
+
+        let buf: Vec<u8> = Vec::new();
+        let mut buf = BufWriter::new(buf);
+        bincode::serialize_into(&mut buf, &rng).expect("Could not serialize");
+
+        let buf = buf.into_inner().unwrap();
+        let mut read = BufReader::new(&buf[..]);
+        let mut deserialized: Xoshiro128StarStar =
+            bincode::deserialize_from(&mut read).expect("Could not deserialize");
+
+        assert_eq!(rng.s, deserialized.s);
+        for _ in 0..16 {
+            assert_eq!(rng.next_u64(), deserialized.next_u64());
+        }
+    }
+}
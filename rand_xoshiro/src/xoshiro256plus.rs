@@ -0,0 +1,198 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The xoshiro256+ random number generator
+
+use core::fmt;
+use rand_core::{RngCore, SeedableRng, Error, le, impls};
+
+/// A xoshiro256+ random number generator.
+///
+/// The xoshiro256+ algorithm is not suitable for cryptographic purposes, but
+/// is very fast and has good statistical properties, besides a low linear
+/// complexity in the lowest bits. It is slightly faster than
+/// [`Xoshiro256StarStar`] and is recommended for generating 64-bit floating
+/// point numbers, which only use the upper bits of the output.
+///
+/// The state is 256 bits, the period is 2<sup>256</sup> - 1. [`jump`] and
+/// [`long_jump`] advance the state by 2<sup>128</sup> and 2<sup>192</sup>
+/// steps respectively.
+///
+/// The algorithm used here is translated from [the `xoshiro256plus.c`
+/// reference source code](http://xoshiro.di.unimi.it/xoshiro256plus.c) by
+/// David Blackman and Sebastiano Vigna.
+///
+/// [`jump`]: #method.jump
+/// [`long_jump`]: #method.long_jump
+/// [`Xoshiro256StarStar`]: struct.Xoshiro256StarStar.html
+#[derive(Clone)]
+#[cfg_attr(feature="serde1", derive(Serialize,Deserialize))]
+pub struct Xoshiro256Plus {
+    s: [u64; 4],
+}
+
+impl Xoshiro256Plus {
+    /// Jump forward, equivalently to 2<sup>128</sup> calls to `next_u64()`.
+    ///
+    /// This can be used to generate 2<sup>128</sup> non-overlapping
+    /// subsequences for parallel computations.
+    pub fn jump(&mut self) {
+        impl_jump!(u64, self, [
+            0x180ec6d33cfd0aba, 0xd5a61266f0c9392c,
+            0xa9582618e03fc9aa, 0x39abdc4529b1661c
+        ]);
+    }
+
+    /// Jump forward, equivalently to 2<sup>192</sup> calls to `next_u64()`.
+    ///
+    /// This can be used to generate 2<sup>64</sup> starting points, from each
+    /// of which `jump()` will generate 2<sup>64</sup> non-overlapping
+    /// subsequences for parallel distributed computations.
+    pub fn long_jump(&mut self) {
+        impl_jump!(u64, self, [
+            0x76e15d3efefdcbbf, 0xc5004e441c522fb3,
+            0x77710069854ee241, 0x39109bb02acbe635
+        ]);
+    }
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Xoshiro256Plus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Xoshiro256Plus {{}}")
+    }
+}
+
+impl SeedableRng for Xoshiro256Plus {
+    type Seed = [u8; 32];
+
+    /// Create a new `Xoshiro256Plus`. If `seed` is entirely 0, it will be
+    /// mapped to a different seed.
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut s = [0u64; 4];
+        le::read_u64_into(&seed, &mut s);
+
+        // An all-zero state is a fixed point of the generator; we cannot
+        // return an error, hence we seed from 0 via SplitMix64 instead, the
+        // seeding method recommended by the authors of this generator.
+        if s.iter().all(|&x| x == 0) {
+            return Self::seed_from_u64(0);
+        }
+
+        Xoshiro256Plus { s }
+    }
+}
+
+impl RngCore for Xoshiro256Plus {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let result = self.s[0].wrapping_add(self.s[3]);
+
+        let t = self.s[1] << 17;
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(45);
+
+        result
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::rand_core::{RngCore, SeedableRng};
+    use super::Xoshiro256Plus;
+
+    #[test]
+    fn test_xoshiro256plus_true_values() {
+        // Values generated with the reference implementation.
+        let mut rng = Xoshiro256Plus { s: [1, 2, 3, 4] };
+        let mut results = [0u64; 10];
+        for i in results.iter_mut() { *i = rng.next_u64(); }
+        let expected: [u64; 10] = [
+            5, 211106232532999, 211106635186183, 9223759065350669058,
+            9250833439874351877, 13862484359527728515, 2346507365006083650,
+            1168864526675804870, 34095955243042024, 3466914240207415127];
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_xoshiro256plus_jump() {
+        // Values generated with the reference implementation.
+        let mut rng = Xoshiro256Plus { s: [1, 2, 3, 4] };
+        rng.jump();
+        assert_eq!(rng.s, [10122426448480695249, 8079205330032121950,
+                           7289065458748526725, 9477464255293849680]);
+
+        let mut rng = Xoshiro256Plus { s: [1, 2, 3, 4] };
+        rng.long_jump();
+        assert_eq!(rng.s, [678511610814637056, 15850499779492529430,
+                           6002989639035333134, 3559352929785830385]);
+    }
+
+    #[test]
+    fn test_xoshiro256plus_zero_seed() {
+        // An all-zero seed must not produce an all-zero state.
+        let mut rng = Xoshiro256Plus::from_seed([0; 32]);
+        let a = rng.next_u64();
+        let b = rng.next_u64();
+        assert!(a != 0);
+        assert!(b != a);
+    }
+
+    #[test]
+    fn test_xoshiro256plus_clone() {
+        let mut rng1 = Xoshiro256Plus::from_seed([7; 32]);
+        let mut rng2 = rng1.clone();
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+    }
+
+    #[cfg(feature="serde1")]
+    #[test]
+    fn test_xoshiro256plus_serde() {
+        use bincode;
+        use std::io::{BufWriter, BufReader};
+
+        let mut rng = Xoshiro256Plus::from_seed([7; 32]);
+
+        let buf: Vec<u8> = Vec::new();
+        let mut buf = BufWriter::new(buf);
+        bincode::serialize_into(&mut buf, &rng).expect("Could not serialize");
+
+        let buf = buf.into_inner().unwrap();
+        let mut read = BufReader::new(&buf[..]);
+        let mut deserialized: Xoshiro256Plus =
+            bincode::deserialize_from(&mut read).expect("Could not deserialize");
+
+        assert_eq!(rng.s, deserialized.s);
+        for _ in 0..16 {
+            assert_eq!(rng.next_u64(), deserialized.next_u64());
+        }
+    }
+}
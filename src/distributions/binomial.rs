@@ -11,14 +11,20 @@
 //! The binomial distribution.
 
 use Rng;
-use distributions::{Distribution, Bernoulli, Cauchy};
-use distributions::utils::log_gamma;
+use distributions::{Distribution, Standard};
+use distributions::utils::stirling_correction;
 
 /// The binomial distribution `Binomial(n, p)`.
 ///
 /// This distribution has density function:
 /// `f(k) = n!/(k! (n-k)!) p^k (1-p)^(n-k)` for `k >= 0`.
 ///
+/// Sampling uses inversion when `n p` is small, and the BTPE algorithm of
+/// Kachitvichyanukul and Schmeiser otherwise. Both take constant expected
+/// time; all constants depending on `n` and `p` are computed by
+/// [`Binomial::new`], so it pays to keep the distribution around when
+/// sampling repeatedly with the same parameters.
+///
 /// # Example
 ///
 /// ```
@@ -28,12 +34,64 @@ use distributions::utils::log_gamma;
 /// let v = bin.sample(&mut rand::thread_rng());
 /// println!("{} is from a binomial distribution", v);
 /// ```
+///
+/// # Reference
+///
+/// V. Kachitvichyanukul and B. W. Schmeiser, *Binomial random variate
+/// generation*, Communications of the ACM 31(2), 1988.
+///
+/// [`Binomial::new`]: struct.Binomial.html#method.new
 #[derive(Clone, Copy, Debug)]
 pub struct Binomial {
     /// Number of trials.
     n: u64,
-    /// Probability of success.
-    p: f64,
+    /// Whether the result is inverted, because `p > 0.5`.
+    flip: bool,
+    method: Method,
+}
+
+/// Use inversion below this value of `n p`, and BTPE above it.
+const INVERSION_THRESHOLD: f64 = 10.0;
+
+/// Restart inversion above this value, to bound the time spent in the tail.
+/// With `n p < 10`, this is reached with probability below `1e-60`.
+const INVERSION_MAX_X: u64 = 110;
+
+/// Evaluate `f(y)` exactly in BTPE when `|y - m|` is at most this.
+const SQUEEZE_THRESHOLD: f64 = 20.0;
+
+#[derive(Clone, Copy, Debug)]
+enum Method {
+    Inversion {
+        /// `p / q`
+        s: f64,
+        /// `(n + 1) p / q`
+        a: f64,
+        /// `f(0) = q^n`
+        r: f64,
+    },
+    Btpe {
+        /// `p / q`
+        s: f64,
+        /// `(n + 1) p / q`
+        a: f64,
+        /// `n p q`
+        npq: f64,
+        /// Mode.
+        m: f64,
+        /// Half-width of the central triangle.
+        p1: f64,
+        x_m: f64,
+        x_l: f64,
+        x_r: f64,
+        /// Height of the parallelograms on top of the triangle.
+        c: f64,
+        lambda_l: f64,
+        lambda_r: f64,
+        p2: f64,
+        p3: f64,
+        p4: f64,
+    },
 }
 
 impl Binomial {
@@ -44,87 +102,165 @@ impl Binomial {
     pub fn new(n: u64, p: f64) -> Binomial {
         assert!(p >= 0.0, "Binomial::new called with p < 0");
         assert!(p <= 1.0, "Binomial::new called with p > 1");
-        Binomial { n, p }
+
+        // The binomial distribution is symmetrical with respect to
+        // `p -> 1-p, k -> n-k`. Switch `p` so that it is at most 0.5, and
+        // invert the result when sampling.
+        let flip = p > 0.5;
+        let p = if flip { 1.0 - p } else { p };
+        let q = 1.0 - p;
+        let float_n = n as f64;
+        let np = float_n * p;
+        let s = p / q;
+        let a = (float_n + 1.0) * s;
+
+        let method = if np < INVERSION_THRESHOLD {
+            let r = (float_n * (-p).ln_1p()).exp();
+            Method::Inversion { s, a, r }
+        } else {
+            let npq = np * q;
+            let f_m = np + p;
+            let m = f_m.floor();
+            let p1 = (2.195 * npq.sqrt() - 4.6 * q).floor() + 0.5;
+            let x_m = m + 0.5;
+            let x_l = x_m - p1;
+            let x_r = x_m + p1;
+            let c = 0.134 + 20.5 / (15.3 + m);
+            let lambda_l = {
+                let a = (f_m - x_l) / (f_m - x_l * p);
+                a * (1.0 + 0.5 * a)
+            };
+            let lambda_r = {
+                let a = (x_r - f_m) / (x_r * q);
+                a * (1.0 + 0.5 * a)
+            };
+            let p2 = p1 * (1.0 + 2.0 * c);
+            let p3 = p2 + c / lambda_l;
+            let p4 = p3 + c / lambda_r;
+            Method::Btpe {
+                s, a, npq, m, p1, x_m, x_l, x_r, c, lambda_l, lambda_r,
+                p2, p3, p4,
+            }
+        };
+        Binomial { n, flip, method }
     }
 }
 
 impl Distribution<u64> for Binomial {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
-        // Handle these values directly.
-        if self.p == 0.0 {
-            return 0;
-        } else if self.p == 1.0 {
-            return self.n;
-        }
-        
-        // For low n, it is faster to sample directly. For both methods,
-        // performance is independent of p. On Intel Haswell CPU this method
-        // appears to be faster for approx n < 300.
-        if self.n < 300 {
-            let mut result = 0;
-            let d = Bernoulli::new(self.p);
-            for _ in 0 .. self.n {
-                result += rng.sample(d) as u32;
-            }
-            return result as u64;
-        }
-        
-        // binomial distribution is symmetrical with respect to p -> 1-p, k -> n-k
-        // switch p so that it is less than 0.5 - this allows for lower expected values
-        // we will just invert the result at the end
-        let p = if self.p <= 0.5 {
-            self.p
-        } else {
-            1.0 - self.p
-        };
-
-        // prepare some cached values
-        let float_n = self.n as f64;
-        let ln_fact_n = log_gamma(float_n + 1.0);
-        let pc = 1.0 - p;
-        let log_p = p.ln();
-        let log_pc = pc.ln();
-        let expected = self.n as f64 * p;
-        let sq = (expected * (2.0 * pc)).sqrt();
-
-        let mut lresult;
-
-        // we use the Cauchy distribution as the comparison distribution
-        // f(x) ~ 1/(1+x^2)
-        let cauchy = Cauchy::new(0.0, 1.0);
-        loop {
-            let mut comp_dev: f64;
-            loop {
-                // draw from the Cauchy distribution
-                comp_dev = rng.sample(cauchy);
-                // shift the peak of the comparison ditribution
-                lresult = expected + sq * comp_dev;
-                // repeat the drawing until we are in the range of possible values
-                if lresult >= 0.0 && lresult < float_n + 1.0 {
-                    break;
+        let result = match self.method {
+            Method::Inversion { s, a, r } => {
+                // Sequential search, using `f(x) = f(x-1) (a/x - s)`.
+                'outer: loop {
+                    let mut u: f64 = rng.sample(Standard);
+                    let mut f = r;
+                    let mut x = 0;
+                    while u > f {
+                        u -= f;
+                        x += 1;
+                        if x > INVERSION_MAX_X {
+                            continue 'outer;
+                        }
+                        f *= a / x as f64 - s;
+                    }
+                    break x;
                 }
             }
+            Method::Btpe {
+                s, a, npq, m, p1, x_m, x_l, x_r, c, lambda_l, lambda_r,
+                p2, p3, p4,
+            } => {
+                let n = self.n as f64;
+                loop {
+                    let u = rng.sample::<f64, _>(Standard) * p4;
+                    let mut v: f64 = rng.sample(Standard);
+                    let y;
+                    if u <= p1 {
+                        // Triangular region: accept immediately.
+                        break (x_m - p1 * v + u).floor() as u64;
+                    } else if u <= p2 {
+                        // Parallelogram region.
+                        let x = x_l + (u - p1) / c;
+                        v = v * c + 1.0 - (x - x_m).abs() / p1;
+                        if v > 1.0 {
+                            continue;
+                        }
+                        y = x.floor();
+                    } else if u <= p3 {
+                        // Left exponential tail.
+                        y = (x_l + v.ln() / lambda_l).floor();
+                        if y < 0.0 {
+                            continue;
+                        }
+                        v *= (u - p2) * lambda_l;
+                    } else {
+                        // Right exponential tail.
+                        y = (x_r - v.ln() / lambda_r).floor();
+                        if y > n {
+                            continue;
+                        }
+                        v *= (u - p3) * lambda_r;
+                    }
 
-            // the result should be discrete
-            lresult = lresult.floor();
+                    // Accept if `v <= f(y) / f(m)`.
+                    let k = (y - m).abs();
+                    if k <= SQUEEZE_THRESHOLD || k >= 0.5 * npq - 1.0 {
+                        // Evaluate `f(y) / f(m)` with the recurrence
+                        // `f(i) = f(i-1) (a/i - s)`.
+                        let (y, m) = (y as u64, m as u64);
+                        let mut f = 1.0;
+                        if m < y {
+                            for i in (m + 1)..(y + 1) {
+                                f *= a / i as f64 - s;
+                            }
+                        } else {
+                            for i in (y + 1)..(m + 1) {
+                                f /= a / i as f64 - s;
+                            }
+                        }
+                        if v <= f {
+                            break y;
+                        }
+                        continue;
+                    }
 
-            let log_binomial_dist = ln_fact_n - log_gamma(lresult+1.0) -
-                log_gamma(float_n - lresult + 1.0) + lresult*log_p + (float_n - lresult)*log_pc;
-            // this is the binomial probability divided by the comparison probability
-            // we will generate a uniform random value and if it is larger than this,
-            // we interpret it as a value falling out of the distribution and repeat
-            let comparison_coeff = (log_binomial_dist.exp() * sq) * (1.2 * (1.0 + comp_dev*comp_dev));
+                    // Squeeze using upper and lower bounds of `ln f(y)`.
+                    let rho = (k / npq)
+                        * ((k * (k / 3.0 + 0.625) + 1.0 / 6.0) / npq + 0.5);
+                    let t = -0.5 * k * k / npq;
+                    let alpha = v.ln();
+                    if alpha < t - rho {
+                        break y as u64;
+                    }
+                    if alpha > t + rho {
+                        continue;
+                    }
 
-            if comparison_coeff >= rng.gen() {
-                break;
+                    // Final comparison with `ln f(y) - ln f(m)`, using
+                    // Stirling's formula with correction terms. The signs of
+                    // the corrections for `y` differ from the reference,
+                    // which adds all four; these follow from expanding
+                    // `ln(m!) - ln(y!)` and `ln((n-m)!) - ln((n-y)!)`.
+                    let x1 = y + 1.0;
+                    let f1 = m + 1.0;
+                    let z = n + 1.0 - m;
+                    let w = n - y + 1.0;
+                    let bound = x_m * (f1 / x1).ln()
+                        + (n - m + 0.5) * (z / w).ln()
+                        + (y - m) * (w * s / x1).ln()
+                        + stirling_correction(f1) + stirling_correction(z)
+                        - stirling_correction(x1) - stirling_correction(w);
+                    if alpha <= bound {
+                        break y as u64;
+                    }
+                }
             }
-        }
+        };
 
-        // invert the result for p < 0.5
-        if p != self.p {
-            self.n - lresult as u64
+        if self.flip {
+            self.n - result
         } else {
-            lresult as u64
+            result
         }
     }
 }
@@ -161,6 +297,9 @@ mod test {
         test_binomial_mean_and_variance(40, 0.5, &mut rng);
         test_binomial_mean_and_variance(20, 0.7, &mut rng);
         test_binomial_mean_and_variance(20, 0.5, &mut rng);
+        test_binomial_mean_and_variance(1000, 0.005, &mut rng);
+        test_binomial_mean_and_variance(100_000, 0.8, &mut rng);
+        test_binomial_mean_and_variance(1 << 40, 0.3, &mut rng);
     }
 
     #[test]
@@ -168,6 +307,8 @@ mod test {
         let mut rng = ::test::rng(352);
         assert_eq!(rng.sample(Binomial::new(20, 0.0)), 0);
         assert_eq!(rng.sample(Binomial::new(20, 1.0)), 20);
+        assert_eq!(rng.sample(Binomial::new(0, 0.5)), 0);
+        assert_eq!(rng.sample(Binomial::new(1 << 40, 1.0)), 1 << 40);
     }
 
     #[test]
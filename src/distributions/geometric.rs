@@ -0,0 +1,126 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The geometric distribution.
+
+use Rng;
+use distributions::{Distribution, OpenClosed01};
+
+/// The geometric distribution `Geometric(p)`.
+///
+/// Counts the failures before the first success in a sequence of
+/// independent trials, each with probability of success `p`. This
+/// distribution has density function:
+/// `f(k) = (1-p)^k p` for `k >= 0`.
+///
+/// Samples larger than `u64::MAX`, which are only possible for tiny `p`, are
+/// returned as `u64::MAX`.
+///
+/// # Example
+///
+/// ```
+/// use rand::distributions::{Geometric, Distribution};
+///
+/// let geo = Geometric::new(0.25);
+/// let v = geo.sample(&mut rand::thread_rng());
+/// println!("{} failures before the first success", v);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Geometric {
+    p: f64,
+    // precalculated values
+    ln_1_minus_p: f64,
+}
+
+impl Geometric {
+    /// Construct a new `Geometric` with the probability of success `p`.
+    ///
+    /// Panics if `p <= 0` or `p > 1`.
+    pub fn new(p: f64) -> Geometric {
+        assert!(p > 0.0, "Geometric::new called with p <= 0");
+        assert!(p <= 1.0, "Geometric::new called with p > 1");
+        Geometric { p, ln_1_minus_p: (-p).ln_1p() }
+    }
+}
+
+impl Distribution<u64> for Geometric {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        if self.p == 1.0 {
+            return 0;
+        }
+        // Inversion: `P(X >= k) = (1-p)^k`.
+        let u: f64 = rng.sample(OpenClosed01);
+        let k = (u.ln() / self.ln_1_minus_p).floor();
+        // 2^64, the smallest `f64` that does not fit in a `u64`
+        if k >= 18446744073709551616.0 {
+            return ::core::u64::MAX;
+        }
+        k as u64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use distributions::Distribution;
+    use super::Geometric;
+
+    #[test]
+    #[should_panic]
+    fn test_geometric_invalid_zero() {
+        Geometric::new(0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_geometric_invalid_one() {
+        Geometric::new(1.1);
+    }
+
+    #[test]
+    fn test_geometric() {
+        let mut rng = ::test::rng(812);
+        for &p in &[0.001, 0.1, 0.5, 0.9] {
+            let geo = Geometric::new(p);
+            let expected_mean = (1.0 - p) / p;
+            let expected_variance = (1.0 - p) / (p * p);
+
+            let mut results = [0.0; 10000];
+            for i in results.iter_mut() { *i = geo.sample(&mut rng) as f64; }
+
+            // within five standard errors
+            let mean = results.iter().sum::<f64>() / results.len() as f64;
+            let std_err = (expected_variance / results.len() as f64).sqrt();
+            assert!((mean - expected_mean).abs() < 5.0 * std_err);
+
+            let variance =
+                results.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>()
+                / results.len() as f64;
+            assert!((variance - expected_variance).abs() < expected_variance / 10.0);
+        }
+    }
+
+    #[test]
+    fn test_geometric_one() {
+        let geo = Geometric::new(1.0);
+        let mut rng = ::test::rng(813);
+        for _ in 0..100 {
+            assert_eq!(geo.sample(&mut rng), 0);
+        }
+    }
+
+    #[test]
+    fn test_geometric_tiny_p() {
+        let geo = Geometric::new(1e-300);
+        let mut rng = ::test::rng(814);
+        for _ in 0..100 {
+            assert_eq!(geo.sample(&mut rng), ::core::u64::MAX);
+        }
+    }
+}
@@ -0,0 +1,423 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The hypergeometric distribution.
+
+use Rng;
+use distributions::{Distribution, Standard};
+use distributions::utils::{log_gamma, stirling_correction};
+
+/// The hypergeometric distribution `Hypergeometric(N, K, n)`.
+///
+/// Counts the successes when drawing `n` items without replacement from a
+/// population of `N` items, of which `K` are successes. This distribution
+/// has density function:
+/// `f(k) = binomial(K, k) binomial(N-K, n-k) / binomial(N, n)`
+/// for `max(0, n+K-N) <= k <= min(n, K)`.
+///
+/// Sampling uses the H2PE algorithm of Kachitvichyanukul and Schmeiser when
+/// the mode is at least 10, which takes constant expected time for large
+/// populations, and inversion otherwise.
+///
+/// # Example
+///
+/// ```
+/// use rand::distributions::{Hypergeometric, Distribution};
+///
+/// // draw 5 cards from a deck and count the hearts
+/// let hypergeo = Hypergeometric::new(52, 13, 5);
+/// let v = hypergeo.sample(&mut rand::thread_rng());
+/// println!("{} hearts", v);
+/// ```
+///
+/// # Reference
+///
+/// V. Kachitvichyanukul and B. W. Schmeiser, *Computer generation of
+/// hypergeometric random variates*, Journal of Statistical Computation and
+/// Simulation 22(2), 1985.
+#[derive(Clone, Copy, Debug)]
+pub struct Hypergeometric {
+    // The parameters are transformed so that `n1 <= n2` and `k <= n1 + n2`,
+    // which guarantees `k <= n2`.
+    /// Number of successes.
+    n1: u64,
+    /// Number of failures.
+    n2: u64,
+    /// Number of draws.
+    k: u64,
+    /// Whether successes and failures were swapped to get `n1 <= n2`.
+    swap_successes: bool,
+    /// Whether the undrawn items are sampled instead of the drawn ones.
+    complement_draws: bool,
+    /// Number of draws before the transformation.
+    draws: u64,
+    method: Method,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Method {
+    Inversion {
+        /// Probability of zero successes.
+        p0: f64,
+    },
+    H2pe {
+        /// Mode.
+        m: f64,
+        /// `ln f(m)`, up to the normalisation.
+        ln_f_m: f64,
+        x_l: f64,
+        x_r: f64,
+        lambda_l: f64,
+        lambda_r: f64,
+        p1: f64,
+        p2: f64,
+        p3: f64,
+    },
+}
+
+impl Hypergeometric {
+    /// Construct a new `Hypergeometric` with the population size `population`
+    /// (`N`), the number of successes in the population `successes` (`K`)
+    /// and the number of draws `draws` (`n`).
+    ///
+    /// Panics if `successes > population` or `draws > population`.
+    pub fn new(population: u64, successes: u64, draws: u64) -> Hypergeometric {
+        assert!(successes <= population,
+                "Hypergeometric::new called with successes > population");
+        assert!(draws <= population,
+                "Hypergeometric::new called with draws > population");
+
+        let failures = population - successes;
+        let swap_successes = successes > failures;
+        let (n1, n2) = if swap_successes {
+            (failures, successes)
+        } else {
+            (successes, failures)
+        };
+        let complement_draws = draws > population / 2;
+        let k = if complement_draws { population - draws } else { draws };
+
+        let (n1f, n2f, kf) = (n1 as f64, n2 as f64, k as f64);
+        let nf = population as f64;
+        let m = ((kf + 1.0) * (n1f + 1.0) / (nf + 2.0)).floor();
+
+        let method = if m < 10.0 {
+            // `f(0) = binomial(n2, k) / binomial(N, k)`, computed as a
+            // product of `min(n1, k)` factors if that is short.
+            let mut p0 = 1.0;
+            if k <= MAX_PRODUCT_LEN {
+                for i in 0..k {
+                    p0 *= (n2 - i) as f64 / (population - i) as f64;
+                }
+            } else if n1 <= MAX_PRODUCT_LEN {
+                for i in 0..n1 {
+                    p0 *= (population - k - i) as f64 / (population - i) as f64;
+                }
+            } else {
+                p0 = ln_p0(n1f, n2f, kf).exp();
+            }
+            Method::Inversion { p0 }
+        } else {
+            let ln_f_m = ln_f(m, n1f, n2f, kf);
+            let variance = (nf - kf) * kf * n1f * n2f / ((nf - 1.0) * nf * nf);
+            // `x_l` and `x_r` have to be integers, so that the tails cover
+            // the integers next to the central region.
+            let d = (1.5 * variance.sqrt()).floor() + 0.5;
+            let x_l = m - d + 0.5;
+            let x_r = m + d + 0.5;
+            // heights of the exponential tails at `x_l` and `x_r`, relative
+            // to `f(m)`
+            let k_l = (ln_f(x_l, n1f, n2f, kf) - ln_f_m).exp();
+            let k_r = (ln_f(x_r - 1.0, n1f, n2f, kf) - ln_f_m).exp();
+            let lambda_l = -(x_l * (n2f - kf + x_l)
+                / ((n1f - x_l + 1.0) * (kf - x_l + 1.0))).ln();
+            let lambda_r = -((n1f - x_r + 1.0) * (kf - x_r + 1.0)
+                / (x_r * (n2f - kf + x_r))).ln();
+            let p1 = 2.0 * d;
+            let p2 = p1 + k_l / lambda_l;
+            let p3 = p2 + k_r / lambda_r;
+            Method::H2pe { m, ln_f_m, x_l, x_r, lambda_l, lambda_r, p1, p2, p3 }
+        };
+
+        Hypergeometric {
+            n1, n2, k, swap_successes, complement_draws, draws, method
+        }
+    }
+}
+
+/// Compute `f(0)` by multiplication up to this many factors, and with
+/// logarithms beyond.
+const MAX_PRODUCT_LEN: u64 = 1000;
+
+/// `ln f(0) = ln(n2! (N-k)! / ((n2-k)! N!))` with `N = n1 + n2`.
+///
+/// Evaluating this with `log_gamma` would lose all precision for large `N`,
+/// as the terms are about `N ln(N)` while the result is small. Instead,
+/// each quotient `a! / (a-k)!` is expanded with Stirling's formula, and the
+/// large terms of the two quotients are cancelled analytically. This
+/// requires `n1, k > MAX_PRODUCT_LEN` and a mode below 10, which imply
+/// `n2 - k > 90 max(n1, k)`, so all arguments of the Stirling corrections
+/// are large.
+fn ln_p0(n1: f64, n2: f64, k: f64) -> f64 {
+    let n = n1 + n2;
+    // `ln(a! / (a-k)!) = k ln(a+1) - k + g(a-k+1) + c(a+1) - c(a-k+1)`,
+    // where `g(b) = b (ln(1 + k/b) - k/b) - ln(1 + k/b)/2` and `c` is the
+    // Stirling correction.
+    let g = |b: f64| b * ln_1p_minus_x(k / b) - 0.5 * (k / b).ln_1p();
+    k * (-n1 / (n + 1.0)).ln_1p()
+        + g(n2 - k + 1.0) - g(n - k + 1.0)
+        + stirling_correction(n2 + 1.0) - stirling_correction(n2 - k + 1.0)
+        - stirling_correction(n + 1.0) + stirling_correction(n - k + 1.0)
+}
+
+/// `ln(1 + x) - x`, accurate for `x` close to zero.
+fn ln_1p_minus_x(x: f64) -> f64 {
+    if x.abs() > 0.25 {
+        return x.ln_1p() - x;
+    }
+    // `sum_{i >= 2} (-x)^i / i`, negated
+    let mut sum = 0.0f64;
+    let mut power = x * x;
+    let mut i = 2.0;
+    while power.abs() > 1e-17 * sum.abs().max(::core::f64::MIN_POSITIVE) {
+        sum += power / i;
+        power *= -x;
+        i += 1.0;
+    }
+    -sum
+}
+
+/// `ln f(x)` for the hypergeometric distribution with `n1` successes, `n2`
+/// failures and `k` draws, up to the normalisation.
+fn ln_f(x: f64, n1: f64, n2: f64, k: f64) -> f64 {
+    -(ln_factorial(x) + ln_factorial(n1 - x) + ln_factorial(k - x)
+        + ln_factorial(n2 - k + x))
+}
+
+fn ln_factorial(x: f64) -> f64 {
+    log_gamma(x + 1.0)
+}
+
+impl Distribution<u64> for Hypergeometric {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        let (n1, n2, k) = (self.n1, self.n2, self.k);
+        let max = if n1 < k { n1 } else { k };
+
+        let x = match self.method {
+            Method::Inversion { p0 } => {
+                let mut p = p0;
+                let mut x = 0;
+                let mut u: f64 = rng.sample(Standard);
+                while u > p && x < max {
+                    u -= p;
+                    p *= (n1 - x) as f64 * (k - x) as f64;
+                    p /= (x + 1) as f64 * (n2 - k + x + 1) as f64;
+                    x += 1;
+                }
+                x
+            }
+            Method::H2pe { m, ln_f_m, x_l, x_r, lambda_l, lambda_r, p1, p2, p3 } => {
+                loop {
+                    let u = rng.sample::<f64, _>(Standard) * p3;
+                    let mut v: f64 = rng.sample(Standard);
+                    let y = if u < p1 {
+                        // central region, uniform hat
+                        (x_l + u).floor()
+                    } else if u < p2 {
+                        // left exponential tail
+                        (x_l + v.ln() / lambda_l).floor()
+                    } else {
+                        // right exponential tail
+                        (x_r - v.ln() / lambda_r).floor()
+                    };
+                    if y < 0.0 || y > max as f64 {
+                        continue;
+                    }
+                    if u >= p2 {
+                        v *= (u - p2) * lambda_r;
+                    } else if u >= p1 {
+                        v *= (u - p1) * lambda_l;
+                    }
+
+                    // Accept if `v <= f(y) / f(m)`.
+                    if m < 100.0 || y <= 50.0 {
+                        // evaluate `f(y) / f(m)` with the recurrence
+                        // `f(i) / f(i-1) = (n1-i+1)(k-i+1) / (i (n2-k+i))`
+                        let (y, m) = (y as u64, m as u64);
+                        let mut f = 1.0;
+                        if m < y {
+                            for i in (m + 1)..(y + 1) {
+                                f *= (n1 - i + 1) as f64 * (k - i + 1) as f64;
+                                f /= i as f64 * (n2 - k + i) as f64;
+                            }
+                        } else {
+                            for i in (y + 1)..(m + 1) {
+                                f *= i as f64 * (n2 - k + i) as f64;
+                                f /= (n1 - i + 1) as f64 * (k - i + 1) as f64;
+                            }
+                        }
+                        if v <= f {
+                            break y;
+                        }
+                    } else if v.ln() <= ln_f(y, n1 as f64, n2 as f64, k as f64) - ln_f_m {
+                        break y as u64;
+                    }
+                }
+            }
+        };
+
+        // undo the transformations of the parameters
+        let x = if self.complement_draws { n1 - x } else { x };
+        if self.swap_successes { self.draws - x } else { x }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use Rng;
+    use distributions::Distribution;
+    use super::Hypergeometric;
+
+    fn test_hypergeometric_mean_and_variance<R: Rng>(
+        population: u64, successes: u64, draws: u64, rng: &mut R)
+    {
+        let hypergeo = Hypergeometric::new(population, successes, draws);
+
+        let (n, k, d) = (population as f64, successes as f64, draws as f64);
+        let expected_mean = d * k / n;
+        let expected_variance = d * k * (n - k) * (n - d) / (n * n * (n - 1.0));
+
+        let mut results = [0.0; 10000];
+        for i in results.iter_mut() {
+            let x = hypergeo.sample(rng);
+            assert!(x <= draws && x <= successes);
+            assert!(draws - x <= population - successes);
+            *i = x as f64;
+        }
+
+        let mean = results.iter().sum::<f64>() / results.len() as f64;
+        assert!((mean - expected_mean).abs() < expected_mean / 50.0);
+
+        let variance =
+            results.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>()
+            / results.len() as f64;
+        assert!((variance - expected_variance).abs() < expected_variance / 10.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_hypergeometric_invalid_successes() {
+        Hypergeometric::new(10, 11, 5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_hypergeometric_invalid_draws() {
+        Hypergeometric::new(10, 5, 11);
+    }
+
+    #[test]
+    fn test_hypergeometric() {
+        let mut rng = ::test::rng(737);
+        // inversion
+        test_hypergeometric_mean_and_variance(52, 13, 5, &mut rng);
+        test_hypergeometric_mean_and_variance(100, 90, 80, &mut rng);
+        test_hypergeometric_mean_and_variance(1_000_000, 50, 100_000, &mut rng);
+        // H2PE with the recurrence
+        test_hypergeometric_mean_and_variance(500, 200, 150, &mut rng);
+        test_hypergeometric_mean_and_variance(500, 300, 350, &mut rng);
+        // H2PE with logarithms
+        test_hypergeometric_mean_and_variance(100_000, 20_000, 5_000, &mut rng);
+        test_hypergeometric_mean_and_variance(
+            10_000_000_000, 4_000_000_000, 7_000_000_000, &mut rng);
+    }
+
+    /// Check the frequencies of the samples against the exact density with
+    /// a chi-square test.
+    fn test_hypergeometric_chi_square<R: Rng>(
+        population: u64, successes: u64, draws: u64, rng: &mut R)
+    {
+        const N_SAMPLES: usize = 200_000;
+        let hypergeo = Hypergeometric::new(population, successes, draws);
+        let min = (draws + successes).saturating_sub(population);
+        let max = if draws < successes { draws } else { successes };
+
+        // unnormalised logarithm of the density, with the recurrence
+        // `f(x) / f(x-1) = (K-x+1)(n-x+1) / (x (N-K-n+x))`
+        let mut ln_pmf = vec![0.0f64];
+        for x in (min + 1)..(max + 1) {
+            let ln_f = ln_pmf[ln_pmf.len() - 1]
+                + ((successes - x + 1) as f64 * (draws - x + 1) as f64).ln()
+                - (x as f64 * (population - successes - draws + x) as f64).ln();
+            ln_pmf.push(ln_f);
+        }
+        let ln_max = ln_pmf.iter().cloned().fold(::core::f64::MIN, f64::max);
+        let pmf: Vec<f64> = ln_pmf.iter().map(|&l| (l - ln_max).exp()).collect();
+        let total: f64 = pmf.iter().sum();
+
+        let mut counts = vec![0u64; pmf.len()];
+        for _ in 0..N_SAMPLES {
+            counts[(hypergeo.sample(rng) - min) as usize] += 1;
+        }
+
+        let mut chi_square = 0.0;
+        let mut dof = 0.0f64;
+        for (&f, &count) in pmf.iter().zip(counts.iter()) {
+            let expected = f / total * N_SAMPLES as f64;
+            if expected >= 10.0 {
+                chi_square += (count as f64 - expected).powi(2) / expected;
+                dof += 1.0;
+            }
+        }
+        // about five standard deviations above the mean
+        assert!(chi_square < dof + 5.0 * (2.0 * dof).sqrt(),
+                "chi-square {} with {} degrees of freedom", chi_square, dof);
+    }
+
+    #[test]
+    fn test_hypergeometric_pmf() {
+        let mut rng = ::test::rng(739);
+        // inversion
+        test_hypergeometric_chi_square(52, 13, 5, &mut rng);
+        // H2PE with the recurrence
+        test_hypergeometric_chi_square(80, 40, 40, &mut rng);
+        test_hypergeometric_chi_square(500, 200, 150, &mut rng);
+        test_hypergeometric_chi_square(500, 300, 350, &mut rng);
+        // H2PE with logarithms
+        test_hypergeometric_chi_square(100_000, 20_000, 5_000, &mut rng);
+    }
+
+    #[test]
+    fn test_hypergeometric_large_population() {
+        // `f(0)` is computed with logarithms
+        let hypergeo = Hypergeometric::new(
+            1_000_000_000_000_000_000, 3_000_000_000, 3_000_000_000);
+        let mut rng = ::test::rng(740);
+        let mut results = [0.0; 10000];
+        for i in results.iter_mut() { *i = hypergeo.sample(&mut rng) as f64; }
+        // the mean and variance are about 9
+        let mean = results.iter().sum::<f64>() / results.len() as f64;
+        assert!((mean - 9.0).abs() < 5.0 * (9.0 / results.len() as f64).sqrt());
+    }
+
+    #[test]
+    fn test_hypergeometric_degenerate() {
+        let mut rng = ::test::rng(738);
+        for &(population, successes, draws, x) in
+            &[(0, 0, 0, 0), (10, 0, 5, 0), (10, 10, 5, 5), (10, 4, 10, 4),
+              (10, 4, 0, 0)]
+        {
+            let hypergeo = Hypergeometric::new(population, successes, draws);
+            for _ in 0..10 {
+                assert_eq!(hypergeo.sample(&mut rng), x);
+            }
+        }
+    }
+}
@@ -74,7 +74,8 @@
 //! `char`.
 //!
 //! [`WeightedIndex`] can be used to do weighted sampling from a set of items,
-//! such as from an array.
+//! such as from an array. [`WeightedAliasIndex`] does the same in constant
+//! time per sample, at the cost of a slower setup.
 //!
 //! # Non-uniform probability distributions
 //!
@@ -86,7 +87,11 @@
 //!   - [`Cauchy`] distribution
 //! - Related to Bernoulli trials (yes/no events, with a given probability):
 //!   - [`Binomial`] distribution
+//!   - [`Geometric`] distribution
+//!   - [`NegativeBinomial`] distribution
 //!   - [`Bernoulli`] distribution, similar to [`Rng::gen_bool`].
+//! - Related to drawing without replacement:
+//!   - [`Hypergeometric`] distribution
 //! - Related to positive real-valued quantities that grow exponentially
 //!   (e.g. prices, incomes, populations):
 //!   - [`LogNormal`] distribution
@@ -95,6 +100,9 @@
 //!   - [`Poisson`] distribution
 //!   - [`Exp`]onential distribution, and [`Exp1`] as a primitive
 //!   - [`Weibull`] distribution
+//! - Related to ranked items with power-law popularity:
+//!   - [`Zipf`] distribution
+//!   - [`Zeta`] distribution
 //! - Gamma and derived distributions:
 //!   - [`Gamma`] distribution
 //!   - [`ChiSquared`] distribution
@@ -165,7 +173,10 @@
 //! [`Exp1`]: struct.Exp1.html
 //! [`FisherF`]: struct.FisherF.html
 //! [`Gamma`]: struct.Gamma.html
+//! [`Geometric`]: struct.Geometric.html
+//! [`Hypergeometric`]: struct.Hypergeometric.html
 //! [`LogNormal`]: struct.LogNormal.html
+//! [`NegativeBinomial`]: struct.NegativeBinomial.html
 //! [`Normal`]: struct.Normal.html
 //! [`Open01`]: struct.Open01.html
 //! [`OpenClosed01`]: struct.OpenClosed01.html
@@ -181,7 +192,10 @@
 //! [`UnitSphereSurface`]: struct.UnitSphereSurface.html
 //! [`UnitCircle`]: struct.UnitCircle.html
 //! [`Weibull`]: struct.Weibull.html
+//! [`Zeta`]: struct.Zeta.html
+//! [`Zipf`]: struct.Zipf.html
 //! [`WeightedIndex`]: struct.WeightedIndex.html
+//! [`WeightedAliasIndex`]: struct.WeightedAliasIndex.html
 
 use Rng;
 
@@ -190,6 +204,7 @@ pub use self::other::Alphanumeric;
 pub use self::float::{OpenClosed01, Open01};
 pub use self::bernoulli::Bernoulli;
 #[cfg(feature="alloc")] pub use self::weighted::{WeightedIndex, WeightedError};
+#[cfg(feature="alloc")] pub use self::weighted_alias::{WeightedAliasIndex, AliasableWeight};
 #[cfg(feature="std")] pub use self::unit_sphere::UnitSphereSurface;
 #[cfg(feature="std")] pub use self::unit_circle::UnitCircle;
 #[cfg(feature="std")] pub use self::gamma::{Gamma, ChiSquared, FisherF,
@@ -199,14 +214,19 @@ pub use self::bernoulli::Bernoulli;
 #[cfg(feature="std")] pub use self::pareto::Pareto;
 #[cfg(feature="std")] pub use self::poisson::Poisson;
 #[cfg(feature="std")] pub use self::binomial::Binomial;
+#[cfg(feature="std")] pub use self::geometric::Geometric;
+#[cfg(feature="std")] pub use self::negative_binomial::NegativeBinomial;
+#[cfg(feature="std")] pub use self::hypergeometric::Hypergeometric;
 #[cfg(feature="std")] pub use self::cauchy::Cauchy;
 #[cfg(feature="std")] pub use self::dirichlet::Dirichlet;
 #[cfg(feature="std")] pub use self::triangular::Triangular;
 #[cfg(feature="std")] pub use self::weibull::Weibull;
+#[cfg(feature="std")] pub use self::zipf::{Zipf, Zeta};
 
 pub mod uniform;
 mod bernoulli;
 #[cfg(feature="alloc")] mod weighted;
+#[cfg(feature="alloc")] mod weighted_alias;
 #[cfg(feature="std")] mod unit_sphere;
 #[cfg(feature="std")] mod unit_circle;
 #[cfg(feature="std")] mod gamma;
@@ -215,10 +235,14 @@ mod bernoulli;
 #[cfg(feature="std")] mod pareto;
 #[cfg(feature="std")] mod poisson;
 #[cfg(feature="std")] mod binomial;
+#[cfg(feature="std")] mod geometric;
+#[cfg(feature="std")] mod negative_binomial;
+#[cfg(feature="std")] mod hypergeometric;
 #[cfg(feature="std")] mod cauchy;
 #[cfg(feature="std")] mod dirichlet;
 #[cfg(feature="std")] mod triangular;
 #[cfg(feature="std")] mod weibull;
+#[cfg(feature="std")] mod zipf;
 
 mod float;
 mod integer;
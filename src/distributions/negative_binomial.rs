@@ -0,0 +1,123 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The negative binomial distribution.
+
+use Rng;
+use distributions::{Distribution, Gamma, Poisson};
+
+/// The negative binomial distribution `NegativeBinomial(r, p)`.
+///
+/// Counts the failures before the `r`-th success in a sequence of
+/// independent trials, each with probability of success `p`. This
+/// distribution has density function:
+/// `f(k) = Γ(k+r)/(k! Γ(r)) p^r (1-p)^k` for `k >= 0`,
+/// which also allows non-integer `r`. For `r = 1` this is the
+/// [`Geometric`] distribution.
+///
+/// Samples are drawn from a Poisson distribution whose mean is itself drawn
+/// from `Gamma(r, (1-p)/p)`.
+///
+/// # Example
+///
+/// ```
+/// use rand::distributions::{NegativeBinomial, Distribution};
+///
+/// let nb = NegativeBinomial::new(3.0, 0.4);
+/// let v = nb.sample(&mut rand::thread_rng());
+/// println!("{} failures before the third success", v);
+/// ```
+///
+/// [`Geometric`]: struct.Geometric.html
+#[derive(Clone, Copy, Debug)]
+pub struct NegativeBinomial {
+    /// Distribution of the Poisson mean, or `None` if `p = 1`.
+    gamma: Option<Gamma>,
+}
+
+impl NegativeBinomial {
+    /// Construct a new `NegativeBinomial` with the number of successes `r`
+    /// and the probability of success `p`.
+    ///
+    /// Panics if `r <= 0`, `p <= 0` or `p > 1`.
+    pub fn new(r: f64, p: f64) -> NegativeBinomial {
+        assert!(r > 0.0, "NegativeBinomial::new called with r <= 0");
+        assert!(p > 0.0, "NegativeBinomial::new called with p <= 0");
+        assert!(p <= 1.0, "NegativeBinomial::new called with p > 1");
+        let gamma = if p == 1.0 {
+            None
+        } else {
+            Some(Gamma::new(r, (1.0 - p) / p))
+        };
+        NegativeBinomial { gamma }
+    }
+}
+
+impl Distribution<u64> for NegativeBinomial {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        let lambda = match self.gamma {
+            Some(ref gamma) => gamma.sample(rng),
+            None => return 0,
+        };
+        // The gamma sample can underflow to zero for small `r`.
+        if lambda <= 0.0 {
+            return 0;
+        }
+        Poisson::new(lambda).sample(rng)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use distributions::Distribution;
+    use super::NegativeBinomial;
+
+    #[test]
+    #[should_panic]
+    fn test_negative_binomial_invalid_r() {
+        NegativeBinomial::new(0.0, 0.5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_negative_binomial_invalid_p() {
+        NegativeBinomial::new(1.0, 0.0);
+    }
+
+    #[test]
+    fn test_negative_binomial() {
+        let mut rng = ::test::rng(913);
+        for &(r, p) in &[(1.0, 0.5), (3.0, 0.4), (0.5, 0.1), (20.0, 0.9)] {
+            let nb = NegativeBinomial::new(r, p);
+            let expected_mean = r * (1.0 - p) / p;
+            let expected_variance = expected_mean / p;
+
+            let mut results = [0.0; 10000];
+            for i in results.iter_mut() { *i = nb.sample(&mut rng) as f64; }
+
+            let mean = results.iter().sum::<f64>() / results.len() as f64;
+            assert!((mean - expected_mean).abs() < expected_mean / 20.0);
+
+            let variance =
+                results.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>()
+                / results.len() as f64;
+            assert!((variance - expected_variance).abs() < expected_variance / 10.0);
+        }
+    }
+
+    #[test]
+    fn test_negative_binomial_one() {
+        let nb = NegativeBinomial::new(5.0, 1.0);
+        let mut rng = ::test::rng(914);
+        for _ in 0..100 {
+            assert_eq!(nb.sample(&mut rng), 0);
+        }
+    }
+}
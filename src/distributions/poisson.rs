@@ -11,7 +11,7 @@
 //! The Poisson distribution.
 
 use Rng;
-use distributions::{Distribution, Cauchy};
+use distributions::{Distribution, Standard};
 use distributions::utils::log_gamma;
 
 /// The Poisson distribution `Poisson(lambda)`.
@@ -19,6 +19,11 @@ use distributions::utils::log_gamma;
 /// This distribution has a density function:
 /// `f(k) = lambda^k * exp(-lambda) / k!` for `k >= 0`.
 ///
+/// Sampling uses inversion for `lambda < 10`, and the PTRS transformed
+/// rejection method of Hörmann otherwise, which takes constant expected
+/// time. All constants depending on `lambda` are computed by
+/// [`Poisson::new`].
+///
 /// # Example
 ///
 /// ```
@@ -28,14 +33,43 @@ use distributions::utils::log_gamma;
 /// let v = poi.sample(&mut rand::thread_rng());
 /// println!("{} is from a Poisson(2) distribution", v);
 /// ```
+///
+/// # Reference
+///
+/// W. Hörmann, *The transformed rejection method for generating Poisson
+/// random variables*, Insurance: Mathematics and Economics 12(1), 1993.
+///
+/// [`Poisson::new`]: struct.Poisson.html#method.new
 #[derive(Clone, Copy, Debug)]
 pub struct Poisson {
-    lambda: f64,
-    // precalculated values
-    exp_lambda: f64,
-    log_lambda: f64,
-    sqrt_2lambda: f64,
-    magic_val: f64,
+    method: Method,
+}
+
+/// Use inversion below this value of `lambda`, and PTRS above it.
+const INVERSION_THRESHOLD: f64 = 10.0;
+
+/// Restart inversion above this value, which guards against rounding errors
+/// in the cumulative probabilities. With `lambda < 10`, this is reached with
+/// probability below `1e-60`.
+const INVERSION_MAX_X: u64 = 110;
+
+#[derive(Clone, Copy, Debug)]
+enum Method {
+    Inversion {
+        /// `f(0) = exp(-lambda)`
+        exp_neg_lambda: f64,
+        lambda: f64,
+    },
+    Ptrs {
+        lambda: f64,
+        log_lambda: f64,
+        a: f64,
+        b: f64,
+        /// `ln(1 / alpha)`
+        log_inv_alpha: f64,
+        /// Acceptance bound of the immediate acceptance test.
+        v_r: f64,
+    },
 }
 
 impl Poisson {
@@ -43,71 +77,65 @@ impl Poisson {
     /// `lambda`. Panics if `lambda <= 0`.
     pub fn new(lambda: f64) -> Poisson {
         assert!(lambda > 0.0, "Poisson::new called with lambda <= 0");
-        let log_lambda = lambda.ln();
-        Poisson {
-            lambda,
-            exp_lambda: (-lambda).exp(),
-            log_lambda,
-            sqrt_2lambda: (2.0 * lambda).sqrt(),
-            magic_val: lambda * log_lambda - log_gamma(1.0 + lambda),
-        }
+        let method = if lambda < INVERSION_THRESHOLD {
+            Method::Inversion { exp_neg_lambda: (-lambda).exp(), lambda }
+        } else {
+            let b = 0.931 + 2.53 * lambda.sqrt();
+            Method::Ptrs {
+                lambda,
+                log_lambda: lambda.ln(),
+                a: -0.059 + 0.02483 * b,
+                b,
+                log_inv_alpha: (1.1239 + 1.1328 / (b - 3.4)).ln(),
+                v_r: 0.9277 - 3.6224 / (b - 2.0),
+            }
+        };
+        Poisson { method }
     }
 }
 
 impl Distribution<u64> for Poisson {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
-        // using the algorithm from Numerical Recipes in C
-
-        // for low expected values use the Knuth method
-        if self.lambda < 12.0 {
-            let mut result = 0;
-            let mut p = 1.0;
-            while p > self.exp_lambda {
-                p *= rng.gen::<f64>();
-                result += 1;
+        match self.method {
+            Method::Inversion { exp_neg_lambda, lambda } => {
+                // Sequential search, using `f(x) = f(x-1) lambda / x`.
+                'outer: loop {
+                    let mut u: f64 = rng.sample(Standard);
+                    let mut f = exp_neg_lambda;
+                    let mut x = 0;
+                    while u > f {
+                        u -= f;
+                        x += 1;
+                        if x > INVERSION_MAX_X {
+                            continue 'outer;
+                        }
+                        f *= lambda / x as f64;
+                    }
+                    return x;
+                }
             }
-            result - 1
-        }
-        // high expected values - rejection method
-        else {
-            let mut int_result: u64;
-
-            // we use the Cauchy distribution as the comparison distribution
-            // f(x) ~ 1/(1+x^2)
-            let cauchy = Cauchy::new(0.0, 1.0);
-
-            loop {
-                let mut result;
-                let mut comp_dev;
-
+            Method::Ptrs { lambda, log_lambda, a, b, log_inv_alpha, v_r } => {
                 loop {
-                    // draw from the Cauchy distribution
-                    comp_dev = rng.sample(cauchy);
-                    // shift the peak of the comparison ditribution
-                    result = self.sqrt_2lambda * comp_dev + self.lambda;
-                    // repeat the drawing until we are in the range of possible values
-                    if result >= 0.0 {
-                        break;
+                    let u = rng.sample::<f64, _>(Standard) - 0.5;
+                    let v: f64 = rng.sample(Standard);
+                    let us = 0.5 - u.abs();
+                    let k = ((2.0 * a / us + b) * u + lambda + 0.43).floor();
+
+                    // Most samples are accepted by this test, which does not
+                    // evaluate the density.
+                    if us >= 0.07 && v <= v_r {
+                        return k as u64;
+                    }
+                    if k < 0.0 || (us < 0.013 && v > us) {
+                        continue;
+                    }
+                    if v.ln() + log_inv_alpha - (a / (us * us) + b).ln()
+                        <= -lambda + k * log_lambda - log_gamma(k + 1.0)
+                    {
+                        return k as u64;
                     }
-                }
-                // now the result is a random variable greater than 0 with Cauchy distribution
-                // the result should be an integer value
-                result = result.floor();
-                int_result = result as u64;
-
-                // this is the ratio of the Poisson distribution to the comparison distribution
-                // the magic value scales the distribution function to a range of approximately 0-1
-                // since it is not exact, we multiply the ratio by 0.9 to avoid ratios greater than 1
-                // this doesn't change the resulting distribution, only increases the rate of failed drawings
-                let check = 0.9 * (1.0 + comp_dev * comp_dev)
-                    * (result * self.log_lambda - log_gamma(1.0 + result) - self.magic_val).exp();
-
-                // check with uniform random value - if below the threshold, we are within the target distribution
-                if rng.gen::<f64>() <= check {
-                    break;
                 }
             }
-            int_result
         }
     }
 }
@@ -132,7 +160,7 @@ mod test {
 
     #[test]
     fn test_poisson_15() {
-        // Take the 'high expected values' path
+        // Take the PTRS path
         let poisson = Poisson::new(15.0);
         let mut rng = ::test::rng(123);
         let mut sum = 0;
@@ -144,6 +172,32 @@ mod test {
         assert!((avg - 15.0).abs() < 0.5); // not 100% certain, but probable enough
     }
 
+    #[test]
+    fn test_poisson_small() {
+        // Take the inversion path
+        let poisson = Poisson::new(2.5);
+        let mut rng = ::test::rng(124);
+        let mut sum = 0;
+        for _ in 0..1000 {
+            sum += poisson.sample(&mut rng);
+        }
+        let avg = (sum as f64) / 1000.0;
+        assert!((avg - 2.5).abs() < 0.25);
+    }
+
+    #[test]
+    fn test_poisson_large() {
+        let poisson = Poisson::new(1e9);
+        let mut rng = ::test::rng(125);
+        let mut sum = 0;
+        for _ in 0..1000 {
+            sum += poisson.sample(&mut rng);
+        }
+        let avg = (sum as f64) / 1000.0;
+        // the standard deviation of `avg` is 1000
+        assert!((avg - 1e9).abs() < 5000.0);
+    }
+
     #[test]
     #[should_panic]
     fn test_poisson_invalid_lambda_zero() {
@@ -441,6 +441,17 @@ pub fn log_gamma(x: f64) -> f64 {
     log + (2.5066282746310005 * a / x).ln()
 }
 
+/// The correction term of Stirling's formula,
+/// `ln(gamma(a)) - ((a - 0.5) ln(a) - a + ln(2 pi)/2)`, which is also
+/// `ln(a!) - ((a + 0.5) ln(a) - a + ln(2 pi)/2)`.
+///
+/// The absolute error is below `1e-13` for `a >= 10`.
+#[cfg(feature="std")]
+pub fn stirling_correction(a: f64) -> f64 {
+    let a2 = a * a;
+    (13860.0 - (462.0 - (132.0 - (99.0 - 140.0 / a2) / a2) / a2) / a2) / a / 166320.0
+}
+
 /// Sample a random number using the Ziggurat method (specifically the
 /// ZIGNOR variant from Doornik 2005). Most of the arguments are
 /// directly from the paper:
@@ -169,7 +169,8 @@ mod test {
     }
 }
 
-/// Error type returned from `WeightedIndex::new`.
+/// Error type returned from `WeightedIndex::new` and
+/// `WeightedAliasIndex::new`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WeightedError {
     /// The provided iterator contained no items.
@@ -180,6 +181,12 @@ pub enum WeightedError {
 
     /// All items in the provided iterator had a weight of zero.
     AllWeightsZero,
+
+    /// The provided iterator contained more than `u32::MAX` items.
+    TooMany,
+
+    /// A weight times the number of items does not fit in the weight type.
+    Overflow,
 }
 
 impl WeightedError {
@@ -188,6 +195,8 @@ impl WeightedError {
             WeightedError::NoItem => "No items found",
             WeightedError::NegativeWeight => "Item has negative weight",
             WeightedError::AllWeightsZero => "All items had weight zero",
+            WeightedError::TooMany => "Too many items",
+            WeightedError::Overflow => "Weights too large for their type",
         }
     }
 }
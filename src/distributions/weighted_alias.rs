@@ -0,0 +1,302 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Weighted sampling with the alias method.
+
+use Rng;
+use distributions::{Distribution, Uniform};
+use distributions::uniform::{UniformSampler, SampleUniform, SampleBorrow};
+use distributions::weighted::WeightedError;
+use core::ops::{Add, Sub, Mul};
+
+// Note that this whole module is only imported if feature="alloc" is enabled.
+#[cfg(not(feature="std"))] use alloc::vec::Vec;
+
+/// A distribution using weighted sampling to pick a discretely selected item,
+/// in constant time.
+///
+/// Sampling a `WeightedAliasIndex` distribution returns the index of a
+/// randomly selected element from the iterator used when the
+/// `WeightedAliasIndex` was created. The chance of a given element being
+/// picked is proportional to the value of the element. The weights can be
+/// any integer or floating-point type implementing [`AliasableWeight`].
+///
+/// This uses the alias method of Walker, constructed with the algorithm of
+/// Vose. Construction takes `O(n)` time and memory for `n` weights, after
+/// which sampling takes `O(1)` time, compared with `O(log n)` for
+/// [`WeightedIndex`]. Sampling needs two random numbers, so for a handful of
+/// weights, or when the distribution is only sampled a few times,
+/// [`WeightedIndex`] can be faster.
+///
+/// With integer weights the probabilities are exact. With floating-point
+/// weights they are subject to rounding errors, like those of
+/// [`WeightedIndex`].
+///
+/// # Example
+///
+/// ```
+/// use rand::prelude::*;
+/// use rand::distributions::WeightedAliasIndex;
+///
+/// let choices = ['a', 'b', 'c'];
+/// let weights = [2,   1,   1];
+/// let dist = WeightedAliasIndex::new(&weights).unwrap();
+/// let mut rng = thread_rng();
+/// for _ in 0..100 {
+///     // 50% chance to print 'a', 25% chance to print 'b', 25% chance to print 'c'
+///     println!("{}", choices[dist.sample(&mut rng)]);
+/// }
+/// ```
+///
+/// [`AliasableWeight`]: trait.AliasableWeight.html
+/// [`WeightedIndex`]: struct.WeightedIndex.html
+#[derive(Debug, Clone)]
+pub struct WeightedAliasIndex<W: AliasableWeight> {
+    /// The item to pick instead of the column's own item, for each column.
+    aliases: Vec<u32>,
+    /// The weight of the column's own item, out of the column height
+    /// `total_weight`, for each column.
+    no_alias_odds: Vec<W>,
+    uniform_index: Uniform<u32>,
+    uniform_within_weight_sum: W::Sampler,
+}
+
+impl<W: AliasableWeight> WeightedAliasIndex<W> {
+    /// Creates a new `WeightedAliasIndex` [`Distribution`] using the values
+    /// in `weights`.
+    ///
+    /// Returns an error if the iterator is empty, if any weight is `< 0` or
+    /// NaN, if the total weight is 0, if there are more than `u32::MAX`
+    /// weights, or if a weight times the number of weights does not fit in
+    /// `W`.
+    ///
+    /// [`Distribution`]: trait.Distribution.html
+    pub fn new<I>(weights: I) -> Result<WeightedAliasIndex<W>, WeightedError>
+        where I: IntoIterator,
+              I::Item: SampleBorrow<W>
+    {
+        let mut weights: Vec<W> = weights.into_iter()
+                                         .map(|w| *w.borrow())
+                                         .collect();
+        let n = weights.len();
+        if n == 0 {
+            return Err(WeightedError::NoItem);
+        }
+        if n > ::core::u32::MAX as usize {
+            return Err(WeightedError::TooMany);
+        }
+        let n_w = W::try_from_usize(n).ok_or(WeightedError::Overflow)?;
+
+        // Scaling each weight by `n` makes the average of the scaled weights
+        // equal to the total weight, which is the height of every column.
+        // This check also bounds the total weight by `W::MAX`.
+        let max_weight = W::MAX.div_usize(n);
+        let zero = W::ZERO;
+        let mut total_weight = zero;
+        for w in &weights {
+            if !(*w >= zero) {
+                return Err(WeightedError::NegativeWeight);
+            }
+            if *w > max_weight {
+                return Err(WeightedError::Overflow);
+            }
+            total_weight = total_weight + *w;
+        }
+        if total_weight == zero {
+            return Err(WeightedError::AllWeightsZero);
+        }
+
+        // Split the columns into those below and above the average.
+        let mut small = Vec::new();
+        let mut large = Vec::new();
+        for (i, w) in weights.iter_mut().enumerate() {
+            *w = *w * n_w;
+            if *w < total_weight {
+                small.push(i as u32);
+            } else {
+                large.push(i as u32);
+            }
+        }
+
+        // Fill each small column with part of a large one, which remains
+        // small or large depending on what is left of it.
+        let mut aliases = vec![0; n];
+        while !small.is_empty() && !large.is_empty() {
+            let s = small.pop().unwrap() as usize;
+            let l = large.pop().unwrap() as usize;
+            aliases[s] = l as u32;
+            // `weights[l] >= total_weight > total_weight - weights[s]`, so
+            // this cannot underflow.
+            weights[l] = weights[l] - (total_weight - weights[s]);
+            if weights[l] < total_weight {
+                small.push(l as u32);
+            } else {
+                large.push(l as u32);
+            }
+        }
+        // The remaining columns are full; with floating-point weights, small
+        // columns can remain due to rounding errors.
+        for &i in small.iter().chain(large.iter()) {
+            weights[i as usize] = total_weight;
+        }
+
+        Ok(WeightedAliasIndex {
+            aliases,
+            no_alias_odds: weights,
+            uniform_index: Uniform::new(0, n as u32),
+            uniform_within_weight_sum: W::Sampler::new(zero, total_weight),
+        })
+    }
+}
+
+impl<W: AliasableWeight> Distribution<usize> for WeightedAliasIndex<W> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        let candidate = self.uniform_index.sample(rng) as usize;
+        if self.uniform_within_weight_sum.sample(rng) < self.no_alias_odds[candidate] {
+            candidate
+        } else {
+            self.aliases[candidate] as usize
+        }
+    }
+}
+
+/// Types that can be used as weights of a [`WeightedAliasIndex`].
+///
+/// This is implemented for all primitive integer and floating-point types.
+///
+/// [`WeightedAliasIndex`]: struct.WeightedAliasIndex.html
+pub trait AliasableWeight: SampleUniform + PartialOrd + Copy +
+    Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+{
+    /// The zero weight.
+    const ZERO: Self;
+
+    /// The largest representable weight.
+    const MAX: Self;
+
+    /// Converts `n` to `Self`, rounding if necessary, or returns `None` if it
+    /// is out of range.
+    fn try_from_usize(n: usize) -> Option<Self>;
+
+    /// Divides by `n`, rounding down for integers.
+    fn div_usize(self, n: usize) -> Self;
+}
+
+macro_rules! impl_aliasable_weight_float {
+    ($($ty:ident),*) => {$(
+        impl AliasableWeight for $ty {
+            const ZERO: Self = 0.0;
+            const MAX: Self = ::core::$ty::MAX;
+
+            fn try_from_usize(n: usize) -> Option<Self> {
+                Some(n as $ty)
+            }
+
+            fn div_usize(self, n: usize) -> Self {
+                self / n as $ty
+            }
+        }
+    )*}
+}
+
+macro_rules! impl_aliasable_weight_int {
+    ($($ty:ident),*) => {$(
+        impl AliasableWeight for $ty {
+            const ZERO: Self = 0;
+            const MAX: Self = ::core::$ty::MAX;
+
+            fn try_from_usize(n: usize) -> Option<Self> {
+                if n as u64 <= ::core::$ty::MAX as u64 {
+                    Some(n as $ty)
+                } else {
+                    None
+                }
+            }
+
+            fn div_usize(self, n: usize) -> Self {
+                // Only called with `n` that fits in `Self`.
+                self / n as $ty
+            }
+        }
+    )*}
+}
+
+impl_aliasable_weight_float!(f32, f64);
+impl_aliasable_weight_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::fmt::Debug;
+
+    fn test_weighted_alias_index<W>(weights: &[W])
+        where W: AliasableWeight + Into<f64> + Debug
+    {
+        let mut r = ::test::rng(701);
+        const N_REPS: u32 = 10000;
+        let total_weight: f64 = weights.iter().map(|&w| w.into()).sum();
+
+        let mut chosen = vec![0u32; weights.len()];
+        let distr = WeightedAliasIndex::new(weights).unwrap();
+        for _ in 0..N_REPS {
+            chosen[distr.sample(&mut r)] += 1;
+        }
+
+        for (i, count) in chosen.iter().enumerate() {
+            let w: f64 = weights[i].into();
+            let exp = w * N_REPS as f64 / total_weight;
+            if w == 0.0 {
+                assert_eq!(*count, 0, "{:?}", weights);
+            } else {
+                // within five standard deviations
+                let err = (*count as f64 - exp).abs();
+                assert!(err <= 5.0 * exp.sqrt() + 1.0, "{:?}: {} vs {}", weights, count, exp);
+            }
+        }
+    }
+
+    #[test]
+    fn test_weighted_alias_index_int() {
+        test_weighted_alias_index(&[1u32, 2, 3, 0, 5, 6, 7, 1, 2, 3, 4, 5, 6, 7]);
+        test_weighted_alias_index(&[0u8, 0, 10, 0, 40]);
+        test_weighted_alias_index(&[5i32]);
+        // every column is filled from the same large one
+        test_weighted_alias_index(&[1000u16, 1, 1, 1, 1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn test_weighted_alias_index_float() {
+        test_weighted_alias_index(&[1.0f64, 0.001, 1.0/3.0, 4.01, 0.0, 3.3, 22.0, 0.001]);
+        test_weighted_alias_index(&[0.1f32, 0.2, 0.3, 0.4]);
+    }
+
+    #[test]
+    fn test_weighted_alias_index_exact() {
+        let mut r = ::test::rng(702);
+        for _ in 0..5 {
+            assert_eq!(WeightedAliasIndex::new(&[0, 1]).unwrap().sample(&mut r), 1);
+            assert_eq!(WeightedAliasIndex::new(&[1, 0]).unwrap().sample(&mut r), 0);
+            assert_eq!(WeightedAliasIndex::new(&[0, 0, 0, 0, 10, 0]).unwrap().sample(&mut r), 4);
+            assert_eq!(WeightedAliasIndex::new(&[0.0, 0.0, 0.5]).unwrap().sample(&mut r), 2);
+        }
+    }
+
+    #[test]
+    fn test_weighted_alias_index_errors() {
+        assert_eq!(WeightedAliasIndex::new(&[10][0..0]).unwrap_err(), WeightedError::NoItem);
+        assert_eq!(WeightedAliasIndex::new(&[0]).unwrap_err(), WeightedError::AllWeightsZero);
+        assert_eq!(WeightedAliasIndex::new(&[0.0, 0.0]).unwrap_err(), WeightedError::AllWeightsZero);
+        assert_eq!(WeightedAliasIndex::new(&[10, 20, -1, 30]).unwrap_err(), WeightedError::NegativeWeight);
+        assert_eq!(WeightedAliasIndex::new(&[1.0, ::core::f64::NAN]).unwrap_err(), WeightedError::NegativeWeight);
+        assert_eq!(WeightedAliasIndex::new(&[200u8, 100]).unwrap_err(), WeightedError::Overflow);
+        assert_eq!(WeightedAliasIndex::new(&[0u8; 256][..]).unwrap_err(), WeightedError::Overflow);
+        assert_eq!(WeightedAliasIndex::new(&[::core::f64::INFINITY]).unwrap_err(), WeightedError::Overflow);
+    }
+}
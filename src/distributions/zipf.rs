@@ -0,0 +1,275 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The Zipf and Zeta distributions.
+
+use Rng;
+use distributions::{Distribution, Standard, OpenClosed01};
+
+/// The Zipf distribution `Zipf(n, s)`.
+///
+/// Samples integers `k` in `[1, n]` with probability proportional to
+/// `k^-s`. This models the popularity of items ranked by frequency, such as
+/// words in a text or keys in a workload.
+///
+/// Sampling uses the rejection-inversion method of Hörmann and Derflinger,
+/// which takes constant expected time for any `n` and `s`.
+///
+/// # Example
+///
+/// ```
+/// use rand::distributions::{Zipf, Distribution};
+///
+/// let zipf = Zipf::new(1000, 1.1);
+/// let v: u64 = zipf.sample(&mut rand::thread_rng());
+/// println!("{} is from a Zipf(1000, 1.1) distribution", v);
+/// ```
+///
+/// # Reference
+///
+/// W. Hörmann and G. Derflinger, *Rejection-inversion to generate variates
+/// from monotone discrete distributions*, ACM Transactions on Modeling and
+/// Computer Simulation 6(3), 1996.
+#[derive(Clone, Copy, Debug)]
+pub struct Zipf {
+    n: f64,
+    s: f64,
+    // precalculated values
+    h_integral_x1: f64,
+    h_integral_n: f64,
+    threshold: f64,
+}
+
+impl Zipf {
+    /// Construct a new `Zipf` with the number of elements `n` and the
+    /// exponent `s`. Panics if `n < 1` or `s < 0`.
+    pub fn new(n: u64, s: f64) -> Zipf {
+        assert!(n >= 1, "Zipf::new called with n < 1");
+        assert!(s >= 0.0, "Zipf::new called with s < 0");
+        let n = n as f64;
+        Zipf {
+            n,
+            s,
+            h_integral_x1: h_integral(1.5, s) - 1.0,
+            h_integral_n: h_integral(n + 0.5, s),
+            threshold: 2.0 - h_integral_inv(h_integral(2.5, s) - h(2.0, s), s),
+        }
+    }
+}
+
+impl Distribution<f64> for Zipf {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        loop {
+            // `u` is uniform in `(h_integral_x1, h_integral_n]`
+            let r: f64 = rng.sample(Standard);
+            let u = self.h_integral_n + r * (self.h_integral_x1 - self.h_integral_n);
+            let x = h_integral_inv(u, self.s);
+            let mut k = (x + 0.5).floor();
+            if k < 1.0 {
+                k = 1.0;
+            } else if k > self.n {
+                k = self.n;
+            }
+            // The first test accepts most samples without evaluating
+            // `h_integral`.
+            if k - x <= self.threshold
+                || u >= h_integral(k + 0.5, self.s) - h(k, self.s)
+            {
+                return k;
+            }
+        }
+    }
+}
+
+impl Distribution<u64> for Zipf {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        let k: f64 = self.sample(rng);
+        k as u64
+    }
+}
+
+/// `h(x) = x^-s`
+fn h(x: f64, s: f64) -> f64 {
+    (-s * x.ln()).exp()
+}
+
+/// The integral of `h`, `H(x) = (x^(1-s) - 1) / (1 - s)`, which is `ln(x)`
+/// for `s = 1`.
+fn h_integral(x: f64, s: f64) -> f64 {
+    let log_x = x.ln();
+    helper2((1.0 - s) * log_x) * log_x
+}
+
+/// The inverse of `H`.
+fn h_integral_inv(x: f64, s: f64) -> f64 {
+    let mut t = x * (1.0 - s);
+    if t < -1.0 {
+        // Limit `t` to the domain of `ln_1p`; this only happens for `x`
+        // slightly outside the range of `H` due to rounding.
+        t = -1.0;
+    }
+    (helper1(t) * x).exp()
+}
+
+/// `ln(1 + x) / x`, accurate for `x` close to zero.
+fn helper1(x: f64) -> f64 {
+    if x.abs() > 1e-8 {
+        x.ln_1p() / x
+    } else {
+        1.0 - x * (0.5 - x * (1.0 / 3.0 - 0.25 * x))
+    }
+}
+
+/// `(exp(x) - 1) / x`, accurate for `x` close to zero.
+fn helper2(x: f64) -> f64 {
+    if x.abs() > 1e-8 {
+        x.exp_m1() / x
+    } else {
+        1.0 + x * 0.5 * (1.0 + x * (1.0 / 3.0) * (1.0 + 0.25 * x))
+    }
+}
+
+/// The Zeta distribution `Zeta(s)`.
+///
+/// Samples integers `k >= 1` with probability `k^-s / ζ(s)`, where `ζ` is
+/// the Riemann zeta function. This is the limit of the [`Zipf`] distribution
+/// as `n` goes to infinity.
+///
+/// The distribution is heavy-tailed: for `s <= 2` its mean is infinite, and
+/// for `s` close to 1 samples can exceed `u64::MAX`. Sampling a `u64`
+/// rejects such samples, while sampling an `f64` returns them, rounded to
+/// the nearest representable value.
+///
+/// Sampling uses the rejection algorithm from L. Devroye, *Non-Uniform Random
+/// Variate Generation*, p. 551.
+///
+/// # Example
+///
+/// ```
+/// use rand::distributions::{Zeta, Distribution};
+///
+/// let zeta = Zeta::new(2.0);
+/// let v: u64 = zeta.sample(&mut rand::thread_rng());
+/// println!("{} is from a Zeta(2) distribution", v);
+/// ```
+///
+/// [`Zipf`]: struct.Zipf.html
+#[derive(Clone, Copy, Debug)]
+pub struct Zeta {
+    s_minus_1: f64,
+    // precalculated values
+    inv_neg_s_minus_1: f64,
+    b: f64,
+}
+
+impl Zeta {
+    /// Construct a new `Zeta` with the exponent `s`. Panics if `s <= 1`.
+    pub fn new(s: f64) -> Zeta {
+        assert!(s > 1.0, "Zeta::new called with s <= 1");
+        let s_minus_1 = s - 1.0;
+        Zeta {
+            s_minus_1,
+            inv_neg_s_minus_1: -1.0 / s_minus_1,
+            b: s_minus_1.exp2(),
+        }
+    }
+
+    /// Sample a value below `limit`, rejecting larger ones.
+    fn sample_below<R: Rng + ?Sized>(&self, rng: &mut R, limit: f64) -> f64 {
+        loop {
+            let u: f64 = rng.sample(OpenClosed01);
+            let x = u.powf(self.inv_neg_s_minus_1).floor();
+            if x >= limit {
+                continue;
+            }
+            let v: f64 = rng.sample(Standard);
+            let t = (1.0 + 1.0 / x).powf(self.s_minus_1);
+            if v * x * (t - 1.0) * self.b <= t * (self.b - 1.0) {
+                return x;
+            }
+        }
+    }
+}
+
+impl Distribution<f64> for Zeta {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        self.sample_below(rng, ::core::f64::INFINITY)
+    }
+}
+
+impl Distribution<u64> for Zeta {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u64 {
+        // 2^64, the smallest `f64` that does not fit in a `u64`
+        self.sample_below(rng, 18446744073709551616.0) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use distributions::Distribution;
+    use super::{Zipf, Zeta};
+
+    #[test]
+    #[should_panic]
+    fn test_zipf_invalid_n() {
+        Zipf::new(0, 1.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zipf_invalid_s() {
+        Zipf::new(10, -1.0);
+    }
+
+    #[test]
+    fn test_zipf() {
+        let mut rng = ::test::rng(1);
+        for &(n, s) in &[(1, 1.0), (10, 0.0), (10, 1.0), (100, 0.5),
+                         (1000, 2.5), (::core::u64::MAX, 1.1)] {
+            let d = Zipf::new(n, s);
+            for _ in 0..1000 {
+                let k: u64 = d.sample(&mut rng);
+                assert!(k >= 1 && k <= n);
+                let x: f64 = d.sample(&mut rng);
+                assert!(x >= 1.0 && x <= n as f64 && x == x.floor());
+            }
+        }
+    }
+
+    #[test]
+    fn test_zipf_one() {
+        let d = Zipf::new(1, 1.5);
+        let mut rng = ::test::rng(2);
+        for _ in 0..100 {
+            let k: u64 = d.sample(&mut rng);
+            assert_eq!(k, 1);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zeta_invalid() {
+        Zeta::new(1.0);
+    }
+
+    #[test]
+    fn test_zeta() {
+        let mut rng = ::test::rng(3);
+        for &s in &[1.01, 1.5, 2.0, 10.0] {
+            let d = Zeta::new(s);
+            for _ in 0..1000 {
+                let k: u64 = d.sample(&mut rng);
+                assert!(k >= 1);
+                let x: f64 = d.sample(&mut rng);
+                assert!(x >= 1.0 && x == x.floor());
+            }
+        }
+    }
+}
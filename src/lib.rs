@@ -247,6 +247,13 @@ extern crate rand_core;
 extern crate rand_isaac;    // only for deprecations
 extern crate rand_xorshift;
 
+#[cfg(feature="serde1")] extern crate serde;
+#[cfg(feature="serde1")] #[macro_use] extern crate serde_derive;
+#[cfg(feature="async")] extern crate futures;
+
+// To test serialization we need bincode
+#[cfg(all(feature="serde1", test))] extern crate bincode;
+
 #[cfg(feature = "log")] #[macro_use] extern crate log;
 #[allow(unused)]
 #[cfg(not(feature = "log"))] macro_rules! trace { ($($x:tt)*) => () }
@@ -261,8 +268,10 @@ extern crate rand_xorshift;
 
 
 // Re-exports from rand_core
-pub use rand_core::{RngCore, CryptoRng, SeedableRng};
+pub use rand_core::{RngCore, CryptoRng, CryptoRngCore, SeedableRng, SplittableRng};
 pub use rand_core::{ErrorKind, Error};
+#[cfg(rust_1_26)] pub use rand_core::SeekableRng;
+#[cfg(feature="async")] pub use rand_core::AsyncRngCore;
 
 // Public exports
 #[cfg(feature="std")] pub use rngs::thread::thread_rng;
@@ -516,7 +525,10 @@ pub trait Rng: RngCore {
     ///
     /// This uses [`fill_bytes`] internally which may handle some RNG errors
     /// implicitly (e.g. waiting if the OS generator is not ready), but panics
-    /// on other errors. See also [`try_fill`] which returns errors.
+    /// on other errors. See also [`try_fill`] which returns errors. Slices and
+    /// arrays of `u32` and `u64` are filled with [`fill_u32`] and [`fill_u64`]
+    /// instead, which give the same results but avoid the conversion through
+    /// bytes.
     ///
     /// # Example
     ///
@@ -528,11 +540,12 @@ pub trait Rng: RngCore {
     /// ```
     ///
     /// [`fill_bytes`]: trait.RngCore.html#method.fill_bytes
+    /// [`fill_u32`]: trait.RngCore.html#method.fill_u32
+    /// [`fill_u64`]: trait.RngCore.html#method.fill_u64
     /// [`try_fill`]: trait.Rng.html#method.try_fill
     /// [`AsByteSliceMut`]: trait.AsByteSliceMut.html
     fn fill<T: AsByteSliceMut + ?Sized>(&mut self, dest: &mut T) {
-        self.fill_bytes(dest.as_byte_slice_mut());
-        dest.to_le();
+        dest.fill_from(self)
     }
 
     /// Fill `dest` entirely with random bytes (uniform value distribution),
@@ -674,6 +687,15 @@ pub trait AsByteSliceMut {
 
     /// Call `to_le` on each element (i.e. byte-swap on Big Endian platforms).
     fn to_le(&mut self);
+
+    /// Fill `self` with random data from `rng`.
+    ///
+    /// The default implementation uses `fill_bytes` followed by `to_le`.
+    /// Slices of `u32` and `u64` use `RngCore::fill_u32` and `fill_u64`.
+    fn fill_from<R: RngCore + ?Sized>(&mut self, rng: &mut R) {
+        rng.fill_bytes(self.as_byte_slice_mut());
+        self.to_le();
+    }
 }
 
 impl AsByteSliceMut for [u8] {
@@ -685,7 +707,17 @@ impl AsByteSliceMut for [u8] {
 }
 
 macro_rules! impl_as_byte_slice {
+    ($t:ty, $fill:ident) => {
+        impl_as_byte_slice!($t, {
+            fn fill_from<R: RngCore + ?Sized>(&mut self, rng: &mut R) {
+                rng.$fill(self)
+            }
+        });
+    };
     ($t:ty) => {
+        impl_as_byte_slice!($t, {});
+    };
+    ($t:ty, { $($fill_from:tt)* }) => {
         impl AsByteSliceMut for [$t] {
             fn as_byte_slice_mut(&mut self) -> &mut [u8] {
                 if self.len() == 0 {
@@ -709,13 +741,15 @@ macro_rules! impl_as_byte_slice {
                     *x = x.to_le();
                 }
             }
+
+            $($fill_from)*
         }
     }
 }
 
 impl_as_byte_slice!(u16);
-impl_as_byte_slice!(u32);
-impl_as_byte_slice!(u64);
+impl_as_byte_slice!(u32, fill_u32);
+impl_as_byte_slice!(u64, fill_u64);
 #[cfg(rust_1_26)] impl_as_byte_slice!(u128);
 impl_as_byte_slice!(usize);
 impl_as_byte_slice!(i8);
@@ -738,6 +772,10 @@ macro_rules! impl_as_byte_slice_arrays {
             fn to_le(&mut self) {
                 self[..].to_le()
             }
+
+            fn fill_from<R: RngCore + ?Sized>(&mut self, rng: &mut R) {
+                self[..].fill_from(rng)
+            }
         }
     };
     (!div $n:expr,) => {};
@@ -752,6 +790,10 @@ macro_rules! impl_as_byte_slice_arrays {
             fn to_le(&mut self) {
                 self[..].to_le()
             }
+
+            fn fill_from<R: RngCore + ?Sized>(&mut self, rng: &mut R) {
+                self[..].fill_from(rng)
+            }
         }
     };
 }
@@ -1019,6 +1061,51 @@ mod test {
         rng.fill(&mut array[..]);
     }
 
+    #[test]
+    fn test_fill_words_block_rng() {
+        use prng::{Hc128Rng, Threefry4x64Rng};
+
+        // `fill_u32` and `fill_u64` must give the same values as
+        // `fill_bytes`, starting from any position in the result buffer.
+        for &offset in [0usize, 1, 15, 16].iter() {
+            for &n in [0usize, 1, 15, 16, 17, 40, 100].iter() {
+                let mut rng1 = Hc128Rng::from_seed([7; 32]);
+                let mut rng2 = rng1.clone();
+                for _ in 0..offset {
+                    assert_eq!(rng1.next_u32(), rng2.next_u32());
+                }
+                let mut bytes = [0u8; 400];
+                let mut words = [0u32; 100];
+                rng1.fill_bytes(&mut bytes[..n * 4]);
+                rng2.fill(&mut words[..n]);
+                for (chunk, &w) in bytes.chunks(4).zip(words[..n].iter()) {
+                    assert_eq!(le_u32(chunk), w);
+                }
+                assert_eq!(rng1.next_u32(), rng2.next_u32());
+
+                let mut rng1 = Threefry4x64Rng::from_seed([7; 32]);
+                let mut rng2 = rng1.clone();
+                for _ in 0..offset {
+                    assert_eq!(rng1.next_u64(), rng2.next_u64());
+                }
+                let mut bytes = [0u8; 800];
+                let mut words = [0u64; 100];
+                rng1.fill_bytes(&mut bytes[..n * 8]);
+                rng2.fill(&mut words[..n]);
+                for (chunk, &w) in bytes.chunks(8).zip(words[..n].iter()) {
+                    let lo = u64::from(le_u32(&chunk[..4]));
+                    let hi = u64::from(le_u32(&chunk[4..]));
+                    assert_eq!((hi << 32) | lo, w);
+                }
+                assert_eq!(rng1.next_u64(), rng2.next_u64());
+            }
+        }
+
+        fn le_u32(b: &[u8]) -> u32 {
+            b.iter().rev().fold(0, |x, &b| (x << 8) | u32::from(b))
+        }
+    }
+
     #[test]
     fn test_gen_range() {
         let mut r = rng(101);
@@ -1086,6 +1173,42 @@ mod test {
         let _c: u8 = Standard.sample(&mut r);
     }
 
+    #[test]
+    fn test_crypto_rng_trait_object() {
+        use distributions::{Distribution, Standard};
+        use prng::ChaChaRng;
+
+        fn requires_crypto_rng<R: CryptoRng + RngCore + ?Sized>(rng: &mut R) -> u32 {
+            rng.gen()
+        }
+
+        let mut rng = ChaChaRng::from_seed([7; 32]);
+        let mut r = &mut rng as &mut CryptoRngCore;
+        r.next_u32();
+        r.gen::<i32>();
+        assert_eq!(r.gen_range(0, 1), 0);
+        let _c: u8 = Standard.sample(&mut r);
+        requires_crypto_rng(r);
+        requires_crypto_rng(&mut r);
+        r.as_rngcore().gen::<u64>();
+    }
+
+    #[test]
+    #[cfg(feature="alloc")]
+    fn test_crypto_rng_boxed_trait() {
+        use prng::Hc128Rng;
+
+        fn requires_crypto_rng<R: CryptoRng + RngCore>(mut rng: R) -> u32 {
+            rng.gen()
+        }
+
+        let mut r = Box::new(Hc128Rng::from_seed([7; 32])) as Box<CryptoRngCore>;
+        r.gen::<i32>();
+        assert_eq!(r.gen_range(0, 1), 0);
+        requires_crypto_rng(&mut r);
+        requires_crypto_rng(r);
+    }
+
     #[test]
     #[cfg(feature="std")]
     fn test_random() {
@@ -11,183 +11,82 @@
 //! The ChaCha random number generator.
 
 use core::fmt;
-use rand_core::{CryptoRng, RngCore, SeedableRng, Error, le};
+use rand_core::{CryptoRng, RngCore, SeedableRng, SplittableRng, Error,
+                ErrorKind, le};
+#[cfg(rust_1_26)] use rand_core::SeekableRng;
 use rand_core::block::{BlockRngCore, BlockRng};
+use rand_core::state::{StateBytes, StateReader, StateWriter};
+#[cfg(feature="zeroize")] use rand_core::impls;
+#[cfg(feature="simd_support")] use packed_simd::u32x4;
 
 const SEED_WORDS: usize = 8; // 8 words for the 256-bit key
 const STATE_WORDS: usize = 16;
 
-/// A cryptographically secure random number generator that uses the ChaCha
-/// algorithm.
-///
-/// ChaCha is a stream cipher designed by Daniel J. Bernstein[^1], that we use
-/// as an RNG. It is an improved variant of the Salsa20 cipher family, which was
-/// selected as one of the "stream ciphers suitable for widespread adoption" by
-/// eSTREAM[^2].
-///
-/// ChaCha uses add-rotate-xor (ARX) operations as its basis. These are safe
-/// against timing attacks, although that is mostly a concern for ciphers and
-/// not for RNGs. Also it is very suitable for SIMD implementation.
-/// Here we do not provide a SIMD implementation yet, except for what is
-/// provided by auto-vectorisation.
-///
-/// With the ChaCha algorithm it is possible to choose the number of rounds the
-/// core algorithm should run. The number of rounds is a tradeoff between
-/// performance and security, where 8 rounds is the minimum potentially
-/// secure configuration, and 20 rounds is widely used as a conservative choice.
-/// We use 20 rounds in this implementation, but hope to allow type-level
-/// configuration in the future.
-///
-/// We use a 64-bit counter and 64-bit stream identifier as in Bernstein's
-/// implementation[^1] except that we use a stream identifier in place of a
-/// nonce. A 64-bit counter over 64-byte (16 word) blocks allows 1 ZiB of output
-/// before cycling, and the stream identifier allows 2<sup>64</sup> unique
-/// streams of output per seed. Both counter and stream are initialized to zero
-/// but may be set via [`set_word_pos`] and [`set_stream`].
-///
-/// The word layout is:
-///
-/// ```text
-/// constant  constant  constant  constant
-/// seed      seed      seed      seed
-/// seed      seed      seed      seed
-/// counter   counter   stream_id stream_id
-/// ```
-///
-/// This implementation uses an output buffer of sixteen `u32` words, and uses
-/// [`BlockRng`] to implement the [`RngCore`] methods.
-///
-/// [^1]: D. J. Bernstein, [*ChaCha, a variant of Salsa20*](
-///       https://cr.yp.to/chacha.html)
-///
-/// [^2]: [eSTREAM: the ECRYPT Stream Cipher Project](
-///       http://www.ecrypt.eu.org/stream/)
-///
-/// [`set_word_pos`]: #method.set_word_pos
-/// [`set_stream`]: #method.set_stream
-/// [`BlockRng`]: ../../../rand_core/block/struct.BlockRng.html
-/// [`RngCore`]: ../../trait.RngCore.html
-#[derive(Clone, Debug)]
-pub struct ChaChaRng(BlockRng<ChaChaCore>);
-
-impl RngCore for ChaChaRng {
-    #[inline]
-    fn next_u32(&mut self) -> u32 {
-        self.0.next_u32()
-    }
+// Number of blocks generated at once. With SIMD support, four consecutive
+// blocks are computed in parallel, one per vector lane.
+#[cfg(not(feature="simd_support"))] const BUF_BLOCKS: usize = 1;
+#[cfg(feature="simd_support")] const BUF_BLOCKS: usize = 4;
+const BUF_WORDS: usize = BUF_BLOCKS * STATE_WORDS;
 
-    #[inline]
-    fn next_u64(&mut self) -> u64 {
-        self.0.next_u64()
-    }
-
-    #[inline]
-    fn fill_bytes(&mut self, dest: &mut [u8]) {
-        self.0.fill_bytes(dest)
-    }
+#[cfg(not(feature="simd_support"))]
+type Buffer = [u32; BUF_WORDS];
+#[cfg(feature="simd_support")]
+type Buffer = ChaChaBuffer;
 
-    #[inline]
-    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
-        self.0.try_fill_bytes(dest)
+/// The output buffer of the ChaCha cores with the `simd_support` feature,
+/// holding four consecutive blocks.
+///
+/// Because of the larger buffer, serialized generators can not be exchanged
+/// between builds with and without `simd_support`.
+// Arrays with more than 32 elements do not implement `Default` and `AsRef`.
+#[cfg(feature="simd_support")]
+#[derive(Copy, Clone)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize))]
+#[allow(missing_debug_implementations)]
+pub struct ChaChaBuffer(
+    #[cfg_attr(feature="serde1", serde(with="buffer_serde"))]
+    [u32; BUF_WORDS]);
+
+#[cfg(all(feature="simd_support", feature="serde1"))]
+array_serde!(buffer_serde, u32, super::BUF_WORDS);
+
+#[cfg(feature="simd_support")]
+impl Default for ChaChaBuffer {
+    fn default() -> Self {
+        ChaChaBuffer([0; BUF_WORDS])
     }
 }
 
-impl SeedableRng for ChaChaRng {
-    type Seed = <ChaChaCore as SeedableRng>::Seed;
-
-    fn from_seed(seed: Self::Seed) -> Self {
-        ChaChaRng(BlockRng::<ChaChaCore>::from_seed(seed))
-    }
-
-    fn from_rng<R: RngCore>(rng: R) -> Result<Self, Error> {
-        BlockRng::<ChaChaCore>::from_rng(rng).map(ChaChaRng)
+#[cfg(feature="simd_support")]
+impl AsRef<[u32]> for ChaChaBuffer {
+    #[inline(always)]
+    fn as_ref(&self) -> &[u32] {
+        &self.0[..]
     }
 }
 
-impl CryptoRng for ChaChaRng {}
-
-impl ChaChaRng {
-    /// Get the offset from the start of the stream, in 32-bit words.
-    /// 
-    /// Since the generated blocks are 16 words (2<sup>4</sup>) long and the
-    /// counter is 64-bits, the offset is a 68-bit number. Sub-word offsets are
-    /// not supported, hence the result can simply be multiplied by 4 to get a
-    /// byte-offset.
-    /// 
-    /// Note: this function is currently only available with Rust 1.26 or later.
-    #[cfg(rust_1_26)]
-    pub fn get_word_pos(&self) -> u128 {
-        let mut c = (self.0.core.state[13] as u64) << 32
-                  | (self.0.core.state[12] as u64);
-        let mut index = self.0.index();
-        // c is the end of the last block generated, unless index is at end
-        if index >= STATE_WORDS {
-            index = 0;
-        } else {
-            c = c.wrapping_sub(1);
-        }
-        ((c as u128) << 4) | (index as u128)
-    }
-
-    /// Set the offset from the start of the stream, in 32-bit words.
-    /// 
-    /// As with `get_word_pos`, we use a 68-bit number. Since the generator
-    /// simply cycles at the end of its period (1 ZiB), we ignore the upper
-    /// 60 bits.
-    /// 
-    /// Note: this function is currently only available with Rust 1.26 or later.
-    #[cfg(rust_1_26)]
-    pub fn set_word_pos(&mut self, word_offset: u128) {
-        let index = (word_offset as usize) & 0xF;
-        let counter = (word_offset >> 4) as u64;
-        self.0.core.state[12] = counter as u32;
-        self.0.core.state[13] = (counter >> 32) as u32;
-        if index != 0 {
-            self.0.generate_and_set(index); // also increments counter
-        } else {
-            self.0.reset();
-        }
-    }
-
-    /// Set the stream number.
-    ///
-    /// This is initialized to zero; 2<sup>64</sup> unique streams of output
-    /// are available per seed/key.
-    /// 
-    /// Note that in order to reproduce ChaCha output with a specific 64-bit
-    /// nonce, one can convert that nonce to a `u64` in little-endian fashion
-    /// and pass to this function. In theory a 96-bit nonce can be used by
-    /// passing the last 64-bits to this function and using the first 32-bits as
-    /// the most significant half of the 64-bit counter (which may be set
-    /// indirectly via `set_word_pos`), but this is not directly supported.
-    pub fn set_stream(&mut self, stream: u64) {
-        let index = self.0.index();
-        self.0.core.state[14] = stream as u32;
-        self.0.core.state[15] = (stream >> 32) as u32;
-        if index < STATE_WORDS {
-            // we need to regenerate a partial result buffer
-            {
-                // reverse of counter adjustment in generate()
-                if self.0.core.state[12] == 0 {
-                    self.0.core.state[13] = self.0.core.state[13].wrapping_sub(1);
-                }
-                self.0.core.state[12] = self.0.core.state[12].wrapping_sub(1);
-            }
-            self.0.generate_and_set(index);
-        }
+#[cfg(feature="simd_support")]
+impl AsMut<[u32]> for ChaChaBuffer {
+    #[inline(always)]
+    fn as_mut(&mut self) -> &mut [u32] {
+        &mut self.0[..]
     }
 }
 
-/// The core of `ChaChaRng`, used with `BlockRng`.
-#[derive(Clone)]
-pub struct ChaChaCore {
-    state: [u32; STATE_WORDS],
+#[cfg(feature="simd_support")]
+impl ::core::ops::Deref for ChaChaBuffer {
+    type Target = [u32; BUF_WORDS];
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
 }
 
-// Custom Debug implementation that does not expose the internal state
-impl fmt::Debug for ChaChaCore {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "ChaChaCore {{}}")
+#[cfg(feature="simd_support")]
+impl ::core::ops::DerefMut for ChaChaBuffer {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
     }
 }
 
@@ -215,62 +114,493 @@ macro_rules! double_round{
     }}
 }
 
-impl BlockRngCore for ChaChaCore {
-    type Item = u32;
-    type Results = [u32; STATE_WORDS];
-
-    fn generate(&mut self, results: &mut Self::Results) {
-        // For some reason extracting this part into a separate function
-        // improves performance by 50%.
-        fn core(results: &mut [u32; STATE_WORDS],
-                state: &[u32; STATE_WORDS])
-        {
-            let mut tmp = *state;
-            let rounds = 20;
-            for _ in 0..rounds / 2 {
-                double_round!(tmp);
+#[cfg(feature="simd_support")]
+macro_rules! quarter_round_simd{
+    ($a: expr, $b: expr, $c: expr, $d: expr) => {{
+        $a = $a + $b; $d ^= $a; $d = ($d << 16) | ($d >> 16);
+        $c = $c + $d; $b ^= $c; $b = ($b << 12) | ($b >> 20);
+        $a = $a + $b; $d ^= $a; $d = ($d <<  8) | ($d >> 24);
+        $c = $c + $d; $b ^= $c; $b = ($b <<  7) | ($b >> 25);
+    }}
+}
+
+#[cfg(feature="simd_support")]
+macro_rules! double_round_simd{
+    ($x: expr) => {{
+        // Column round
+        quarter_round_simd!($x[ 0], $x[ 4], $x[ 8], $x[12]);
+        quarter_round_simd!($x[ 1], $x[ 5], $x[ 9], $x[13]);
+        quarter_round_simd!($x[ 2], $x[ 6], $x[10], $x[14]);
+        quarter_round_simd!($x[ 3], $x[ 7], $x[11], $x[15]);
+        // Diagonal round
+        quarter_round_simd!($x[ 0], $x[ 5], $x[10], $x[15]);
+        quarter_round_simd!($x[ 1], $x[ 6], $x[11], $x[12]);
+        quarter_round_simd!($x[ 2], $x[ 7], $x[ 8], $x[13]);
+        quarter_round_simd!($x[ 3], $x[ 4], $x[ 9], $x[14]);
+    }}
+}
+
+#[inline(always)]
+fn get_counter(state: &[u32; STATE_WORDS]) -> u64 {
+    (u64::from(state[13]) << 32) | u64::from(state[12])
+}
+
+#[inline(always)]
+fn set_counter(state: &mut [u32; STATE_WORDS], counter: u64) {
+    state[12] = counter as u32;
+    state[13] = (counter >> 32) as u32;
+}
+
+// Compute the block for the current counter.
+#[cfg(not(feature="simd_support"))]
+#[inline(always)]
+fn chacha_blocks(results: &mut [u32; BUF_WORDS],
+                 state: &[u32; STATE_WORDS],
+                 rounds: usize)
+{
+    let mut tmp = *state;
+    for _ in 0..rounds / 2 {
+        double_round!(tmp);
+    }
+    for i in 0..STATE_WORDS {
+        results[i] = tmp[i].wrapping_add(state[i]);
+    }
+}
+
+// Compute the blocks for the current counter and the three following counter
+// values in parallel. Lane `j` of each vector holds a word of block `j`.
+#[cfg(feature="simd_support")]
+#[inline(always)]
+fn chacha_blocks(results: &mut [u32; BUF_WORDS],
+                 state: &[u32; STATE_WORDS],
+                 rounds: usize)
+{
+    let mut init = [u32x4::splat(0); STATE_WORDS];
+    for i in 0..STATE_WORDS {
+        init[i] = u32x4::splat(state[i]);
+    }
+    let counter = get_counter(state);
+    let c = [counter, counter.wrapping_add(1),
+             counter.wrapping_add(2), counter.wrapping_add(3)];
+    init[12] = u32x4::new(c[0] as u32, c[1] as u32, c[2] as u32, c[3] as u32);
+    init[13] = u32x4::new((c[0] >> 32) as u32, (c[1] >> 32) as u32,
+                          (c[2] >> 32) as u32, (c[3] >> 32) as u32);
+
+    let mut tmp = init;
+    for _ in 0..rounds / 2 {
+        double_round_simd!(tmp);
+    }
+    for i in 0..STATE_WORDS {
+        let x = tmp[i] + init[i];
+        for block in 0..BUF_BLOCKS {
+            results[block * STATE_WORDS + i] = x.extract(block);
+        }
+    }
+}
+
+// Replace the constants of the state when deriving the key of a child in
+// `fork`, so that this block is not part of the keystream of any stream.
+const FORK_CONSTANTS: [u32; 4] =
+    [0x646E6172, 0x61686320, 0x20616863, 0x6B726F66]; // "rand chacha fork"
+
+// Initial state from a 256-bit key, with counter and stream set to zero.
+fn init_state(seed: [u8; SEED_WORDS*4]) -> [u32; STATE_WORDS] {
+    let mut seed_le = [0u32; SEED_WORDS];
+    le::read_u32_into(&seed, &mut seed_le);
+    [0x61707865, 0x3320646E, 0x79622D32, 0x6B206574, // constants
+     seed_le[0], seed_le[1], seed_le[2], seed_le[3], // seed
+     seed_le[4], seed_le[5], seed_le[6], seed_le[7], // seed
+     0, 0, 0, 0] // counter
+}
+
+// Implements a ChaCha variant with the given number of rounds: the `BlockRng`
+// wrapper `$rng` with the word position and stream API, and its core `$core`.
+macro_rules! chacha_impl {
+    ($(#[$rng_attr:meta])* pub struct $rng:ident;
+     $(#[$core_attr:meta])* pub struct $core:ident;
+     rounds = $rounds:expr, id = $id:expr) => {
+        $(#[$rng_attr])*
+        #[derive(Clone, Debug)]
+        #[cfg_attr(feature="serde1", derive(Serialize, Deserialize))]
+        pub struct $rng(BlockRng<$core>);
+
+        impl RngCore for $rng {
+            #[inline]
+            fn next_u32(&mut self) -> u32 {
+                self.0.next_u32()
+            }
+
+            #[inline]
+            fn next_u64(&mut self) -> u64 {
+                self.0.next_u64()
+            }
+
+            #[inline]
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                self.0.fill_bytes(dest)
+            }
+
+            #[inline]
+            fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+                self.0.try_fill_bytes(dest)
+            }
+
+            #[inline]
+            fn fill_u32(&mut self, dest: &mut [u32]) {
+                self.0.fill_u32(dest)
+            }
+        }
+
+        impl SeedableRng for $rng {
+            type Seed = <$core as SeedableRng>::Seed;
+
+            fn from_seed(seed: Self::Seed) -> Self {
+                $rng(BlockRng::<$core>::from_seed(seed))
+            }
+
+            fn from_rng<R: RngCore>(rng: R) -> Result<Self, Error> {
+                BlockRng::<$core>::from_rng(rng).map($rng)
+            }
+        }
+
+        impl CryptoRng for $rng {}
+
+        impl $rng {
+            /// Get the offset from the start of the stream, in 32-bit words.
+            /// 
+            /// Since the generated blocks are 16 words (2<sup>4</sup>) long and the
+            /// counter is 64-bits, the offset is a 68-bit number. Sub-word offsets are
+            /// not supported, hence the result can simply be multiplied by 4 to get a
+            /// byte-offset.
+            /// 
+            /// Note: this function is currently only available with Rust 1.26 or later.
+            #[cfg(rust_1_26)]
+            pub fn get_word_pos(&self) -> u128 {
+                let mut c = get_counter(&self.0.core.state);
+                let mut index = self.0.index();
+                // c is the end of the last blocks generated, unless index is
+                // at end
+                if index >= BUF_WORDS {
+                    index = 0;
+                } else {
+                    c = c.wrapping_sub(BUF_BLOCKS as u64);
+                }
+                (((c as u128) << 4) + (index as u128)) & ((1 << 68) - 1)
+            }
+
+            /// Set the offset from the start of the stream, in 32-bit words.
+            /// 
+            /// As with `get_word_pos`, we use a 68-bit number. Since the generator
+            /// simply cycles at the end of its period (1 ZiB), we ignore the upper
+            /// 60 bits.
+            /// 
+            /// Note: this function is currently only available with Rust 1.26 or later.
+            #[cfg(rust_1_26)]
+            pub fn set_word_pos(&mut self, word_offset: u128) {
+                let index = (word_offset as usize) & 0xF;
+                let counter = (word_offset >> 4) as u64;
+                set_counter(&mut self.0.core.state, counter);
+                if index != 0 {
+                    self.0.generate_and_set(index); // also increments counter
+                } else {
+                    self.0.reset();
+                }
             }
-            for i in 0..STATE_WORDS {
-                results[i] = tmp[i].wrapping_add(state[i]);
+
+            /// Set the stream number.
+            ///
+            /// This is initialized to zero; 2<sup>64</sup> unique streams of output
+            /// are available per seed/key.
+            /// 
+            /// Note that in order to reproduce ChaCha output with a specific 64-bit
+            /// nonce, one can convert that nonce to a `u64` in little-endian fashion
+            /// and pass to this function. In theory a 96-bit nonce can be used by
+            /// passing the last 64-bits to this function and using the first 32-bits as
+            /// the most significant half of the 64-bit counter (which may be set
+            /// indirectly via `set_word_pos`), but this is not directly supported.
+            pub fn set_stream(&mut self, stream: u64) {
+                let index = self.0.index();
+                self.0.core.state[14] = stream as u32;
+                self.0.core.state[15] = (stream >> 32) as u32;
+                if index < BUF_WORDS {
+                    // we need to regenerate a partial result buffer
+                    {
+                        // reverse of counter adjustment in generate()
+                        let c = get_counter(&self.0.core.state);
+                        set_counter(&mut self.0.core.state,
+                                    c.wrapping_sub(BUF_BLOCKS as u64));
+                    }
+                    self.0.generate_and_set(index);
+                }
             }
         }
 
-        core(results, &self.state);
+        /// A step is the generation of one `u32` word, as with
+        /// `get_word_pos` and `set_word_pos`. Positions wrap around at
+        /// 2<sup>68</sup> words.
+        #[cfg(rust_1_26)]
+        impl SeekableRng for $rng {
+            fn position(&self) -> u128 {
+                self.get_word_pos()
+            }
+
+            fn seek(&mut self, pos: u128) {
+                self.set_word_pos(pos)
+            }
+        }
 
-        // update 64-bit counter
-        self.state[12] = self.state[12].wrapping_add(1);
-        if self.state[12] != 0 { return; };
-        self.state[13] = self.state[13].wrapping_add(1);
+        /// The state is the key as eight `u32`, the block counter and the
+        /// stream as `u64`, and the index of the next word in the block as
+        /// `u32`: 52 bytes, independent of the `simd_support` feature.
+        impl StateBytes for $rng {
+            const ALGORITHM_ID: [u8; 8] = $id;
+            const FORMAT_VERSION: u32 = 1;
+
+            fn state_len() -> usize {
+                52
+            }
+
+            fn write_state(&self, state: &mut [u8]) {
+                let s = &self.0.core.state;
+                let mut block = get_counter(s);
+                let mut index = self.0.index();
+                if index >= BUF_WORDS {
+                    index = 0;
+                } else {
+                    block = block.wrapping_sub(BUF_BLOCKS as u64)
+                                 .wrapping_add((index / STATE_WORDS) as u64);
+                    index %= STATE_WORDS;
+                }
+                let mut w = StateWriter::new(state);
+                w.write_u32s(&s[4..12]);
+                w.write_u64(block);
+                w.write_u64(u64::from(s[14]) | (u64::from(s[15]) << 32));
+                w.write_u32(index as u32);
+            }
+
+            fn read_state(state: &[u8]) -> Result<Self, Error> {
+                let mut r = StateReader::new(state);
+                let mut seed = [0u8; SEED_WORDS*4];
+                r.read_bytes(&mut seed);
+                let block = r.read_u64();
+                let stream = r.read_u64();
+                let index = r.read_u32() as usize;
+                if index >= STATE_WORDS {
+                    return Err(Error::new(ErrorKind::Unavailable,
+                                          "state bytes: invalid word index"));
+                }
+                let mut rng = Self::from_seed(seed);
+                rng.set_stream(stream);
+                set_counter(&mut rng.0.core.state, block);
+                if index != 0 {
+                    rng.0.generate_and_set(index);
+                }
+                Ok(rng)
+            }
+        }
+
+        /// `split` seeds the child with the next 256 bits of output.
+        ///
+        /// `fork` generates the key of the child with the ChaCha block
+        /// function, using the key of this generator, `index` as the stream,
+        /// and different constants than those of the keystream. The key of a
+        /// child is therefore never part of the output of this generator, on
+        /// any stream or at any position. The child only depends on the key
+        /// of this generator, not on its position or stream.
+        impl SplittableRng for $rng {
+            fn split(&mut self) -> Self {
+                let mut seed = <Self as SeedableRng>::Seed::default();
+                self.fill_bytes(&mut seed);
+                Self::from_seed(seed)
+            }
+
+            fn fork(&self, index: u64) -> Self {
+                let mut state = self.0.core.state;
+                state[..4].copy_from_slice(&FORK_CONSTANTS);
+                set_counter(&mut state, 0);
+                state[14] = index as u32;
+                state[15] = (index >> 32) as u32;
+                let mut block = [0u32; BUF_WORDS];
+                chacha_blocks(&mut block, &state, $rounds);
+
+                let mut seed = <Self as SeedableRng>::Seed::default();
+                for (bytes, word) in seed.chunks_mut(4).zip(block.iter()) {
+                    for (i, b) in bytes.iter_mut().enumerate() {
+                        *b = (word >> (8 * i)) as u8;
+                    }
+                }
+                Self::from_seed(seed)
+            }
+        }
+
+        $(#[$core_attr])*
+        #[derive(Clone)]
+        #[cfg_attr(feature="serde1", derive(Serialize, Deserialize))]
+        pub struct $core {
+            state: [u32; STATE_WORDS],
+        }
+
+        // Custom Debug implementation that does not expose the internal state
+        impl fmt::Debug for $core {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, concat!(stringify!($core), " {{}}"))
+            }
+        }
+
+        impl BlockRngCore for $core {
+            type Item = u32;
+            type Results = Buffer;
+
+            fn generate(&mut self, results: &mut Self::Results) {
+                // For some reason extracting this part into a separate function
+                // improves performance by 50%.
+                fn core(results: &mut [u32; BUF_WORDS],
+                        state: &[u32; STATE_WORDS])
+                {
+                    chacha_blocks(results, state, $rounds);
+                }
+
+                core(results, &self.state);
+
+                // update 64-bit counter
+                let c = get_counter(&self.state);
+                set_counter(&mut self.state, c.wrapping_add(BUF_BLOCKS as u64));
+            }
+        }
+
+        impl SeedableRng for $core {
+            type Seed = [u8; SEED_WORDS*4];
+
+            fn from_seed(seed: Self::Seed) -> Self {
+                Self { state: init_state(seed) }
+            }
+        }
+
+        impl CryptoRng for $core {}
+
+        #[cfg(feature="zeroize")]
+        impl Drop for $core {
+            fn drop(&mut self) {
+                impls::zeroize(&mut self.state);
+            }
+        }
+
+        impl From<$core> for $rng {
+            fn from(core: $core) -> Self {
+                $rng(BlockRng::new(core))
+            }
+        }
     }
 }
 
-impl SeedableRng for ChaChaCore {
-    type Seed = [u8; SEED_WORDS*4];
-
-    fn from_seed(seed: Self::Seed) -> Self {
-        let mut seed_le = [0u32; SEED_WORDS];
-        le::read_u32_into(&seed, &mut seed_le);
-        Self {
-            state: [0x61707865, 0x3320646E, 0x79622D32, 0x6B206574, // constants
-                    seed_le[0], seed_le[1], seed_le[2], seed_le[3], // seed
-                    seed_le[4], seed_le[5], seed_le[6], seed_le[7], // seed
-                    0, 0, 0, 0], // counter
-         }
-    }
+chacha_impl! {
+    /// A cryptographically secure random number generator that uses the ChaCha
+    /// algorithm.
+    ///
+    /// ChaCha is a stream cipher designed by Daniel J. Bernstein[^1], that we use
+    /// as an RNG. It is an improved variant of the Salsa20 cipher family, which was
+    /// selected as one of the "stream ciphers suitable for widespread adoption" by
+    /// eSTREAM[^2].
+    ///
+    /// ChaCha uses add-rotate-xor (ARX) operations as its basis. These are safe
+    /// against timing attacks, although that is mostly a concern for ciphers and
+    /// not for RNGs. Also it is very suitable for SIMD implementation: with the
+    /// `simd_support` feature, four consecutive blocks are computed in
+    /// parallel using SIMD vectors. The output is identical either way.
+    ///
+    /// With the ChaCha algorithm it is possible to choose the number of rounds the
+    /// core algorithm should run. The number of rounds is a tradeoff between
+    /// performance and security, where 8 rounds is the minimum potentially
+    /// secure configuration, and 20 rounds is widely used as a conservative choice.
+    /// We use 20 rounds in this implementation; [`ChaCha12Rng`] and
+    /// [`ChaCha8Rng`] are the faster 12- and 8-round variants.
+    ///
+    /// We use a 64-bit counter and 64-bit stream identifier as in Bernstein's
+    /// implementation[^1] except that we use a stream identifier in place of a
+    /// nonce. A 64-bit counter over 64-byte (16 word) blocks allows 1 ZiB of output
+    /// before cycling, and the stream identifier allows 2<sup>64</sup> unique
+    /// streams of output per seed. Both counter and stream are initialized to zero
+    /// but may be set via [`set_word_pos`] and [`set_stream`].
+    ///
+    /// The word layout is:
+    ///
+    /// ```text
+    /// constant  constant  constant  constant
+    /// seed      seed      seed      seed
+    /// seed      seed      seed      seed
+    /// counter   counter   stream_id stream_id
+    /// ```
+    ///
+    /// This implementation uses an output buffer of sixteen `u32` words (64
+    /// words with the `simd_support` feature), and uses [`BlockRng`] to
+    /// implement the [`RngCore`] methods.
+    ///
+    /// [^1]: D. J. Bernstein, [*ChaCha, a variant of Salsa20*](
+    ///       https://cr.yp.to/chacha.html)
+    ///
+    /// [^2]: [eSTREAM: the ECRYPT Stream Cipher Project](
+    ///       http://www.ecrypt.eu.org/stream/)
+    ///
+    /// [`ChaCha12Rng`]: struct.ChaCha12Rng.html
+    /// [`ChaCha8Rng`]: struct.ChaCha8Rng.html
+    /// [`set_word_pos`]: #method.set_word_pos
+    /// [`set_stream`]: #method.set_stream
+    /// [`BlockRng`]: ../../../rand_core/block/struct.BlockRng.html
+    /// [`RngCore`]: ../../trait.RngCore.html
+    pub struct ChaChaRng;
+
+    /// The core of `ChaChaRng`, used with `BlockRng`.
+    pub struct ChaChaCore;
+
+    rounds = 20, id = *b"ChaCha20"
 }
 
-impl CryptoRng for ChaChaCore {}
+chacha_impl! {
+    /// A cryptographically secure random number generator that uses the ChaCha
+    /// algorithm with 12 rounds.
+    ///
+    /// This is identical to [`ChaChaRng`] except for the number of rounds,
+    /// and supports the same [`set_word_pos`] and [`set_stream`] API. With 12
+    /// rounds it is considerably faster than the 20-round variant while
+    /// retaining a comfortable security margin: the best known attacks break
+    /// 7 rounds.
+    ///
+    /// [`ChaChaRng`]: struct.ChaChaRng.html
+    /// [`set_word_pos`]: #method.set_word_pos
+    /// [`set_stream`]: #method.set_stream
+    pub struct ChaCha12Rng;
 
-impl From<ChaChaCore> for ChaChaRng {
-    fn from(core: ChaChaCore) -> Self {
-        ChaChaRng(BlockRng::new(core))
-    }
+    /// The core of `ChaCha12Rng`, used with `BlockRng`.
+    pub struct ChaCha12Core;
+
+    rounds = 12, id = *b"ChaCha12"
+}
+
+chacha_impl! {
+    /// A cryptographically secure random number generator that uses the ChaCha
+    /// algorithm with 8 rounds.
+    ///
+    /// This is identical to [`ChaChaRng`] except for the number of rounds,
+    /// and supports the same [`set_word_pos`] and [`set_stream`] API. 8 rounds
+    /// is the minimum potentially secure configuration; it is the fastest
+    /// ChaCha variant and well suited to simulations where there is no
+    /// adversary, but offers only a small security margin.
+    ///
+    /// [`ChaChaRng`]: struct.ChaChaRng.html
+    /// [`set_word_pos`]: #method.set_word_pos
+    /// [`set_stream`]: #method.set_stream
+    pub struct ChaCha8Rng;
+
+    /// The core of `ChaCha8Rng`, used with `BlockRng`.
+    pub struct ChaCha8Core;
+
+    rounds = 8, id = *b"ChaCha8\0"
 }
 
 #[cfg(test)]
 mod test {
     use {RngCore, SeedableRng};
-    use super::ChaChaRng;
+    use super::{ChaChaRng, ChaCha12Rng, ChaCha8Rng};
 
     #[test]
     fn test_chacha_construction() {
@@ -447,4 +777,201 @@ mod test {
             assert_eq!(rng.next_u32(), clone.next_u32());
         }
     }
+
+    #[test]
+    #[cfg(rust_1_26)]
+    fn test_chacha_seekable() {
+        use SeekableRng;
+
+        fn checkpoint<R: SeekableRng>(rng: &R) -> u128 {
+            rng.position()
+        }
+
+        let seed = [7u8; 32];
+        let mut rng1 = ChaChaRng::from_seed(seed);
+        for _ in 0..37 {
+            rng1.next_u32();
+        }
+        let pos = checkpoint(&rng1);
+        assert_eq!(pos, 37);
+
+        let mut rng2 = ChaChaRng::from_seed(seed);
+        rng2.seek(pos);
+        assert_eq!(rng1.next_u64(), rng2.next_u64());
+        rng1.advance(1000);
+        (&mut rng2).advance(1000);
+        assert_eq!(rng1.position(), 1039);
+        assert_eq!(rng1.next_u32(), rng2.next_u32());
+    }
+
+    #[test]
+    fn test_chacha_split_fork() {
+        use SplittableRng;
+        use super::{init_state, chacha_blocks, FORK_CONSTANTS, BUF_WORDS};
+
+        let seed = [0u8; 32];
+        let mut rng = ChaChaRng::from_seed(seed);
+        let mut child1 = rng.split();
+        let mut child2 = rng.split();
+        assert!(child1.next_u64() != child2.next_u64());
+
+        // The key of a child is the first 8 words of the block with the fork
+        // constants on stream `index`, independent of the position of the
+        // parent.
+        let mut fork = rng.fork(5);
+        let mut state = init_state(seed);
+        state[..4].copy_from_slice(&FORK_CONSTANTS);
+        state[14] = 5;
+        let mut block = [0u32; BUF_WORDS];
+        chacha_blocks(&mut block, &state, 20);
+        let mut key = [0u8; 32];
+        for (bytes, word) in key.chunks_mut(4).zip(block.iter()) {
+            for (i, b) in bytes.iter_mut().enumerate() {
+                *b = (word >> (8 * i)) as u8;
+            }
+        }
+        let mut expected = ChaChaRng::from_seed(key);
+        assert_eq!(fork.next_u64(), expected.next_u64());
+        assert!(rng.fork(6).next_u64() != rng.fork(5).next_u64());
+
+        // The key is not the keystream of the parent at the end of stream
+        // `index`.
+        let mut clone = ChaChaRng::from_seed(seed);
+        clone.set_stream(5);
+        clone.0.core.state[12] = !0;
+        clone.0.core.state[13] = !0;
+        let mut end = [0u8; 32];
+        clone.fill_bytes(&mut end);
+        assert!(end != key);
+    }
+
+    #[test]
+    fn test_chacha12_true_values() {
+        // Test vector TC1 (256-bit key) from
+        // https://tools.ietf.org/html/draft-strombergson-chacha-test-vectors-00
+        let seed = [0u8; 32];
+        let mut rng = ChaCha12Rng::from_seed(seed);
+
+        let mut results = [0u32; 16];
+        for i in results.iter_mut() { *i = rng.next_u32(); }
+        let expected = [0x6a9af49b, 0x53f95507, 0x12ce1f81, 0xd583265f,
+                        0xbbc32904, 0x1474e049, 0xa589007e, 0x5f15ae2e,
+                        0x79f86405, 0xc0e37ad2, 0x3428e82c, 0x798cfaac,
+                        0x2c9f623a, 0x1969dea0, 0x2fe80b61, 0xbe261341];
+        assert_eq!(results, expected);
+
+        for i in results.iter_mut() { *i = rng.next_u32(); }
+        let expected = [0x4188d50b, 0xfe743e20, 0x3371fc86, 0x3d17e08c,
+                        0xb7eb28c6, 0xcccbbd19, 0x21851515, 0xb489c04c,
+                        0xcd8d2542, 0x11f14ca1, 0x97b802c6, 0x43c88c1b,
+                        0xca461ee9, 0xc0515190, 0xb0a64427, 0x1693e617];
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_chacha8_true_values() {
+        // Test vector TC1 (256-bit key) from
+        // https://tools.ietf.org/html/draft-strombergson-chacha-test-vectors-00
+        let seed = [0u8; 32];
+        let mut rng = ChaCha8Rng::from_seed(seed);
+
+        let mut results = [0u32; 16];
+        for i in results.iter_mut() { *i = rng.next_u32(); }
+        let expected = [0x2fef003e, 0xd6405f89, 0xe8b85b7f, 0xa1a5091f,
+                        0xc30e842c, 0x3b7f9ace, 0x88e11b18, 0x1e1a71ef,
+                        0x72e14c98, 0x416f21b9, 0x6753449f, 0x19566d45,
+                        0xa3424a31, 0x01b086da, 0xb8fd7b38, 0x42fe0c0e];
+        assert_eq!(results, expected);
+
+        for i in results.iter_mut() { *i = rng.next_u32(); }
+        let expected = [0x0dfaaed2, 0x51c1a5ea, 0x6cdb0abf, 0xada5f201,
+                        0x1258fdc0, 0xaaa2f959, 0x8f0ff2dc, 0x6ba266d5,
+                        0x38ec3250, 0x98dac5bb, 0x566f0cee, 0x652a878b,
+                        0x25bf8aa0, 0xbb21eb1d, 0xd8e5564b, 0xaa681e82];
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    #[cfg(rust_1_26)]
+    fn test_chacha8_word_pos() {
+        let seed = [0,0,0,0, 1,0,0,0, 2,0,0,0, 3,0,0,0, 4,0,0,0, 5,0,0,0, 6,0,0,0, 7,0,0,0];
+        let mut rng1 = ChaCha8Rng::from_seed(seed);
+        for _ in 0..37 { rng1.next_u32(); }
+        assert_eq!(rng1.get_word_pos(), 37);
+
+        let mut rng2 = ChaCha8Rng::from_seed(seed);
+        rng2.set_word_pos(37);
+        for _ in 0..20 {
+            assert_eq!(rng1.next_u32(), rng2.next_u32());
+        }
+    }
+
+    #[test]
+    fn test_chacha12_clone_streams() {
+        let seed = [0,0,0,0, 1,0,0,0, 2,0,0,0, 3,0,0,0, 4,0,0,0, 5,0,0,0, 6,0,0,0, 7,0,0,0];
+        let mut rng = ChaCha12Rng::from_seed(seed);
+        let mut clone = rng.clone();
+        for _ in 0..16 {
+            assert_eq!(rng.next_u64(), clone.next_u64());
+        }
+
+        rng.set_stream(51);
+        for _ in 0..7 {
+            assert!(rng.next_u32() != clone.next_u32());
+        }
+        clone.set_stream(51);   // switch part way through block
+        for _ in 7..16 {
+            assert_eq!(rng.next_u32(), clone.next_u32());
+        }
+    }
+
+    #[cfg(feature="serde1")]
+    #[test]
+    fn test_chacha_serde() {
+        use bincode;
+        use std::io::{BufWriter, BufReader};
+
+        let mut rng = ChaChaRng::from_seed([0x55; 32]);
+        rng.next_u32();
+
+        let buf: Vec<u8> = Vec::new();
+        let mut buf = BufWriter::new(buf);
+        bincode::serialize_into(&mut buf, &rng).expect("Could not serialize");
+
+        let buf = buf.into_inner().unwrap();
+        let mut read = BufReader::new(&buf[..]);
+        let mut deserialized: ChaChaRng =
+            bincode::deserialize_from(&mut read).expect("Could not deserialize");
+
+        for _ in 0..100 {
+            assert_eq!(rng.next_u32(), deserialized.next_u32());
+        }
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn test_chacha_state_bytes() {
+        use rand_core::state::StateBytes;
+        use ErrorKind;
+
+        let mut rng = ChaChaRng::from_seed([0x55; 32]);
+        rng.set_stream(7);
+        for _ in 0..40 {
+            let bytes = rng.to_state_bytes();
+            assert_eq!(bytes.len(), 64);
+            assert_eq!(&bytes[..12], b"ChaCha20\x01\0\0\0");
+            let mut restored = ChaChaRng::from_state_bytes(&bytes).unwrap();
+            let mut clone = rng.clone();
+            for _ in 0..20 {
+                assert_eq!(restored.next_u32(), clone.next_u32());
+            }
+            rng.next_u32();
+        }
+
+        let bytes = rng.to_state_bytes();
+        let err = ChaCha8Rng::from_state_bytes(&bytes).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Unavailable);
+        let err = ChaChaRng::from_state_bytes(&bytes[..63]).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Unavailable);
+    }
 }
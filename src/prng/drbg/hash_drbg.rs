@@ -0,0 +1,345 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The Hash_DRBG random number generator.
+
+use core::fmt;
+use core::marker::PhantomData;
+use rand_core::{CryptoRng, RngCore, SeedableRng, Error, ErrorKind, impls, le};
+use rand_core::block::BlockRngCore;
+use rand_core::state::{StateBytes, StateReader, StateWriter};
+use super::{DrbgHash, DrbgSeed, Sha256, hash_parts, MAX_OUTPUT_LEN,
+            MAX_SEED_LEN, MAX_REQUEST_LEN, MAX_RESEED_INTERVAL, ENTROPY_LEN};
+
+/// A cryptographically secure random number generator that uses the
+/// Hash_DRBG mechanism of NIST SP 800-90A.
+///
+/// Hash_DRBG keeps a value `V` and a constant `C` of the seed length of the
+/// hash function (440 bits for SHA-256, 888 bits for SHA-512), and generates
+/// output by hashing `V`, `V + 1`, `V + 2`, etc. After every request `V` is
+/// updated, which gives it backtracking resistance: a compromise of the state
+/// does not reveal previous output.
+///
+/// Hash_DRBG needs about half the hash computations of [`HmacDrbg`] for long
+/// requests.
+///
+/// The hash function `H` is [`Sha256`] by default; [`Sha512`] is faster on
+/// 64-bit platforms. See the [module documentation] for the generate and
+/// reseed semantics, and how to use it with `ReseedingRng`.
+///
+/// [`HmacDrbg`]: struct.HmacDrbg.html
+/// [`Sha256`]: struct.Sha256.html
+/// [`Sha512`]: struct.Sha512.html
+/// [module documentation]: index.html
+#[derive(Clone)]
+pub struct HashDrbg<H: DrbgHash = Sha256> {
+    v: [u8; MAX_SEED_LEN],
+    c: [u8; MAX_SEED_LEN],
+    reseed_counter: u64,
+    reseed_interval: u64,
+    hash: PhantomData<H>,
+}
+
+impl<H: DrbgHash> HashDrbg<H> {
+    /// Instantiate a new Hash_DRBG from entropy input, a nonce and a
+    /// personalization string (which may be empty).
+    ///
+    /// # Panics
+    ///
+    /// If `entropy` is shorter than the security strength of 32 bytes.
+    pub fn new(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Self {
+        assert!(entropy.len() >= H::SECURITY_STRENGTH,
+                "HashDrbg: insufficient entropy input");
+        let mut drbg = HashDrbg {
+            v: [0; MAX_SEED_LEN],
+            c: [0; MAX_SEED_LEN],
+            reseed_counter: 1,
+            reseed_interval: MAX_RESEED_INTERVAL,
+            hash: PhantomData,
+        };
+        hash_df::<H>(&[entropy, nonce, personalization],
+                     &mut drbg.v[..H::SEED_LEN]);
+        drbg.derive_c();
+        drbg
+    }
+
+    /// Reseed with new entropy input and additional input (which may be
+    /// empty).
+    ///
+    /// # Panics
+    ///
+    /// If `entropy` is shorter than the security strength of 32 bytes.
+    pub fn reseed(&mut self, entropy: &[u8], additional_input: &[u8]) {
+        assert!(entropy.len() >= H::SECURITY_STRENGTH,
+                "HashDrbg: insufficient entropy input");
+        let mut v = [0u8; MAX_SEED_LEN];
+        hash_df::<H>(&[&[1], &self.v[..H::SEED_LEN], entropy, additional_input],
+                     &mut v[..H::SEED_LEN]);
+        self.v = v;
+        self.derive_c();
+        self.reseed_counter = 1;
+    }
+
+    /// Fill `dest` with random data, with additional input (which may be
+    /// empty).
+    ///
+    /// Returns an error of kind `ErrorKind::NotReady` if the generator must
+    /// be reseeded first.
+    ///
+    /// # Panics
+    ///
+    /// If `dest` is longer than `MAX_REQUEST_LEN`.
+    pub fn generate(&mut self, dest: &mut [u8], additional_input: &[u8])
+        -> Result<(), Error>
+    {
+        assert!(dest.len() <= MAX_REQUEST_LEN,
+                "HashDrbg: request exceeds MAX_REQUEST_LEN");
+        if self.reseed_counter > self.reseed_interval {
+            return Err(Error::new(ErrorKind::NotReady,
+                                  "HashDrbg: reseed required"));
+        }
+
+        let sl = H::SEED_LEN;
+        let n = H::OUTPUT_LEN;
+        let mut w = [0u8; MAX_OUTPUT_LEN];
+        if !additional_input.is_empty() {
+            hash_parts::<H>(&[&[2], &self.v[..sl], additional_input],
+                            &mut w[..n]);
+            add_be(&mut self.v[..sl], &w[..n]);
+        }
+
+        // Hashgen
+        let mut data = self.v;
+        for chunk in dest.chunks_mut(n) {
+            hash_parts::<H>(&[&data[..sl]], &mut w[..n]);
+            chunk.copy_from_slice(&w[..chunk.len()]);
+            add_be(&mut data[..sl], &[1]);
+        }
+
+        hash_parts::<H>(&[&[3], &self.v[..sl]], &mut w[..n]);
+        add_be(&mut self.v[..sl], &w[..n]);
+        add_be(&mut self.v[..sl], &self.c[..sl]);
+        let mut counter = [0u8; 8];
+        for (i, b) in counter.iter_mut().enumerate() {
+            *b = (self.reseed_counter >> (56 - 8 * i)) as u8;
+        }
+        add_be(&mut self.v[..sl], &counter);
+        self.reseed_counter += 1;
+
+        // Wipe the copy of `V` and the hash outputs derived from it.
+        #[cfg(feature="zeroize")]
+        {
+            impls::zeroize(&mut data);
+            impls::zeroize(&mut w);
+        }
+        Ok(())
+    }
+
+    /// Set the number of generate requests after which the generator must be
+    /// reseeded.
+    ///
+    /// # Panics
+    ///
+    /// If `interval` is zero or larger than `MAX_RESEED_INTERVAL`.
+    pub fn set_reseed_interval(&mut self, interval: u64) {
+        assert!(interval > 0 && interval <= MAX_RESEED_INTERVAL,
+                "HashDrbg: invalid reseed interval");
+        self.reseed_interval = interval;
+    }
+
+    // Wipe the secret state `V` and `C`.
+    #[cfg(feature="zeroize")]
+    fn zeroize(&mut self) {
+        impls::zeroize(&mut self.v);
+        impls::zeroize(&mut self.c);
+    }
+
+    // C = Hash_df(0x00 || V)
+    fn derive_c(&mut self) {
+        let sl = H::SEED_LEN;
+        hash_df::<H>(&[&[0], &self.v[..sl]], &mut self.c[..sl]);
+    }
+}
+
+// The Hash_df derivation function of the concatenation of `input`, filling
+// `out`.
+fn hash_df<H: DrbgHash>(input: &[&[u8]], out: &mut [u8]) {
+    let bits = out.len() as u32 * 8;
+    let bits = [(bits >> 24) as u8, (bits >> 16) as u8,
+                (bits >> 8) as u8, bits as u8];
+    let mut temp = [0u8; MAX_OUTPUT_LEN];
+    for (counter, chunk) in out.chunks_mut(H::OUTPUT_LEN).enumerate() {
+        let mut h = H::default();
+        h.update(&[counter as u8 + 1]);
+        h.update(&bits);
+        for part in input {
+            h.update(part);
+        }
+        h.finish(&mut temp[..H::OUTPUT_LEN]);
+        chunk.copy_from_slice(&temp[..chunk.len()]);
+    }
+}
+
+// Add the big-endian number `src` to `dst`, modulo 2^(8 * dst.len()).
+fn add_be(dst: &mut [u8], src: &[u8]) {
+    let mut carry = 0u16;
+    let mut src = src.iter().rev();
+    for d in dst.iter_mut().rev() {
+        let sum = *d as u16 + *src.next().unwrap_or(&0) as u16 + carry;
+        *d = sum as u8;
+        carry = sum >> 8;
+    }
+}
+
+drbg_impl!(HashDrbg);
+
+/// The state is the seed length of the hash function as `u32`, the value `V` and the
+/// constant `C` of the seed length, and the reseed counter and reseed
+/// interval as `u64`. The state can only be restored with the same hash
+/// function; this is checked through the seed length only.
+impl<H: DrbgHash> StateBytes for HashDrbg<H> {
+    const ALGORITHM_ID: [u8; 8] = *b"HASHDRBG";
+    const FORMAT_VERSION: u32 = 1;
+
+    fn state_len() -> usize {
+        4 + 2 * H::SEED_LEN + 16
+    }
+
+    fn write_state(&self, state: &mut [u8]) {
+        let n = H::SEED_LEN;
+        let mut w = StateWriter::new(state);
+        w.write_u32(n as u32);
+        w.write_bytes(&self.v[..n]);
+        w.write_bytes(&self.c[..n]);
+        w.write_u64(self.reseed_counter);
+        w.write_u64(self.reseed_interval);
+    }
+
+    fn read_state(state: &[u8]) -> Result<Self, Error> {
+        let n = H::SEED_LEN;
+        let mut r = StateReader::new(state);
+        if r.read_u32() as usize != n {
+            return Err(Error::new(ErrorKind::Unavailable,
+                                  "state bytes: HashDrbg hash mismatch"));
+        }
+        let mut drbg = HashDrbg {
+            v: [0; MAX_SEED_LEN],
+            c: [0; MAX_SEED_LEN],
+            reseed_counter: 0,
+            reseed_interval: 0,
+            hash: PhantomData,
+        };
+        r.read_bytes(&mut drbg.v[..n]);
+        r.read_bytes(&mut drbg.c[..n]);
+        drbg.reseed_counter = r.read_u64();
+        drbg.reseed_interval = r.read_u64();
+        if drbg.reseed_counter == 0 || drbg.reseed_interval == 0
+            || drbg.reseed_interval > MAX_RESEED_INTERVAL
+        {
+            return Err(Error::new(ErrorKind::Unavailable,
+                                  "state bytes: invalid HashDrbg counter"));
+        }
+        Ok(drbg)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {RngCore, SeedableRng, ErrorKind};
+    use super::super::{Sha256, Sha512, DrbgSeed, unhex};
+    use super::HashDrbg;
+
+    #[test]
+    // NIST CAVS 14.3 Hash_DRBG no reseed (SHA-256, 256+128, 0, 0) #0
+    fn test_hash_drbg_sha256_cavp() {
+        let mut entropy = [0u8; 48];
+        unhex("a65ad0f345db4e0effe875c3a2e71f42c7129d620ff5c119a9ef55f05185e0fb\
+               8581f9317517276e06e9607ddbcbcc2e", &mut entropy);
+        let mut expected = [0u8; 128];
+        unhex("d3e160c35b99f340b2628264d1751060e0045da383ff57a57d73a673d2b8d80d\
+               aaf6a6c35a91bb4579d73fd0c8fed111b0391306828adfed528f018121b3febd\
+               c343e797b87dbb63db1333ded9d1ece177cfa6b71fe8ab1da46624ed6415e51c\
+               cde2c7ca86e283990eeaeb91120415528b2295910281b02dd431f4c9f70427df",
+              &mut expected);
+
+        let mut drbg = HashDrbg::<Sha256>::new(&entropy[..32], &entropy[32..],
+                                               &[]);
+        let mut output = [0u8; 128];
+        drbg.generate(&mut output, &[]).unwrap();
+        drbg.generate(&mut output, &[]).unwrap();
+        assert_eq!(&output[..], &expected[..]);
+    }
+
+    #[test]
+    // NIST CAVS 14.3 Hash_DRBG PR False (SHA-256, 256+128, 0, 0)
+    fn test_hash_drbg_sha256_cavp_reseed() {
+        let mut entropy = [0u8; 80];
+        unhex("63363377e41e86468deb0ab4a8ed683f6a134e47e014c700454e81e95358a569\
+               808aa38f2a72a62359915a9f8a04ca68\
+               e62b8a8ee8f141b6980566e3bfe3c04903dad4ac2cdf9f2280010a6739bc83d3",
+              &mut entropy);
+        let mut expected = [0u8; 128];
+        unhex("04eec63bb231df2c630a1afbe724949d005a587851e1aa795e477347c8b05662\
+               1c18bddcdd8d99fc5fc2b92053d8cfacfb0bb8831205fad1ddd6c071318a6018\
+               f03b73f5ede4d4d071f9de03fd7aea105d9299b8af99aa075bdb4db9aa28c18d\
+               174b56ee2a014d098896ff2282c955a81969e069fa8ce007a180183a07dfae17",
+              &mut expected);
+
+        let mut drbg = HashDrbg::<Sha256>::new(&entropy[..32],
+                                               &entropy[32..48], &[]);
+        drbg.reseed(&entropy[48..], &[]);
+        let mut output = [0u8; 128];
+        drbg.generate(&mut output, &[]).unwrap();
+        drbg.generate(&mut output, &[]).unwrap();
+        assert_eq!(&output[..], &expected[..]);
+    }
+
+    #[test]
+    fn test_hash_drbg_reseed_interval() {
+        let mut drbg = HashDrbg::<Sha512>::from_seed(DrbgSeed([7; 48]));
+        drbg.set_reseed_interval(2);
+        let mut buf = [0u8; 16];
+        assert!(drbg.generate(&mut buf, &[]).is_ok());
+        assert!(drbg.try_fill_bytes(&mut buf).is_ok());
+        let err = drbg.try_fill_bytes(&mut buf).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NotReady);
+
+        drbg.reseed(&[8; 32], &[]);
+        assert!(drbg.try_fill_bytes(&mut buf).is_ok());
+    }
+
+    #[test]
+    fn test_hash_drbg_clone() {
+        let mut drbg1 = HashDrbg::<Sha256>::from_seed(DrbgSeed([0x55; 48]));
+        let mut drbg2 = drbg1.clone();
+        for _ in 0..16 {
+            assert_eq!(drbg1.next_u64(), drbg2.next_u64());
+        }
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn test_hash_drbg_state_bytes() {
+        use rand_core::state::StateBytes;
+
+        let mut drbg = HashDrbg::<Sha256>::from_seed(DrbgSeed([0x55; 48]));
+        drbg.next_u32();
+
+        let bytes = drbg.to_state_bytes();
+        assert_eq!(bytes.len(), 12 + 4 + 2 * 55 + 16);
+        let mut restored = HashDrbg::<Sha256>::from_state_bytes(&bytes).unwrap();
+        for _ in 0..16 {
+            assert_eq!(drbg.next_u64(), restored.next_u64());
+        }
+
+        let err = HashDrbg::<Sha512>::from_state_bytes(&bytes).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Unavailable);
+    }
+}
@@ -0,0 +1,363 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The HMAC_DRBG random number generator.
+
+use core::fmt;
+use core::marker::PhantomData;
+use rand_core::{CryptoRng, RngCore, SeedableRng, Error, ErrorKind, impls, le};
+use rand_core::block::BlockRngCore;
+use rand_core::state::{StateBytes, StateReader, StateWriter};
+use super::{DrbgHash, DrbgSeed, Sha256, hash_parts, MAX_OUTPUT_LEN,
+            MAX_BLOCK_LEN, MAX_REQUEST_LEN, MAX_RESEED_INTERVAL, ENTROPY_LEN};
+
+/// A cryptographically secure random number generator that uses the
+/// HMAC_DRBG mechanism of NIST SP 800-90A.
+///
+/// HMAC_DRBG keeps a key `K` and a value `V` of the output length of the hash
+/// function, and generates output by iterating `V = HMAC(K, V)`. After every
+/// request both are updated, which gives it backtracking resistance: a
+/// compromise of the state does not reveal previous output.
+///
+/// The hash function `H` is [`Sha256`] by default; [`Sha512`] is faster on
+/// 64-bit platforms. See the [module documentation] for the generate and
+/// reseed semantics, and how to use it with `ReseedingRng`.
+///
+/// [`Sha256`]: struct.Sha256.html
+/// [`Sha512`]: struct.Sha512.html
+/// [module documentation]: index.html
+#[derive(Clone)]
+pub struct HmacDrbg<H: DrbgHash = Sha256> {
+    k: [u8; MAX_OUTPUT_LEN],
+    v: [u8; MAX_OUTPUT_LEN],
+    reseed_counter: u64,
+    reseed_interval: u64,
+    hash: PhantomData<H>,
+}
+
+impl<H: DrbgHash> HmacDrbg<H> {
+    /// Instantiate a new HMAC_DRBG from entropy input, a nonce and a
+    /// personalization string (which may be empty).
+    ///
+    /// # Panics
+    ///
+    /// If `entropy` is shorter than the security strength of 32 bytes.
+    pub fn new(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Self {
+        assert!(entropy.len() >= H::SECURITY_STRENGTH,
+                "HmacDrbg: insufficient entropy input");
+        let mut drbg = HmacDrbg {
+            k: [0; MAX_OUTPUT_LEN],
+            v: [1; MAX_OUTPUT_LEN],
+            reseed_counter: 1,
+            reseed_interval: MAX_RESEED_INTERVAL,
+            hash: PhantomData,
+        };
+        drbg.update(&[entropy, nonce, personalization]);
+        drbg
+    }
+
+    /// Reseed with new entropy input and additional input (which may be
+    /// empty).
+    ///
+    /// # Panics
+    ///
+    /// If `entropy` is shorter than the security strength of 32 bytes.
+    pub fn reseed(&mut self, entropy: &[u8], additional_input: &[u8]) {
+        assert!(entropy.len() >= H::SECURITY_STRENGTH,
+                "HmacDrbg: insufficient entropy input");
+        self.update(&[entropy, additional_input]);
+        self.reseed_counter = 1;
+    }
+
+    /// Fill `dest` with random data, with additional input (which may be
+    /// empty).
+    ///
+    /// Returns an error of kind `ErrorKind::NotReady` if the generator must
+    /// be reseeded first.
+    ///
+    /// # Panics
+    ///
+    /// If `dest` is longer than `MAX_REQUEST_LEN`.
+    pub fn generate(&mut self, dest: &mut [u8], additional_input: &[u8])
+        -> Result<(), Error>
+    {
+        assert!(dest.len() <= MAX_REQUEST_LEN,
+                "HmacDrbg: request exceeds MAX_REQUEST_LEN");
+        if self.reseed_counter > self.reseed_interval {
+            return Err(Error::new(ErrorKind::NotReady,
+                                  "HmacDrbg: reseed required"));
+        }
+
+        if !additional_input.is_empty() {
+            self.update(&[additional_input]);
+        }
+        let n = H::OUTPUT_LEN;
+        for chunk in dest.chunks_mut(n) {
+            let mut v = [0u8; MAX_OUTPUT_LEN];
+            hmac::<H>(&self.k[..n], &[&self.v[..n]], &mut v[..n]);
+            self.v = v;
+            chunk.copy_from_slice(&v[..chunk.len()]);
+        }
+        self.update(&[additional_input]);
+        self.reseed_counter += 1;
+        Ok(())
+    }
+
+    // Wipe the secret state `K` and `V`.
+    #[cfg(feature="zeroize")]
+    fn zeroize(&mut self) {
+        impls::zeroize(&mut self.k);
+        impls::zeroize(&mut self.v);
+    }
+
+    /// Set the number of generate requests after which the generator must be
+    /// reseeded.
+    ///
+    /// # Panics
+    ///
+    /// If `interval` is zero or larger than `MAX_RESEED_INTERVAL`.
+    pub fn set_reseed_interval(&mut self, interval: u64) {
+        assert!(interval > 0 && interval <= MAX_RESEED_INTERVAL,
+                "HmacDrbg: invalid reseed interval");
+        self.reseed_interval = interval;
+    }
+
+    // The HMAC_DRBG update function, with `provided_data` the concatenation
+    // of `data`.
+    fn update(&mut self, data: &[&[u8]]) {
+        let n = H::OUTPUT_LEN;
+        let empty = data.iter().all(|d| d.is_empty());
+        for &sep in [[0u8], [1u8]].iter() {
+            let mut k = [0u8; MAX_OUTPUT_LEN];
+            {
+                let v: &[u8] = &self.v[..n];
+                let mut parts: [&[u8]; 5] = [v, &sep, &[], &[], &[]];
+                parts[2..2 + data.len()].copy_from_slice(data);
+                hmac::<H>(&self.k[..n], &parts[..2 + data.len()], &mut k[..n]);
+            }
+            self.k = k;
+            let mut v = [0u8; MAX_OUTPUT_LEN];
+            hmac::<H>(&self.k[..n], &[&self.v[..n]], &mut v[..n]);
+            self.v = v;
+            if empty { break; }
+        }
+    }
+}
+
+// HMAC of the concatenation of `parts`, with a key not longer than the block
+// length of `H`.
+fn hmac<H: DrbgHash>(key: &[u8], parts: &[&[u8]], out: &mut [u8]) {
+    let mut pad = [0u8; MAX_BLOCK_LEN];
+    let pad = &mut pad[..H::BLOCK_LEN];
+    pad[..key.len()].copy_from_slice(key);
+
+    for b in pad.iter_mut() { *b ^= 0x36; }
+    let mut inner = H::default();
+    inner.update(pad);
+    for part in parts {
+        inner.update(part);
+    }
+    let mut inner_hash = [0u8; MAX_OUTPUT_LEN];
+    inner.finish(&mut inner_hash[..H::OUTPUT_LEN]);
+
+    for b in pad.iter_mut() { *b ^= 0x36 ^ 0x5c; }
+    hash_parts::<H>(&[pad, &inner_hash[..H::OUTPUT_LEN]], out);
+}
+
+drbg_impl!(HmacDrbg);
+
+/// The state is the output length of the hash function as `u32`, the key `K` and the
+/// value `V` of the output length, and the reseed counter and reseed
+/// interval as `u64`. The state can only be restored with the same hash
+/// function; this is checked through the output length only.
+impl<H: DrbgHash> StateBytes for HmacDrbg<H> {
+    const ALGORITHM_ID: [u8; 8] = *b"HMACDRBG";
+    const FORMAT_VERSION: u32 = 1;
+
+    fn state_len() -> usize {
+        4 + 2 * H::OUTPUT_LEN + 16
+    }
+
+    fn write_state(&self, state: &mut [u8]) {
+        let n = H::OUTPUT_LEN;
+        let mut w = StateWriter::new(state);
+        w.write_u32(n as u32);
+        w.write_bytes(&self.k[..n]);
+        w.write_bytes(&self.v[..n]);
+        w.write_u64(self.reseed_counter);
+        w.write_u64(self.reseed_interval);
+    }
+
+    fn read_state(state: &[u8]) -> Result<Self, Error> {
+        let n = H::OUTPUT_LEN;
+        let mut r = StateReader::new(state);
+        if r.read_u32() as usize != n {
+            return Err(Error::new(ErrorKind::Unavailable,
+                                  "state bytes: HmacDrbg hash mismatch"));
+        }
+        let mut drbg = HmacDrbg {
+            k: [0; MAX_OUTPUT_LEN],
+            v: [0; MAX_OUTPUT_LEN],
+            reseed_counter: 0,
+            reseed_interval: 0,
+            hash: PhantomData,
+        };
+        r.read_bytes(&mut drbg.k[..n]);
+        r.read_bytes(&mut drbg.v[..n]);
+        drbg.reseed_counter = r.read_u64();
+        drbg.reseed_interval = r.read_u64();
+        if drbg.reseed_counter == 0 || drbg.reseed_interval == 0
+            || drbg.reseed_interval > MAX_RESEED_INTERVAL
+        {
+            return Err(Error::new(ErrorKind::Unavailable,
+                                  "state bytes: invalid HmacDrbg counter"));
+        }
+        Ok(drbg)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {RngCore, SeedableRng, ErrorKind};
+    use super::super::{Sha256, Sha512, DrbgSeed, unhex};
+    use super::HmacDrbg;
+
+    #[test]
+    // NIST CAVS 14.3 HMAC_DRBG no reseed (SHA-256, 256+128, 256, 256) #0
+    fn test_hmac_drbg_sha256_cavp() {
+        let mut entropy = [0u8; 48];
+        unhex("5d3286bc53a258a53ba781e2c4dcd79a790e43bbe0e89fb3eed39086be34174b\
+               c5422294b7318952ace7055ab7570abf", &mut entropy);
+        let mut pers = [0u8; 32];
+        unhex("2dba094d008e150d51c4135bb2f03dcde9cbf3468a12908a1b025c120c985b9d",
+              &mut pers);
+        let mut add1 = [0u8; 32];
+        unhex("793a7ef8f6f0482beac542bb785c10f8b7b406a4de92667ab168ecc2cf7573c6",
+              &mut add1);
+        let mut add2 = [0u8; 32];
+        unhex("2238cdb4e23d629fe0c2a83dd8d5144ce1a6229ef41dabe2a99ff722e510b530",
+              &mut add2);
+        let mut expected = [0u8; 128];
+        unhex("d04678198ae7e1aeb435b45291458ffde0891560748b43330eaf866b5a6385e7\
+               4c6fa5a5a44bdb284d436e98d244018d6acedcdfa2e9f499d8089e4db86ae89a\
+               6ab2d19cb705e2f048f97fb597f04106a1fa6a1416ad3d859118e079a0c319eb\
+               95686f4cbcce3b5101c7a0b010ef029c4ef6d06cdfac97efb9773891688c37cf",
+              &mut expected);
+
+        let mut drbg = HmacDrbg::<Sha256>::new(&entropy[..32], &entropy[32..],
+                                               &pers);
+        let mut output = [0u8; 128];
+        drbg.generate(&mut output, &add1).unwrap();
+        drbg.generate(&mut output, &add2).unwrap();
+        assert_eq!(&output[..], &expected[..]);
+    }
+
+    #[test]
+    // NIST CAVS 14.3 HMAC_DRBG PR False (SHA-512, 256+128, 256, 256) #0
+    fn test_hmac_drbg_sha512_cavp_reseed() {
+        let mut entropy = [0u8; 80];
+        unhex("da740cbc36057a8e282ae717fe7dfbb245e9e5d49908a0119c5dbcf0a1f2d5ab\
+               46561ff612217ba3ff91baa06d4b5440\
+               1d61d4d8a41c3254b92104fd555adae0569d1835bb52657ec7fbba0fe03579c5",
+              &mut entropy);
+        let mut pers = [0u8; 32];
+        unhex("fc227293523ecb5b1e28c87863626627d958acc558a672b148ce19e2abd2dde4",
+              &mut pers);
+        let mut add_reseed = [0u8; 32];
+        unhex("b9ed8e35ad018a375b61189c8d365b00507cb1b4510d21cac212356b5bbaa8b2",
+              &mut add_reseed);
+        let mut add1 = [0u8; 32];
+        unhex("b7998998eaf9e5d34e64ff7f03de765b31f407899d20535573e670c1b402c26a",
+              &mut add1);
+        let mut add2 = [0u8; 32];
+        unhex("2089d49d63e0c4df58879d0cb1ba998e5b3d1a7786b785e7cf13ca5ea5e33cfd",
+              &mut add2);
+        let mut expected = [0u8; 256];
+        unhex("5b70f3e4da95264233efbab155b828d4e231b67cc92757feca407cc9615a6608\
+               71cb07ad1a2e9a99412feda8ee34dc9c57fa08d3f8225b30d29887d20907d123\
+               30fffd14d1697ba0756d37491b0a8814106e46c8677d49d9157109c402ad0c24\
+               7a2f50cd5d99e538c850b906937a05dbb8888d984bc77f6ca00b0e3bc97b16d6\
+               d25814a54aa12143afddd8b2263690565d545f4137e593bb3ca88a37b0aadf79\
+               726b95c61906257e6dc47acd5b6b7e4b534243b13c16ad5a0a1163c0099fce43\
+               f428cd27c3e6463cf5e9a9621f4b3d0b3d4654316f4707675df39278d5783823\
+               049477dcce8c57fdbd576711c91301e9bd6bb0d3e72dc46d480ed8f61fd63811",
+              &mut expected);
+
+        let mut drbg = HmacDrbg::<Sha512>::new(&entropy[..32],
+                                               &entropy[32..48], &pers);
+        drbg.reseed(&entropy[48..], &add_reseed);
+        let mut output = [0u8; 256];
+        drbg.generate(&mut output, &add1).unwrap();
+        drbg.generate(&mut output, &add2).unwrap();
+        assert_eq!(&output[..], &expected[..]);
+    }
+
+    #[test]
+    fn test_hmac_drbg_reseed_interval() {
+        let mut drbg = HmacDrbg::<Sha256>::from_seed(DrbgSeed([7; 48]));
+        drbg.set_reseed_interval(2);
+        let mut buf = [0u8; 16];
+        assert!(drbg.generate(&mut buf, &[]).is_ok());
+        assert!(drbg.try_fill_bytes(&mut buf).is_ok());
+        let err = drbg.try_fill_bytes(&mut buf).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NotReady);
+
+        drbg.reseed(&[8; 32], &[]);
+        assert!(drbg.try_fill_bytes(&mut buf).is_ok());
+    }
+
+    #[test]
+    fn test_hmac_drbg_from_seed() {
+        let seed = [0x55u8; 48];
+        let mut drbg1 = HmacDrbg::<Sha256>::from_seed(DrbgSeed(seed));
+        let mut drbg2 = HmacDrbg::<Sha256>::new(&seed[..32], &seed[32..], &[]);
+        let mut buf1 = [0u8; 100];
+        let mut buf2 = [0u8; 100];
+        drbg1.fill_bytes(&mut buf1);
+        drbg2.generate(&mut buf2, &[]).unwrap();
+        assert_eq!(&buf1[..], &buf2[..]);
+    }
+
+    #[cfg(feature="std")]
+    #[test]
+    fn test_hmac_drbg_reseeding_rng() {
+        use {Rng, FromEntropy};
+        use rngs::EntropyRng;
+        use rngs::adapter::ReseedingRng;
+
+        let drbg = HmacDrbg::<Sha512>::from_entropy();
+        let mut rng = ReseedingRng::new(drbg, 256, EntropyRng::new());
+        let mut buf = [0u64; 128];
+        rng.fill(&mut buf[..]);
+        assert!(buf.iter().any(|&x| x != 0));
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn test_hmac_drbg_state_bytes() {
+        use rand_core::state::StateBytes;
+
+        let mut drbg = HmacDrbg::<Sha512>::from_seed(DrbgSeed([0x55; 48]));
+        drbg.set_reseed_interval(4);
+        drbg.next_u32();
+
+        let bytes = drbg.to_state_bytes();
+        assert_eq!(bytes.len(), 12 + 4 + 2 * 64 + 16);
+        let mut restored = HmacDrbg::<Sha512>::from_state_bytes(&bytes).unwrap();
+        for _ in 0..3 {
+            assert_eq!(drbg.next_u64(), restored.next_u64());
+        }
+        let mut buf = [0u8; 4];
+        assert!(restored.try_fill_bytes(&mut buf).is_err());
+
+        let err = HmacDrbg::<Sha256>::from_state_bytes(&bytes).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Unavailable);
+    }
+}
@@ -0,0 +1,239 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The NIST SP 800-90A deterministic random bit generators.
+//!
+//! This module provides [`HmacDrbg`] and [`HashDrbg`], the HMAC_DRBG and
+//! Hash_DRBG mechanisms of NIST Special Publication 800-90A Rev. 1[^1],
+//! instantiated with either [`Sha256`] or [`Sha512`]. Both have a security
+//! strength of 256 bits.
+//!
+//! The generators follow the instantiate / reseed / generate functions of the
+//! specification: [`new`] takes entropy input, a nonce and an optional
+//! personalization string, [`reseed`] takes new entropy input and optional
+//! additional input, and [`generate`] takes optional additional input. After
+//! the reseed interval (2<sup>48</sup> generate requests unless set lower with
+//! [`set_reseed_interval`]) `generate` returns an error of kind
+//! [`ErrorKind::NotReady`] until the generator is reseeded.
+//!
+//! Through `SeedableRng` the generators are instantiated from a [`DrbgSeed`],
+//! which holds 256 bits of entropy input followed by a 128-bit nonce, without
+//! a personalization string. The `RngCore` implementation performs one
+//! generate request for each call, without additional input; `BlockRngCore`
+//! generates 64 bytes per request. This allows using them with
+//! [`ReseedingRng`], which reseeds by instantiating a new generator from its
+//! reseeder:
+//!
+//! ```
+//! use rand::prelude::*;
+//! use rand::prng::drbg::{HmacDrbg, Sha512};
+//! use rand::rngs::EntropyRng;
+//! use rand::rngs::adapter::ReseedingRng;
+//!
+//! let drbg = HmacDrbg::<Sha512>::from_rng(EntropyRng::new()).unwrap();
+//! let mut rng = ReseedingRng::new(drbg, 1 << 20, EntropyRng::new());
+//! println!("{}", rng.gen::<u64>());
+//! ```
+//!
+//! Note that using these generators alone does not make an application
+//! compliant with SP 800-90A; the entropy sources and their health tests are
+//! specified separately in SP 800-90B and SP 800-90C.
+//!
+//! [^1]: E. Barker and J. Kelsey, [*Recommendation for Random Number Generation
+//!       Using Deterministic Random Bit Generators*](
+//!       https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-90Ar1.pdf),
+//!       NIST Special Publication 800-90A Revision 1, 2015.
+//!
+//! [`HmacDrbg`]: struct.HmacDrbg.html
+//! [`HashDrbg`]: struct.HashDrbg.html
+//! [`Sha256`]: struct.Sha256.html
+//! [`Sha512`]: struct.Sha512.html
+//! [`new`]: struct.HmacDrbg.html#method.new
+//! [`reseed`]: struct.HmacDrbg.html#method.reseed
+//! [`generate`]: struct.HmacDrbg.html#method.generate
+//! [`set_reseed_interval`]: struct.HmacDrbg.html#method.set_reseed_interval
+//! [`ErrorKind::NotReady`]: ../../enum.ErrorKind.html#variant.NotReady
+//! [`DrbgSeed`]: struct.DrbgSeed.html
+//! [`ReseedingRng`]: ../../rngs/adapter/struct.ReseedingRng.html
+
+use core::fmt;
+
+pub use self::hash_drbg::HashDrbg;
+pub use self::hmac_drbg::HmacDrbg;
+pub use self::sha2::{Sha256, Sha512};
+
+/// The maximum number of bytes per generate request (2<sup>19</sup> bits).
+pub const MAX_REQUEST_LEN: usize = 1 << 16;
+
+/// The maximum, and default, reseed interval in generate requests.
+pub const MAX_RESEED_INTERVAL: u64 = 1 << 48;
+
+// Size of the buffers for hash outputs and Hash_DRBG state.
+const MAX_OUTPUT_LEN: usize = 64;
+const MAX_BLOCK_LEN: usize = 128;
+const MAX_SEED_LEN: usize = 111;
+
+const ENTROPY_LEN: usize = 32;
+const NONCE_LEN: usize = 16;
+
+/// A hash function for use with [`HmacDrbg`] and [`HashDrbg`].
+///
+/// This is implemented for [`Sha256`] and [`Sha512`]. Implementations for
+/// other hash functions must not exceed the sizes of SHA-512.
+///
+/// [`HmacDrbg`]: struct.HmacDrbg.html
+/// [`HashDrbg`]: struct.HashDrbg.html
+/// [`Sha256`]: struct.Sha256.html
+/// [`Sha512`]: struct.Sha512.html
+pub trait DrbgHash: Clone + Default {
+    /// The output length in bytes (at most 64).
+    const OUTPUT_LEN: usize;
+    /// The input block length in bytes (at most 128), used by HMAC.
+    const BLOCK_LEN: usize;
+    /// The seed length of Hash_DRBG in bytes (at most 111), from table 2 of
+    /// SP 800-90A.
+    const SEED_LEN: usize;
+    /// The maximum security strength in bytes, also the minimum length of
+    /// the entropy input.
+    const SECURITY_STRENGTH: usize;
+
+    /// Process input data.
+    fn update(&mut self, data: &[u8]);
+
+    /// Write the first `out.len()` bytes of the hash to `out`.
+    fn finish(self, out: &mut [u8]);
+}
+
+// Hash the concatenation of `parts`.
+fn hash_parts<H: DrbgHash>(parts: &[&[u8]], out: &mut [u8]) {
+    let mut h = H::default();
+    for part in parts {
+        h.update(part);
+    }
+    h.finish(out);
+}
+
+/// The seed type of `HmacDrbg` and `HashDrbg`: 256 bits of entropy input
+/// followed by a 128-bit nonce.
+///
+/// This is a wrapper around `[u8; 48]`, because arrays of more than 32
+/// elements do not implement `Default`.
+#[derive(Clone)]
+pub struct DrbgSeed(pub [u8; ENTROPY_LEN + NONCE_LEN]);
+
+impl Default for DrbgSeed {
+    fn default() -> Self {
+        DrbgSeed([0; ENTROPY_LEN + NONCE_LEN])
+    }
+}
+
+impl AsRef<[u8]> for DrbgSeed {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsMut<[u8]> for DrbgSeed {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+// Custom Debug implementation that does not expose the seed
+impl fmt::Debug for DrbgSeed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DrbgSeed {{}}")
+    }
+}
+
+// Implement the traits shared by `HmacDrbg` and `HashDrbg` in terms of their
+// `new` and `generate` methods.
+macro_rules! drbg_impl {
+    ($name:ident) => {
+        impl<H: DrbgHash> RngCore for $name<H> {
+            fn next_u32(&mut self) -> u32 {
+                impls::next_u32_via_fill(self)
+            }
+
+            fn next_u64(&mut self) -> u64 {
+                impls::next_u64_via_fill(self)
+            }
+
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                self.try_fill_bytes(dest).unwrap_or_else(|err|
+                    panic!(concat!(stringify!($name), " failed: {}"), err))
+            }
+
+            fn try_fill_bytes(&mut self, dest: &mut [u8])
+                -> Result<(), Error>
+            {
+                for chunk in dest.chunks_mut(MAX_REQUEST_LEN) {
+                    self.generate(chunk, &[])?;
+                }
+                Ok(())
+            }
+        }
+
+        impl<H: DrbgHash> CryptoRng for $name<H> {}
+
+        impl<H: DrbgHash> BlockRngCore for $name<H> {
+            type Item = u32;
+            type Results = [u32; 16];
+
+            /// Perform a generate request for 64 bytes.
+            ///
+            /// # Panics
+            ///
+            /// If the generator must be reseeded.
+            fn generate(&mut self, results: &mut Self::Results) {
+                let mut buf = [0u8; 64];
+                self.fill_bytes(&mut buf);
+                le::read_u32_into(&buf, results);
+            }
+        }
+
+        impl<H: DrbgHash> SeedableRng for $name<H> {
+            type Seed = DrbgSeed;
+
+            /// Instantiate with the seed as entropy input followed by a
+            /// nonce, and an empty personalization string.
+            fn from_seed(seed: Self::Seed) -> Self {
+                let (entropy, nonce) = seed.0.split_at(ENTROPY_LEN);
+                $name::new(entropy, nonce, &[])
+            }
+        }
+
+        // Custom Debug implementation that does not expose the internal state
+        impl<H: DrbgHash> fmt::Debug for $name<H> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, concat!(stringify!($name), " {{}}"))
+            }
+        }
+
+        #[cfg(feature="zeroize")]
+        impl<H: DrbgHash> Drop for $name<H> {
+            fn drop(&mut self) {
+                self.zeroize();
+            }
+        }
+    }
+}
+
+mod hash_drbg;
+mod hmac_drbg;
+mod sha2;
+
+#[cfg(test)]
+fn unhex(s: &str, out: &mut [u8]) {
+    assert_eq!(s.len(), out.len() * 2);
+    for (i, b) in out.iter_mut().enumerate() {
+        *b = u8::from_str_radix(&s[2*i..2*i+2], 16).unwrap();
+    }
+}
@@ -0,0 +1,370 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The SHA-256 and SHA-512 hash functions (FIPS 180-4).
+
+use core::fmt;
+use super::DrbgHash;
+#[cfg(feature="zeroize")] use rand_core::impls;
+
+const K256: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1,
+    0x923f82a4, 0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3,
+    0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786,
+    0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147,
+    0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13,
+    0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+    0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a,
+    0x5b9cca4f, 0x682e6ff3, 0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208,
+    0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2];
+
+const K512: [u64; 80] = [
+    0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc, 0x3956c25bf348b538, 0x59f111f1b605d019,
+    0x923f82a4af194f9b, 0xab1c5ed5da6d8118, 0xd807aa98a3030242,
+    0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235,
+    0xc19bf174cf692694, 0xe49b69c19ef14ad2, 0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65, 0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f,
+    0xbf597fc7beef0ee4, 0xc6e00bf33da88fc2, 0xd5a79147930aa725,
+    0x06ca6351e003826f, 0x142929670a0e6e70, 0x27b70a8546d22ffc,
+    0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6,
+    0x92722c851482353b, 0xa2bfe8a14cf10364, 0xa81a664bbc423001,
+    0xc24b8b70d0f89791, 0xc76c51a30654be30, 0xd192e819d6ef5218,
+    0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8, 0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3, 0x748f82ee5defb2fc,
+    0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915,
+    0xc67178f2e372532b, 0xca273eceea26619c, 0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178, 0x06f067aa72176fba,
+    0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c, 0x4cc5d4becb3e42b6, 0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec, 0x6c44198c4a475817];
+
+/// The SHA-256 hash function, for use with [`HmacDrbg`] and [`HashDrbg`].
+///
+/// [`HmacDrbg`]: struct.HmacDrbg.html
+/// [`HashDrbg`]: struct.HashDrbg.html
+#[derive(Clone)]
+pub struct Sha256 {
+    state: [u32; 8],
+    buf: [u8; 64],
+    buf_len: usize,
+    len: u64,
+}
+
+impl Sha256 {
+    fn compress(state: &mut [u32; 8], block: &[u8]) {
+        let mut w = [0u32; 64];
+        for (i, chunk) in block.chunks(4).enumerate() {
+            w[i] = (chunk[0] as u32) << 24 | (chunk[1] as u32) << 16
+                 | (chunk[2] as u32) << 8 | chunk[3] as u32;
+        }
+        for i in 16..64 {
+            let s0 = w[i-15].rotate_right(7) ^ w[i-15].rotate_right(18)
+                     ^ (w[i-15] >> 3);
+            let s1 = w[i-2].rotate_right(17) ^ w[i-2].rotate_right(19)
+                     ^ (w[i-2] >> 10);
+            w[i] = w[i-16].wrapping_add(s0).wrapping_add(w[i-7])
+                   .wrapping_add(s1);
+        }
+
+        let mut s = *state;
+        for i in 0..64 {
+            let ch = (s[4] & s[5]) ^ (!s[4] & s[6]);
+            let maj = (s[0] & s[1]) ^ (s[0] & s[2]) ^ (s[1] & s[2]);
+            let sum0 = s[0].rotate_right(2) ^ s[0].rotate_right(13)
+                       ^ s[0].rotate_right(22);
+            let sum1 = s[4].rotate_right(6) ^ s[4].rotate_right(11)
+                       ^ s[4].rotate_right(25);
+            let t1 = s[7].wrapping_add(sum1).wrapping_add(ch)
+                     .wrapping_add(K256[i]).wrapping_add(w[i]);
+            let t2 = sum0.wrapping_add(maj);
+            s = [t1.wrapping_add(t2), s[0], s[1], s[2],
+                 s[3].wrapping_add(t1), s[4], s[5], s[6]];
+        }
+        for (x, y) in state.iter_mut().zip(s.iter()) {
+            *x = x.wrapping_add(*y);
+        }
+    }
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Sha256 {
+            state: [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a,
+                    0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19],
+            buf: [0; 64],
+            buf_len: 0,
+            len: 0,
+        }
+    }
+}
+
+impl DrbgHash for Sha256 {
+    const OUTPUT_LEN: usize = 32;
+    const BLOCK_LEN: usize = 64;
+    const SEED_LEN: usize = 55;
+    const SECURITY_STRENGTH: usize = 32;
+
+    fn update(&mut self, data: &[u8]) {
+        self.len = self.len.wrapping_add(data.len() as u64);
+        let state = &mut self.state;
+        let rest = absorb(&mut self.buf, self.buf_len, data,
+                          |block| Self::compress(state, block));
+        self.buf[..rest.len()].copy_from_slice(rest);
+        self.buf_len = (self.len % 64) as usize;
+    }
+
+    fn finish(mut self, out: &mut [u8]) {
+        let bit_len = self.len.wrapping_mul(8);
+        let mut padding = [0u8; 64];
+        padding[0] = 0x80;
+        let pad_len = if self.buf_len < 56 { 56 } else { 120 } - self.buf_len;
+        self.update(&padding[..pad_len]);
+        let mut len_bytes = [0u8; 8];
+        for (i, b) in len_bytes.iter_mut().enumerate() {
+            *b = (bit_len >> (56 - 8 * i)) as u8;
+        }
+        self.update(&len_bytes);
+        debug_assert_eq!(self.buf_len, 0);
+
+        for (chunk, word) in out.chunks_mut(4).zip(self.state.iter()) {
+            for (i, b) in chunk.iter_mut().enumerate() {
+                *b = (word >> (24 - 8 * i)) as u8;
+            }
+        }
+    }
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Sha256 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Sha256 {{}}")
+    }
+}
+
+// The hash state may contain secret input, like the Fortuna pools.
+#[cfg(feature="zeroize")]
+impl Drop for Sha256 {
+    fn drop(&mut self) {
+        impls::zeroize(&mut self.state);
+        impls::zeroize(&mut self.buf);
+    }
+}
+
+/// The SHA-512 hash function, for use with [`HmacDrbg`] and [`HashDrbg`].
+///
+/// [`HmacDrbg`]: struct.HmacDrbg.html
+/// [`HashDrbg`]: struct.HashDrbg.html
+#[derive(Clone)]
+pub struct Sha512 {
+    state: [u64; 8],
+    buf: [u8; 128],
+    buf_len: usize,
+    len: u64,
+}
+
+impl Sha512 {
+    fn compress(state: &mut [u64; 8], block: &[u8]) {
+        let mut w = [0u64; 80];
+        for (i, chunk) in block.chunks(8).enumerate() {
+            w[i] = chunk.iter().fold(0, |acc, &b| acc << 8 | b as u64);
+        }
+        for i in 16..80 {
+            let s0 = w[i-15].rotate_right(1) ^ w[i-15].rotate_right(8)
+                     ^ (w[i-15] >> 7);
+            let s1 = w[i-2].rotate_right(19) ^ w[i-2].rotate_right(61)
+                     ^ (w[i-2] >> 6);
+            w[i] = w[i-16].wrapping_add(s0).wrapping_add(w[i-7])
+                   .wrapping_add(s1);
+        }
+
+        let mut s = *state;
+        for i in 0..80 {
+            let ch = (s[4] & s[5]) ^ (!s[4] & s[6]);
+            let maj = (s[0] & s[1]) ^ (s[0] & s[2]) ^ (s[1] & s[2]);
+            let sum0 = s[0].rotate_right(28) ^ s[0].rotate_right(34)
+                       ^ s[0].rotate_right(39);
+            let sum1 = s[4].rotate_right(14) ^ s[4].rotate_right(18)
+                       ^ s[4].rotate_right(41);
+            let t1 = s[7].wrapping_add(sum1).wrapping_add(ch)
+                     .wrapping_add(K512[i]).wrapping_add(w[i]);
+            let t2 = sum0.wrapping_add(maj);
+            s = [t1.wrapping_add(t2), s[0], s[1], s[2],
+                 s[3].wrapping_add(t1), s[4], s[5], s[6]];
+        }
+        for (x, y) in state.iter_mut().zip(s.iter()) {
+            *x = x.wrapping_add(*y);
+        }
+    }
+}
+
+impl Default for Sha512 {
+    fn default() -> Self {
+        Sha512 {
+            state: [0x6a09e667f3bcc908, 0xbb67ae8584caa73b,
+                    0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+                    0x510e527fade682d1, 0x9b05688c2b3e6c1f,
+                    0x1f83d9abfb41bd6b, 0x5be0cd19137e2179],
+            buf: [0; 128],
+            buf_len: 0,
+            len: 0,
+        }
+    }
+}
+
+impl DrbgHash for Sha512 {
+    const OUTPUT_LEN: usize = 64;
+    const BLOCK_LEN: usize = 128;
+    const SEED_LEN: usize = 111;
+    const SECURITY_STRENGTH: usize = 32;
+
+    fn update(&mut self, data: &[u8]) {
+        self.len = self.len.wrapping_add(data.len() as u64);
+        let state = &mut self.state;
+        let rest = absorb(&mut self.buf, self.buf_len, data,
+                          |block| Self::compress(state, block));
+        self.buf[..rest.len()].copy_from_slice(rest);
+        self.buf_len = (self.len % 128) as usize;
+    }
+
+    fn finish(mut self, out: &mut [u8]) {
+        // The length is a 128-bit number; inputs are never long enough to
+        // need the upper half.
+        let bit_len = self.len.wrapping_mul(8);
+        let mut padding = [0u8; 128];
+        padding[0] = 0x80;
+        let pad_len = if self.buf_len < 112 { 112 } else { 240 } - self.buf_len;
+        self.update(&padding[..pad_len]);
+        let mut len_bytes = [0u8; 16];
+        for (i, b) in len_bytes[8..].iter_mut().enumerate() {
+            *b = (bit_len >> (56 - 8 * i)) as u8;
+        }
+        self.update(&len_bytes);
+        debug_assert_eq!(self.buf_len, 0);
+
+        for (chunk, word) in out.chunks_mut(8).zip(self.state.iter()) {
+            for (i, b) in chunk.iter_mut().enumerate() {
+                *b = (word >> (56 - 8 * i)) as u8;
+            }
+        }
+    }
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Sha512 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Sha512 {{}}")
+    }
+}
+
+// The hash state may contain secret input, like the Fortuna pools.
+#[cfg(feature="zeroize")]
+impl Drop for Sha512 {
+    fn drop(&mut self) {
+        impls::zeroize(&mut self.state);
+        impls::zeroize(&mut self.buf);
+    }
+}
+
+// Feed `data` to `compress` in whole blocks, starting with the `buf_len`
+// bytes already in `buf`. Returns the remainder of `data` that does not fill
+// a whole block.
+fn absorb<'a, F>(buf: &mut [u8], buf_len: usize, mut data: &'a [u8],
+                 mut compress: F) -> &'a [u8]
+    where F: FnMut(&[u8])
+{
+    let block_len = buf.len();
+    if buf_len > 0 {
+        let n = ::core::cmp::min(block_len - buf_len, data.len());
+        buf[buf_len..buf_len + n].copy_from_slice(&data[..n]);
+        data = &data[n..];
+        if buf_len + n < block_len {
+            // Not a whole block yet; keep the data where it is.
+            return &[];
+        }
+        compress(buf);
+    }
+    while data.len() >= block_len {
+        compress(&data[..block_len]);
+        data = &data[block_len..];
+    }
+    data
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{DrbgHash, unhex};
+    use super::{Sha256, Sha512};
+
+    // Hash 1000 bytes of input, fed in pieces of increasing length.
+    fn hash_pieces<H: DrbgHash>(out: &mut [u8]) {
+        let mut msg = [0u8; 1000];
+        for (i, b) in msg.iter_mut().enumerate() {
+            *b = (i % 251) as u8;
+        }
+        let mut h = H::default();
+        let mut rest = &msg[..];
+        let mut n = 0;
+        while !rest.is_empty() {
+            n = ::core::cmp::min(n + 1, rest.len());
+            h.update(&rest[..n]);
+            rest = &rest[n..];
+        }
+        h.finish(out);
+    }
+
+    #[test]
+    fn test_sha256() {
+        let mut expected = [0u8; 32];
+        let mut out = [0u8; 32];
+
+        unhex("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+              &mut expected);
+        let mut h = Sha256::default();
+        h.update(b"abc");
+        h.finish(&mut out);
+        assert_eq!(out, expected);
+
+        unhex("4e4c294b331f7a2099a379bec34b9f9fc03dc46ab465d998f4d683da53487e6d",
+              &mut expected);
+        hash_pieces::<Sha256>(&mut out);
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn test_sha512() {
+        let mut expected = [0u8; 64];
+        let mut out = [0u8; 64];
+
+        unhex("ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+               2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+              &mut expected);
+        let mut h = Sha512::default();
+        h.update(b"abc");
+        h.finish(&mut out);
+        assert_eq!(&out[..], &expected[..]);
+
+        unhex("5096498d96f50f9a137c4db5b8b0cd38383ad55350fb5a98805fedc31fa1262f\
+               1f0cf4d6f12d7ecd8dedd933a4c9126344fe22e937a8ad35fdeae1e876ae698b",
+              &mut expected);
+        hash_pieces::<Sha512>(&mut out);
+        assert_eq!(&out[..], &expected[..]);
+    }
+}
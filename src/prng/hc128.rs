@@ -11,11 +11,18 @@
 //! The HC-128 random number generator.
 
 use core::fmt;
-use rand_core::{CryptoRng, RngCore, SeedableRng, Error, le};
+use rand_core::{CryptoRng, RngCore, SeedableRng, SplittableRng, Error,
+                ErrorKind, le};
+use prng::drbg::{DrbgHash, Sha256};
 use rand_core::block::{BlockRngCore, BlockRng};
+use rand_core::state::{StateBytes, StateReader, StateWriter};
+#[cfg(feature="zeroize")] use rand_core::impls;
 
 const SEED_WORDS: usize = 8; // 128 bit key followed by 128 bit iv
 
+#[cfg(feature="serde1")]
+array_serde!(table_serde, u32, 1024);
+
 /// A cryptographically secure random number generator that uses the HC-128
 /// algorithm.
 ///
@@ -69,6 +76,7 @@ const SEED_WORDS: usize = 8; // 128 bit key followed by 128 bit iv
 /// [`BlockRng`]: ../../../rand_core/block/struct.BlockRng.html
 /// [`RngCore`]: ../../trait.RngCore.html
 #[derive(Clone, Debug)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize))]
 pub struct Hc128Rng(BlockRng<Hc128Core>);
 
 impl RngCore for Hc128Rng {
@@ -89,6 +97,10 @@ impl RngCore for Hc128Rng {
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         self.0.try_fill_bytes(dest)
     }
+
+    fn fill_u32(&mut self, dest: &mut [u32]) {
+        self.0.fill_u32(dest)
+    }
 }
 
 impl SeedableRng for Hc128Rng {
@@ -105,9 +117,81 @@ impl SeedableRng for Hc128Rng {
 
 impl CryptoRng for Hc128Rng {}
 
+/// The state is the table `T` as 1024 `u32`, the step counter modulo 1024 as
+/// `u32`, the index into the result buffer as `u32` and the result buffer as
+/// 16 `u32`: 4168 bytes.
+impl StateBytes for Hc128Rng {
+    const ALGORITHM_ID: [u8; 8] = *b"HC-128\0\0";
+    const FORMAT_VERSION: u32 = 1;
+
+    fn state_len() -> usize {
+        4 * 1024 + 4 + BlockRng::<Hc128Core>::buffer_state_len()
+    }
+
+    fn write_state(&self, state: &mut [u8]) {
+        let mut w = StateWriter::new(state);
+        w.write_u32s(&self.0.core.t);
+        w.write_u32((self.0.core.counter1024 % 1024) as u32);
+        self.0.write_buffer_state(&mut w);
+    }
+
+    fn read_state(state: &[u8]) -> Result<Self, Error> {
+        let mut r = StateReader::new(state);
+        let mut core = Hc128Core { t: [0; 1024], counter1024: 0 };
+        r.read_u32s(&mut core.t);
+        core.counter1024 = r.read_u32() as usize;
+        if core.counter1024 >= 1024 || core.counter1024 % 16 != 0 {
+            return Err(Error::new(ErrorKind::Unavailable,
+                                  "state bytes: invalid HC-128 counter"));
+        }
+        let mut rng = BlockRng::new(core);
+        rng.read_buffer_state(&mut r)?;
+        Ok(Hc128Rng(rng))
+    }
+}
+
+/// `split` seeds the child with the next 256 bits of output.
+///
+/// `fork` seeds the child with the SHA-256 hash of the internal state of this
+/// generator and `index`. The child depends on the current state of this
+/// generator, which changes as it generates output.
+impl SplittableRng for Hc128Rng {
+    fn split(&mut self) -> Self {
+        let mut seed = <Self as SeedableRng>::Seed::default();
+        self.fill_bytes(&mut seed);
+        Hc128Rng::from_seed(seed)
+    }
+
+    fn fork(&self, index: u64) -> Self {
+        let core = &self.0.core;
+        let mut h = Sha256::default();
+        let mut buf = [0u8; 64];
+        for words in core.t.chunks(16) {
+            for (bytes, word) in buf.chunks_mut(4).zip(words.iter()) {
+                for (i, b) in bytes.iter_mut().enumerate() {
+                    *b = (word >> (8 * i)) as u8;
+                }
+            }
+            h.update(&buf);
+        }
+        let counter = core.counter1024 as u64;
+        for (i, b) in buf[..16].iter_mut().enumerate() {
+            *b = if i < 8 { (counter >> (8 * i)) as u8 }
+                 else { (index >> (8 * (i - 8))) as u8 };
+        }
+        h.update(&buf[..16]);
+
+        let mut seed = <Self as SeedableRng>::Seed::default();
+        h.finish(&mut seed);
+        Hc128Rng::from_seed(seed)
+    }
+}
+
 /// The core of `Hc128Rng`, used with `BlockRng`.
 #[derive(Clone)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize))]
 pub struct Hc128Core {
+    #[cfg_attr(feature="serde1", serde(with="table_serde"))]
     t: [u32; 1024],
     counter1024: usize,
 }
@@ -334,6 +418,13 @@ impl SeedableRng for Hc128Core {
 
 impl CryptoRng for Hc128Core {}
 
+#[cfg(feature="zeroize")]
+impl Drop for Hc128Core {
+    fn drop(&mut self) {
+        impls::zeroize(&mut self.t);
+    }
+}
+
 #[cfg(test)]
 mod test {
     use {RngCore, SeedableRng};
@@ -461,4 +552,42 @@ mod test {
             assert_eq!(rng1.next_u32(), rng2.next_u32());
         }
     }
+
+    #[cfg(feature="serde1")]
+    #[test]
+    fn test_hc128_serde() {
+        use bincode;
+        use std::io::{BufWriter, BufReader};
+
+        let mut rng = Hc128Rng::from_seed([0x55; 32]);
+        rng.next_u32();
+
+        let buf: Vec<u8> = Vec::new();
+        let mut buf = BufWriter::new(buf);
+        bincode::serialize_into(&mut buf, &rng).expect("Could not serialize");
+
+        let buf = buf.into_inner().unwrap();
+        let mut read = BufReader::new(&buf[..]);
+        let mut deserialized: Hc128Rng =
+            bincode::deserialize_from(&mut read).expect("Could not deserialize");
+
+        for _ in 0..2048 {
+            assert_eq!(rng.next_u32(), deserialized.next_u32());
+        }
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn test_hc128_state_bytes() {
+        use rand_core::state::StateBytes;
+
+        let mut rng = Hc128Rng::from_seed([0x55; 32]);
+        rng.next_u32();
+
+        let bytes = rng.to_state_bytes();
+        let mut restored = Hc128Rng::from_state_bytes(&bytes).unwrap();
+        for _ in 0..2048 {
+            assert_eq!(rng.next_u32(), restored.next_u32());
+        }
+    }
 }
@@ -0,0 +1,420 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://www.rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The HC-256 random number generator.
+
+use core::fmt;
+use rand_core::{CryptoRng, RngCore, SeedableRng, Error, ErrorKind, le};
+use rand_core::block::{BlockRngCore, BlockRng};
+use rand_core::state::{StateBytes, StateReader, StateWriter};
+#[cfg(feature="zeroize")] use rand_core::impls;
+
+const SEED_WORDS: usize = 16; // 256 bit key followed by 256 bit iv
+
+#[cfg(feature="serde1")]
+array_serde!(table_serde, u32, 1024);
+
+/// A cryptographically secure random number generator that uses the HC-256
+/// algorithm.
+///
+/// HC-256 is a stream cipher designed by Hongjun Wu[^1], and the 256-bit
+/// companion of [`Hc128Rng`]. HC-128 was selected for the eSTREAM
+/// portfolio[^2]; HC-256 was submitted alongside it and uses the same design
+/// with two tables of 1024 words each, a stronger update function and a
+/// 256-bit key.
+///
+/// Like HC-128, HC-256 works with simple indexing into a large array, which
+/// gives it good performance once initialized. The tables take 8 KiB, twice
+/// the size of those of HC-128, and key expansion is correspondingly more
+/// expensive.
+///
+/// HC-256 has no known weaknesses that are easier to exploit than doing a
+/// brute-force search of 2<sup>256</sup>. Prefer [`Hc128Rng`] or
+/// [`ChaChaRng`] unless a 256-bit key is required.
+///
+/// The average cycle length is expected to be much larger than
+/// 2<sup>256</sup>. We support seeding with a 512-bit array, [`Hc256Seed`],
+/// which matches the 256-bit key concatenated with a 256-bit IV from the
+/// stream cipher.
+///
+/// This implementation uses an output buffer of sixteen `u32` words, and uses
+/// [`BlockRng`] to implement the [`RngCore`] methods.
+///
+/// ## References
+/// [^1]: Hongjun Wu (2004). ["A New Stream Cipher HC-256"](
+///       https://www.iacr.org/archive/fse2004/30170227/30170227.pdf).
+///       *Fast Software Encryption*, LNCS 3017, pp. 226–244, Springer-Verlag.
+///
+/// [^2]: [eSTREAM: the ECRYPT Stream Cipher Project](
+///       http://www.ecrypt.eu.org/stream/)
+///
+/// [`Hc128Rng`]: ../hc128/struct.Hc128Rng.html
+/// [`ChaChaRng`]: ../chacha/struct.ChaChaRng.html
+/// [`Hc256Seed`]: struct.Hc256Seed.html
+/// [`BlockRng`]: ../../../rand_core/block/struct.BlockRng.html
+/// [`RngCore`]: ../../trait.RngCore.html
+#[derive(Clone, Debug)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize))]
+pub struct Hc256Rng(BlockRng<Hc256Core>);
+
+impl RngCore for Hc256Rng {
+    #[inline(always)]
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    #[inline(always)]
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.0.try_fill_bytes(dest)
+    }
+
+    fn fill_u32(&mut self, dest: &mut [u32]) {
+        self.0.fill_u32(dest)
+    }
+}
+
+impl SeedableRng for Hc256Rng {
+    type Seed = <Hc256Core as SeedableRng>::Seed;
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Hc256Rng(BlockRng::<Hc256Core>::from_seed(seed))
+    }
+
+    fn from_rng<R: RngCore>(rng: R) -> Result<Self, Error> {
+        BlockRng::<Hc256Core>::from_rng(rng).map(Hc256Rng)
+    }
+}
+
+impl CryptoRng for Hc256Rng {}
+
+/// The state is the tables `P` and `Q` as 1024 `u32` each, the step counter
+/// as `u32`, the index into the result buffer as `u32` and the result buffer
+/// as 16 `u32`: 8264 bytes.
+impl StateBytes for Hc256Rng {
+    const ALGORITHM_ID: [u8; 8] = *b"HC-256\0\0";
+    const FORMAT_VERSION: u32 = 1;
+
+    fn state_len() -> usize {
+        2 * 4 * 1024 + 4 + BlockRng::<Hc256Core>::buffer_state_len()
+    }
+
+    fn write_state(&self, state: &mut [u8]) {
+        let mut w = StateWriter::new(state);
+        w.write_u32s(&self.0.core.p);
+        w.write_u32s(&self.0.core.q);
+        w.write_u32(self.0.core.counter2048 as u32);
+        self.0.write_buffer_state(&mut w);
+    }
+
+    fn read_state(state: &[u8]) -> Result<Self, Error> {
+        let mut r = StateReader::new(state);
+        let mut core = Hc256Core { p: [0; 1024], q: [0; 1024], counter2048: 0 };
+        r.read_u32s(&mut core.p);
+        r.read_u32s(&mut core.q);
+        core.counter2048 = r.read_u32() as usize;
+        if core.counter2048 >= 2048 || core.counter2048 % 16 != 0 {
+            return Err(Error::new(ErrorKind::Unavailable,
+                                  "state bytes: invalid HC-256 counter"));
+        }
+        let mut rng = BlockRng::new(core);
+        rng.read_buffer_state(&mut r)?;
+        Ok(Hc256Rng(rng))
+    }
+}
+
+/// The seed type of `Hc256Rng` and `Hc256Core`: a 256-bit key followed by a
+/// 256-bit IV.
+///
+/// This is a wrapper around `[u8; 64]`, because arrays of more than 32
+/// elements do not implement `Default`.
+#[derive(Clone)]
+pub struct Hc256Seed(pub [u8; SEED_WORDS*4]);
+
+impl Default for Hc256Seed {
+    fn default() -> Self {
+        Hc256Seed([0; SEED_WORDS*4])
+    }
+}
+
+impl AsRef<[u8]> for Hc256Seed {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsMut<[u8]> for Hc256Seed {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+// Custom Debug implementation that does not expose the seed
+impl fmt::Debug for Hc256Seed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Hc256Seed {{}}")
+    }
+}
+
+/// The core of `Hc256Rng`, used with `BlockRng`.
+#[derive(Clone)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize))]
+pub struct Hc256Core {
+    #[cfg_attr(feature="serde1", serde(with="table_serde"))]
+    p: [u32; 1024],
+    #[cfg_attr(feature="serde1", serde(with="table_serde"))]
+    q: [u32; 1024],
+    counter2048: usize,
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Hc256Core {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Hc256Core {{}}")
+    }
+}
+
+impl BlockRngCore for Hc256Core {
+    type Item = u32;
+    type Results = [u32; 16];
+
+    fn generate(&mut self, results: &mut Self::Results) {
+        // Blocks of sixteen steps never cross from the P into the Q table.
+        let j = self.counter2048 % 1024;
+        if self.counter2048 < 1024 {
+            for (k, r) in results.iter_mut().enumerate() {
+                *r = step(&mut self.p, &self.q, j + k);
+            }
+        } else {
+            for (k, r) in results.iter_mut().enumerate() {
+                *r = step(&mut self.q, &self.p, j + k);
+            }
+        }
+        self.counter2048 = (self.counter2048 + 16) % 2048;
+    }
+}
+
+// One step of HC-256: update `x[j]` and generate 32 bits keystream.
+//
+// With `x = P` and `y = Q` this uses the functions g1 and h1 of the
+// specification, with `x = Q` and `y = P` the functions g2 and h2.
+#[inline(always)]
+fn step(x: &mut [u32; 1024], y: &[u32; 1024], j: usize) -> u32 {
+    let a = x[j.wrapping_sub(3) & 0x3ff];
+    let b = x[(j + 1) & 0x3ff];
+    let g = (a.rotate_right(10) ^ b.rotate_right(23))
+            .wrapping_add(y[((a ^ b) & 0x3ff) as usize]);
+    x[j] = x[j].wrapping_add(x[j.wrapping_sub(10) & 0x3ff]).wrapping_add(g);
+
+    let u = x[j.wrapping_sub(12) & 0x3ff];
+    let h = y[(u & 0xff) as usize]
+            .wrapping_add(y[256 + ((u >> 8) & 0xff) as usize])
+            .wrapping_add(y[512 + ((u >> 16) & 0xff) as usize])
+            .wrapping_add(y[768 + (u >> 24) as usize]);
+    h ^ x[j]
+}
+
+impl Hc256Core {
+    // Initialize an HC-256 random number generator. The seed has to be
+    // 512 bits in length (`[u32; 16]`), matching the 256 bit `key` followed by
+    // 256 bit `iv` when HC-256 where to be used as a stream cipher.
+    fn init(seed: [u32; SEED_WORDS]) -> Self {
+        #[inline]
+        fn f1(x: u32) -> u32 {
+            x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3)
+        }
+
+        #[inline]
+        fn f2(x: u32) -> u32 {
+            x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10)
+        }
+
+        // Expand the key and iv into the intermediate values W[0] ... W[2559]
+        let mut w = [0u32; 2560];
+        w[..SEED_WORDS].copy_from_slice(&seed);
+        for i in 16..2560 {
+            w[i] = f2(w[i-2]).wrapping_add(w[i-7]).wrapping_add(f1(w[i-15]))
+                   .wrapping_add(w[i-16]).wrapping_add(i as u32);
+        }
+
+        // P and Q are W[512] ... W[1535] and W[1536] ... W[2559]
+        let mut core = Self { p: [0; 1024], q: [0; 1024], counter2048: 0 };
+        core.p.copy_from_slice(&w[512..1536]);
+        core.q.copy_from_slice(&w[1536..2560]);
+
+        // run the cipher 4096 steps without generating output
+        let mut discard = [0u32; 16];
+        for _ in 0..256 { core.generate(&mut discard) };
+        core
+    }
+}
+
+impl SeedableRng for Hc256Core {
+    type Seed = Hc256Seed;
+
+    /// Create an HC-256 random number generator with a seed. The seed has to be
+    /// 512 bits in length, matching the 256 bit `key` followed by 256 bit `iv`
+    /// when HC-256 where to be used as a stream cipher.
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut seed_u32 = [0u32; SEED_WORDS];
+        le::read_u32_into(&seed.0, &mut seed_u32);
+        Self::init(seed_u32)
+    }
+}
+
+impl CryptoRng for Hc256Core {}
+
+#[cfg(feature="zeroize")]
+impl Drop for Hc256Core {
+    fn drop(&mut self) {
+        impls::zeroize(&mut self.p);
+        impls::zeroize(&mut self.q);
+    }
+}
+
+impl From<Hc256Core> for Hc256Rng {
+    fn from(core: Hc256Core) -> Self {
+        Hc256Rng(BlockRng::new(core))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {RngCore, SeedableRng};
+    use super::{Hc256Rng, Hc256Seed};
+
+    fn seed(key0: u8, iv0: u8) -> Hc256Seed {
+        let mut seed = Hc256Seed::default();
+        seed.0[0] = key0;
+        seed.0[32] = iv0;
+        seed
+    }
+
+    #[test]
+    // Test vector 1 from the paper "A New Stream Cipher HC-256"
+    fn test_hc256_true_values_a() {
+        let mut rng = Hc256Rng::from_seed(seed(0, 0));
+
+        let mut results = [0u32; 16];
+        for i in results.iter_mut() { *i = rng.next_u32(); }
+        let expected = [0x8589075b, 0x0df3f6d8, 0x2fc0c542, 0x5179b6a6,
+                        0x3465f053, 0xf2891f80, 0x8b24744e, 0x18480b72,
+                        0xec2792cd, 0xbf4dcfeb, 0x7769bf8d, 0xfa14aee4,
+                        0x7b4c50e8, 0xeaf3a9c8, 0xf506016c, 0x81697e32];
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    // Test vector 2 from the paper "A New Stream Cipher HC-256"
+    fn test_hc256_true_values_b() {
+        let mut rng = Hc256Rng::from_seed(seed(0, 1));
+
+        let mut results = [0u32; 16];
+        for i in results.iter_mut() { *i = rng.next_u32(); }
+        let expected = [0xbfa2e2af, 0xe9ce174f, 0x8b05c2fe, 0xb18bb1d1,
+                        0xee42c05f, 0x01312b71, 0xc61f50dd, 0x502a080b,
+                        0xedfec706, 0x633d9241, 0xa6dac448, 0xaf8561ff,
+                        0x5e04135a, 0x9448c434, 0x2de7e9f3, 0x37520bdf];
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    // Test vector 3 from the paper "A New Stream Cipher HC-256"
+    fn test_hc256_true_values_c() {
+        let mut rng = Hc256Rng::from_seed(seed(0x55, 0));
+
+        let mut results = [0u32; 16];
+        for i in results.iter_mut() { *i = rng.next_u32(); }
+        let expected = [0xfe4a401c, 0xed5fe24f, 0xd19a8f95, 0x6fc036ae,
+                        0x3c5aa688, 0x23e2abc0, 0x2f90b3ae, 0xa8d30e42,
+                        0x59f03a6c, 0x6e39eb44, 0x8f7579fb, 0x70137a5e,
+                        0x6d10b7d8, 0xadd0f7cd, 0x723423da, 0xf575dde6];
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_hc256_true_values_u64() {
+        let mut rng = Hc256Rng::from_seed(seed(0x55, 0));
+
+        let mut results = [0u64; 8];
+        for i in results.iter_mut() { *i = rng.next_u64(); }
+        let expected = [0xed5fe24ffe4a401c, 0x6fc036aed19a8f95,
+                        0x23e2abc03c5aa688, 0xa8d30e422f90b3ae,
+                        0x6e39eb4459f03a6c, 0x70137a5e8f7579fb,
+                        0xadd0f7cd6d10b7d8, 0xf575dde6723423da];
+        assert_eq!(results, expected);
+
+        // The RNG operates in a P block of 1024 results and next a Q block.
+        // After skipping 2*1012 u32 results we end up at the end of the Q
+        // block, and cross into the P block of the second round.
+        for _ in 0..1012 { rng.next_u64(); }
+
+        for i in results.iter_mut() { *i = rng.next_u64(); }
+        let expected = [0xf125e035f728828a, 0x1d8a61a973adbfe6,
+                        0xd0e19ea3ba900d74, 0x1bf56a7307315825,
+                        0x1c1435ea2d47324b, 0xa12e64558c795d32,
+                        0x9a5c3bdbeb5a5122, 0xb4d589e6a249bfad];
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn test_hc256_clone() {
+        let mut rng1 = Hc256Rng::from_seed(seed(0x55, 0));
+        let mut rng2 = rng1.clone();
+        for _ in 0..16 {
+            assert_eq!(rng1.next_u32(), rng2.next_u32());
+        }
+    }
+
+    #[cfg(feature="serde1")]
+    #[test]
+    fn test_hc256_serde() {
+        use bincode;
+        use std::io::{BufWriter, BufReader};
+
+        let mut rng = Hc256Rng::from_seed(seed(0x55, 1));
+        rng.next_u32();
+
+        let buf: Vec<u8> = Vec::new();
+        let mut buf = BufWriter::new(buf);
+        bincode::serialize_into(&mut buf, &rng).expect("Could not serialize");
+
+        let buf = buf.into_inner().unwrap();
+        let mut read = BufReader::new(&buf[..]);
+        let mut deserialized: Hc256Rng =
+            bincode::deserialize_from(&mut read).expect("Could not deserialize");
+
+        for _ in 0..2048 {
+            assert_eq!(rng.next_u32(), deserialized.next_u32());
+        }
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn test_hc256_state_bytes() {
+        use rand_core::state::StateBytes;
+
+        let mut rng = Hc256Rng::from_seed(seed(0x55, 1));
+        rng.next_u32();
+
+        let bytes = rng.to_state_bytes();
+        let mut restored = Hc256Rng::from_state_bytes(&bytes).unwrap();
+        for _ in 0..2048 {
+            assert_eq!(rng.next_u32(), restored.next_u32());
+        }
+    }
+}
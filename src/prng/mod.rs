@@ -43,11 +43,37 @@
 //! where there is a risk of players predicting the next output value from
 //! previous values, in which case a CSPRNG should be used).
 //!
-//! Currently Rand provides only one PRNG, and not a very good one at that:
+//! The sub-crates `rand_mt`, `rand_pcg`, `rand_xorshift` and `rand_xoshiro`
+//! provide the following PRNGs:
 //!
 //! | name | full name | performance | memory | quality | period | features |
 //! |------|-----------|-------------|--------|---------|--------|----------|
+//! | [`Mt19937`] | Mersenne Twister 32-bit | ★★☆☆☆ | 2504 bytes | ★★☆☆☆ | `u32` * 2<sup>19937</sup> - 1 | — |
+//! | [`Mt19937_64`] | Mersenne Twister 64-bit | ★★★☆☆ | 2504 bytes | ★★☆☆☆ | `u64` * 2<sup>19937</sup> - 1 | — |
+//! | [`Pcg32`] | PCG XSH RR 64/32 (LCG) | ★★★☆☆ | 16 bytes | ★★★☆☆ | `u32` * 2<sup>64</sup> | 2<sup>63</sup> streams |
+//! | [`Pcg64`] | PCG XSL RR 128/64 (LCG) | ★★★☆☆ | 32 bytes | ★★★☆☆ | `u64` * 2<sup>128</sup> | 2<sup>127</sup> streams |
+//! | [`Pcg64Mcg`] | PCG XSL RR 128/64 (MCG) | ★★★★☆ | 16 bytes | ★★★☆☆ | `u64` * 2<sup>126</sup> | — |
 //! | [`XorShiftRng`] | Xorshift 32/128 | ★★★☆☆ | 16 bytes | ★☆☆☆☆ | `u32` * 2<sup>128</sup> - 1 | — |
+//! | [`Xoshiro256StarStar`] | Xoshiro256** | ★★★★☆ | 32 bytes | ★★★☆☆ | `u64` * 2<sup>256</sup> - 1 | jump-ahead |
+//! | [`Xoshiro256Plus`] | Xoshiro256+ | ★★★★☆ | 32 bytes | ★★☆☆☆ | `u64` * 2<sup>256</sup> - 1 | jump-ahead |
+//! | [`Xoroshiro128StarStar`] | Xoroshiro128** | ★★★★☆ | 16 bytes | ★★★☆☆ | `u64` * 2<sup>128</sup> - 1 | jump-ahead |
+//! | [`Xoshiro128StarStar`] | Xoshiro128** | ★★★☆☆ | 16 bytes | ★★★☆☆ | `u32` * 2<sup>128</sup> - 1 | jump-ahead |
+//! | [`Xoshiro128PlusPlus`] | Xoshiro128++ | ★★★☆☆ | 16 bytes | ★★★☆☆ | `u32` * 2<sup>128</sup> - 1 | jump-ahead |
+//! | [`Xoroshiro64Star`] | Xoroshiro64* | ★★★☆☆ | 8 bytes | ★★☆☆☆ | `u32` * 2<sup>64</sup> - 1 | jump-ahead |
+//!
+//! The 128-bit PCG generators, `Pcg64` and `Pcg64Mcg`, require Rust 1.26 or
+//! later. The Mersenne Twister generators are mainly useful to reproduce
+//! results of other software, such as C++'s `std::mt19937` or NumPy.
+//!
+//! This crate provides two *counter-based* PRNGs, where each output block is
+//! a pure function of a key and a counter. This makes them well suited to
+//! massively parallel computations, where each work item can compute its
+//! random numbers directly from its own counter value:
+//!
+//! | name | full name | performance | memory | quality | period | features |
+//! |------|-----------|-------------|--------|---------|--------|----------|
+//! | [`Philox4x32Rng`] | Philox4x32-10 | ★★☆☆☆ | 48 bytes | ★★★★☆ | `u32` * 2<sup>130</sup> | 2<sup>64</sup> keys, random access |
+//! | [`Threefry4x64Rng`] | Threefry4x64-20 | ★★☆☆☆ | 112 bytes | ★★★★☆ | `u64` * 2<sup>258</sup> | 2<sup>256</sup> keys, random access |
 //!
 // Quality stars [not rendered in documentation]:
 // 5. reserved for crypto-level (e.g. ChaCha8, ISAAC)
@@ -83,13 +109,18 @@
 //! ciphers are basically a CSPRNG and a combining operation, usually XOR. This
 //! means that we can easily use any stream cipher as a CSPRNG.
 //!
-//! This crate currently provides two CSPRNGs. The sub-crate `rand_isaac`
+//! This crate currently provides several CSPRNGs. The sub-crate `rand_isaac`
 //! provides two CSPRNG-like PRNGs:
 //!
 //! | name | full name |  performance | initialization | memory | predictability | forward secrecy |
 //! |------|-----------|--------------|--------------|----------|----------------|-------------------------|
 //! | [`ChaChaRng`] | ChaCha20 | ★☆☆☆☆ | fast | 136 bytes | secure | no |
+//! | [`ChaCha12Rng`] | ChaCha12 | ★★☆☆☆ | fast | 136 bytes | secure | no |
+//! | [`ChaCha8Rng`] | ChaCha8 | ★★☆☆☆ | fast | 136 bytes | small security margin | no |
 //! | [`Hc128Rng`] | HC-128 | ★★☆☆☆ | slow | 4176 bytes | secure | no |
+//! | [`Hc256Rng`] | HC-256 | ★★☆☆☆ | slow | 8272 bytes | secure | no |
+//! | [`HmacDrbg`] | HMAC_DRBG | ★☆☆☆☆ | fast | 144 bytes | secure | yes |
+//! | [`HashDrbg`] | Hash_DRBG | ★☆☆☆☆ | fast | 240 bytes | secure | yes |
 //! | [`IsaacRng`] | ISAAC | ★★☆☆☆ | slow | 2072 bytes | unknown | unknown |
 //! | [`Isaac64Rng`] | ISAAC-64 | ★★☆☆☆ | slow | 4136 bytes| unknown | unknown |
 //!
@@ -285,6 +316,11 @@
 //! - The ability to jump or seek around in the random number stream;
 //!   with large periood this can be used as an alternative to streams.
 //!
+//! All generators in this module, [`IsaacRng`], [`Isaac64Rng`] and
+//! [`XorShiftRng`] implement [`StateBytes`]: their state can be stored in a
+//! versioned, little-endian binary format, which is independent of the
+//! platform and can be read by other implementations.
+//!
 //!
 //! # Further reading
 //!
@@ -299,12 +335,31 @@
 //! [`rngs` module]: ../rngs/index.html
 //! [basic PRNGs]: #basic-pseudo-random-number-generators-prngs
 //! [CSPRNGs]: #cryptographically-secure-pseudo-random-number-generators-csprngs
+//! [`Mt19937`]: ../../rand_mt/struct.Mt19937.html
+//! [`Mt19937_64`]: ../../rand_mt/struct.Mt19937_64.html
+//! [`Pcg32`]: ../../rand_pcg/type.Pcg32.html
+//! [`Pcg64`]: ../../rand_pcg/type.Pcg64.html
+//! [`Pcg64Mcg`]: ../../rand_pcg/type.Pcg64Mcg.html
 //! [`XorShiftRng`]: ../../rand_xorshift/struct.XorShiftRng.html
+//! [`Xoshiro256StarStar`]: ../../rand_xoshiro/struct.Xoshiro256StarStar.html
+//! [`Xoshiro256Plus`]: ../../rand_xoshiro/struct.Xoshiro256Plus.html
+//! [`Xoroshiro128StarStar`]: ../../rand_xoshiro/struct.Xoroshiro128StarStar.html
+//! [`Xoshiro128StarStar`]: ../../rand_xoshiro/struct.Xoshiro128StarStar.html
+//! [`Xoshiro128PlusPlus`]: ../../rand_xoshiro/struct.Xoshiro128PlusPlus.html
+//! [`Xoroshiro64Star`]: ../../rand_xoshiro/struct.Xoroshiro64Star.html
+//! [`Philox4x32Rng`]: philox/struct.Philox4x32Rng.html
+//! [`Threefry4x64Rng`]: threefry/struct.Threefry4x64Rng.html
 //! [`ChaChaRng`]: chacha/struct.ChaChaRng.html
+//! [`ChaCha12Rng`]: chacha/struct.ChaCha12Rng.html
+//! [`ChaCha8Rng`]: chacha/struct.ChaCha8Rng.html
 //! [`Hc128Rng`]: hc128/struct.Hc128Rng.html
+//! [`Hc256Rng`]: hc256/struct.Hc256Rng.html
+//! [`HmacDrbg`]: drbg/struct.HmacDrbg.html
+//! [`HashDrbg`]: drbg/struct.HashDrbg.html
 //! [`IsaacRng`]: ../../rand_isaac/isaac/struct.IsaacRng.html
 //! [`Isaac64Rng`]: ../../rand_isaac/isaac64/struct.Isaac64Rng.html
 //! [`ThreadRng`]: ../rngs/struct.ThreadRng.html
+//! [`StateBytes`]: ../../rand_core/state/trait.StateBytes.html
 //! [`FromEntropy`]: ../trait.FromEntropy.html
 //! [`EntropyRng`]: ../rngs/struct.EntropyRng.html
 //! [TestU01]: http://simul.iro.umontreal.ca/testu01/tu01.html
@@ -316,11 +371,23 @@
 //! [next-bit test]: https://en.wikipedia.org/wiki/Next-bit_test
 
 
+#[cfg(feature="serde1")]
+#[macro_use]
+mod array_serde;
+
 pub mod chacha;
+pub mod drbg;
 pub mod hc128;
+pub mod hc256;
+pub mod philox;
+pub mod threefry;
 
-pub use self::chacha::ChaChaRng;
+pub use self::chacha::{ChaChaRng, ChaCha12Rng, ChaCha8Rng};
+pub use self::drbg::{HmacDrbg, HashDrbg};
 pub use self::hc128::Hc128Rng;
+pub use self::hc256::Hc256Rng;
+pub use self::philox::Philox4x32Rng;
+pub use self::threefry::Threefry4x64Rng;
 
 // Deprecations (to be removed in 0.7)
 #[doc(hidden)] #[allow(deprecated)]
@@ -0,0 +1,294 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The Philox4x32-10 counter-based random number generator.
+
+use core::fmt;
+use rand_core::{RngCore, SeedableRng, Error, le};
+use rand_core::block::{BlockRngCore, BlockRng};
+use rand_core::state::{StateBytes, StateReader, StateWriter};
+
+const ROUNDS: usize = 10;
+const PHILOX_M0: u32 = 0xD2511F53;
+const PHILOX_M1: u32 = 0xCD9E8D57;
+const PHILOX_W0: u32 = 0x9E3779B9; // golden ratio
+const PHILOX_W1: u32 = 0xBB67AE85; // sqrt(3) - 1
+
+/// A counter-based random number generator using the Philox4x32-10
+/// algorithm.
+///
+/// Philox is one of the counter-based generators designed by Salmon et
+/// al.[^1] Unlike conventional generators, each output block is a pure
+/// function of a key and a counter: there is no state other than the counter,
+/// and [`generate_at`] can compute any block directly. This makes it easy to
+/// hand out random numbers to many parallel work items, each using a unique
+/// counter value (or key), without any coordination between them.
+///
+/// Philox4x32-10 applies 10 rounds of a multiply-based bijection to a
+/// 128-bit counter, keyed by a 64-bit key, producing four `u32` words per
+/// counter value. It passes TestU01's BigCrush, but is not designed to be
+/// cryptographically secure.
+///
+/// The key is set by `SeedableRng` from an 8-byte seed, and the counter
+/// starts at zero. The counter is treated as a 128-bit little-endian number
+/// (`counter[0]` is the least significant word) and incremented after each
+/// block; [`new`] and [`set_counter`] allow starting at an arbitrary block.
+///
+/// This implementation uses [`BlockRng`] to implement the [`RngCore`]
+/// methods, with an output buffer of a single block.
+///
+/// [^1]: J. K. Salmon, M. A. Moraes, R. O. Dror, and D. E. Shaw, [*Parallel
+///       Random Numbers: As Easy as 1, 2, 3*](
+///       http://www.thesalmons.org/john/random123/papers/random123sc11.pdf),
+///       Proceedings of SC11, 2011.
+///
+/// [`generate_at`]: #method.generate_at
+/// [`new`]: #method.new
+/// [`set_counter`]: #method.set_counter
+/// [`BlockRng`]: ../../../rand_core/block/struct.BlockRng.html
+/// [`RngCore`]: ../../trait.RngCore.html
+#[derive(Clone, Debug)]
+pub struct Philox4x32Rng(BlockRng<Philox4x32Core>);
+
+impl RngCore for Philox4x32Rng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.0.try_fill_bytes(dest)
+    }
+
+    #[inline]
+    fn fill_u32(&mut self, dest: &mut [u32]) {
+        self.0.fill_u32(dest)
+    }
+}
+
+impl SeedableRng for Philox4x32Rng {
+    type Seed = <Philox4x32Core as SeedableRng>::Seed;
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Philox4x32Rng(BlockRng::<Philox4x32Core>::from_seed(seed))
+    }
+
+    fn from_rng<R: RngCore>(rng: R) -> Result<Self, Error> {
+        BlockRng::<Philox4x32Core>::from_rng(rng).map(Philox4x32Rng)
+    }
+}
+
+/// The state is the key as two `u32`, the counter of the next block as four
+/// `u32`, the index into the result buffer as `u32` and the result buffer as
+/// four `u32`: 44 bytes.
+impl StateBytes for Philox4x32Rng {
+    const ALGORITHM_ID: [u8; 8] = *b"Philox\0\0";
+    const FORMAT_VERSION: u32 = 1;
+
+    fn state_len() -> usize {
+        4 * 6 + BlockRng::<Philox4x32Core>::buffer_state_len()
+    }
+
+    fn write_state(&self, state: &mut [u8]) {
+        let mut w = StateWriter::new(state);
+        w.write_u32s(&self.0.core.key);
+        w.write_u32s(&self.0.core.counter);
+        self.0.write_buffer_state(&mut w);
+    }
+
+    fn read_state(state: &[u8]) -> Result<Self, Error> {
+        let mut r = StateReader::new(state);
+        let mut core = Philox4x32Core { key: [0; 2], counter: [0; 4] };
+        r.read_u32s(&mut core.key);
+        r.read_u32s(&mut core.counter);
+        let mut rng = BlockRng::new(core);
+        rng.read_buffer_state(&mut r)?;
+        Ok(Philox4x32Rng(rng))
+    }
+}
+
+impl Philox4x32Rng {
+    /// Create a generator with the given key, whose first output is the
+    /// block for `counter`.
+    pub fn new(key: [u32; 2], counter: [u32; 4]) -> Self {
+        Philox4x32Rng(BlockRng::new(Philox4x32Core { key, counter }))
+    }
+
+    /// Set the counter of the next block, discarding any remaining output of
+    /// the current block.
+    pub fn set_counter(&mut self, counter: [u32; 4]) {
+        self.0.core.counter = counter;
+        self.0.reset();
+    }
+
+    /// Compute the output block for `key` and `counter`.
+    ///
+    /// This is a pure function: the result is the same as the block a
+    /// generator constructed with `new(key, counter)` returns first.
+    pub fn generate_at(key: [u32; 2], counter: [u32; 4]) -> [u32; 4] {
+        Philox4x32Core::generate_at(key, counter)
+    }
+}
+
+/// The core of `Philox4x32Rng`, used with `BlockRng`.
+#[derive(Clone)]
+pub struct Philox4x32Core {
+    key: [u32; 2],
+    counter: [u32; 4],
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Philox4x32Core {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Philox4x32Core {{}}")
+    }
+}
+
+#[inline(always)]
+fn mulhilo(a: u32, b: u32) -> (u32, u32) {
+    let product = u64::from(a) * u64::from(b);
+    ((product >> 32) as u32, product as u32)
+}
+
+impl Philox4x32Core {
+    /// Compute the output block for `key` and `counter`.
+    pub fn generate_at(key: [u32; 2], counter: [u32; 4]) -> [u32; 4] {
+        let mut key = key;
+        let mut x = counter;
+        for round in 0..ROUNDS {
+            if round > 0 {
+                key[0] = key[0].wrapping_add(PHILOX_W0);
+                key[1] = key[1].wrapping_add(PHILOX_W1);
+            }
+            let (hi0, lo0) = mulhilo(PHILOX_M0, x[0]);
+            let (hi1, lo1) = mulhilo(PHILOX_M1, x[2]);
+            x = [hi1 ^ x[1] ^ key[0], lo1, hi0 ^ x[3] ^ key[1], lo0];
+        }
+        x
+    }
+}
+
+impl BlockRngCore for Philox4x32Core {
+    type Item = u32;
+    type Results = [u32; 4];
+
+    fn generate(&mut self, results: &mut Self::Results) {
+        *results = Self::generate_at(self.key, self.counter);
+
+        // update 128-bit counter
+        for word in self.counter.iter_mut() {
+            *word = word.wrapping_add(1);
+            if *word != 0 { break; }
+        }
+    }
+}
+
+impl SeedableRng for Philox4x32Core {
+    type Seed = [u8; 8];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut key = [0u32; 2];
+        le::read_u32_into(&seed, &mut key);
+        Self { key, counter: [0; 4] }
+    }
+}
+
+impl From<Philox4x32Core> for Philox4x32Rng {
+    fn from(core: Philox4x32Core) -> Self {
+        Philox4x32Rng(BlockRng::new(core))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {RngCore, SeedableRng};
+    use super::Philox4x32Rng;
+
+    #[test]
+    fn test_philox_true_values() {
+        // Known-answer test vectors from Random123 (`kat_vectors`).
+        assert_eq!(Philox4x32Rng::generate_at([0, 0], [0, 0, 0, 0]),
+                   [0x6627e8d5, 0xe169c58d, 0xbc57ac4c, 0x9b00dbd8]);
+        assert_eq!(Philox4x32Rng::generate_at([!0, !0], [!0, !0, !0, !0]),
+                   [0x408f276d, 0x41c83b0e, 0xa20bc7c6, 0x6d5451fd]);
+        assert_eq!(Philox4x32Rng::generate_at(
+                       [0xa4093822, 0x299f31d0],
+                       [0x243f6a88, 0x85a308d3, 0x13198a2e, 0x03707344]),
+                   [0xd16cfe09, 0x94fdcceb, 0x5001e420, 0x24126ea1]);
+    }
+
+    #[test]
+    fn test_philox_rng() {
+        let mut rng = Philox4x32Rng::from_seed([0; 8]);
+        let mut results = [0u32; 4];
+        for i in results.iter_mut() { *i = rng.next_u32(); }
+        assert_eq!(results, [0x6627e8d5, 0xe169c58d, 0xbc57ac4c, 0x9b00dbd8]);
+
+        // The second block uses the next counter value.
+        for i in results.iter_mut() { *i = rng.next_u32(); }
+        assert_eq!(results, Philox4x32Rng::generate_at([0, 0], [1, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_philox_counter() {
+        let key = [0xa4093822, 0x299f31d0];
+
+        // The counter carries into the higher words.
+        let mut rng = Philox4x32Rng::new(key, [!0, !0, 0, 0]);
+        rng.next_u64();
+        rng.next_u64();
+        let mut results = [0u32; 4];
+        for i in results.iter_mut() { *i = rng.next_u32(); }
+        assert_eq!(results, Philox4x32Rng::generate_at(key, [0, 0, 1, 0]));
+
+        // `set_counter` discards the rest of the current block.
+        let mut rng = Philox4x32Rng::new(key, [0; 4]);
+        rng.next_u32();
+        rng.set_counter([0x243f6a88, 0x85a308d3, 0x13198a2e, 0x03707344]);
+        for i in results.iter_mut() { *i = rng.next_u32(); }
+        assert_eq!(results, [0xd16cfe09, 0x94fdcceb, 0x5001e420, 0x24126ea1]);
+    }
+
+    #[test]
+    fn test_philox_clone() {
+        let mut rng = Philox4x32Rng::from_seed([1, 2, 3, 4, 5, 6, 7, 8]);
+        rng.next_u32();
+        let mut clone = rng.clone();
+        for _ in 0..16 {
+            assert_eq!(rng.next_u64(), clone.next_u64());
+        }
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn test_philox_state_bytes() {
+        use rand_core::state::StateBytes;
+
+        let mut rng = Philox4x32Rng::from_seed([0x55; 8]);
+        rng.next_u32();
+
+        let bytes = rng.to_state_bytes();
+        let mut restored = Philox4x32Rng::from_state_bytes(&bytes).unwrap();
+        for _ in 0..100 {
+            assert_eq!(rng.next_u32(), restored.next_u32());
+        }
+    }
+}
@@ -0,0 +1,312 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The Threefry4x64-20 counter-based random number generator.
+
+use core::fmt;
+use rand_core::{RngCore, SeedableRng, Error, le};
+use rand_core::block::{BlockRngCore, BlockRng64};
+use rand_core::state::{StateBytes, StateReader, StateWriter};
+
+const ROUNDS: usize = 20;
+// Key schedule parity constant from Threefish.
+const SKEIN_KS_PARITY: u64 = 0x1BD11BDAA9FC1A22;
+// Rotation constants for Threefry4x64, applied in a cycle of eight rounds.
+const ROTATIONS: [(u32, u32); 8] = [(14, 16), (52, 57), (23, 40), (5, 37),
+                                    (25, 33), (46, 12), (58, 22), (32, 32)];
+
+/// A counter-based random number generator using the Threefry4x64-20
+/// algorithm.
+///
+/// Threefry is one of the counter-based generators designed by Salmon et
+/// al.[^1], derived from the Threefish block cipher. Like [`Philox4x32Rng`],
+/// each output block is a pure function of a key and a counter, which can be
+/// computed directly with [`generate_at`].
+///
+/// Threefry4x64-20 applies 20 rounds of add-rotate-xor operations to a
+/// 256-bit counter, keyed by a 256-bit key, producing four `u64` words per
+/// counter value. It passes TestU01's BigCrush, but the reduced number of
+/// rounds compared to Threefish means it is not designed to be
+/// cryptographically secure.
+///
+/// The key is set by `SeedableRng` from a 32-byte seed, and the counter
+/// starts at zero. The counter is treated as a 256-bit little-endian number
+/// (`counter[0]` is the least significant word) and incremented after each
+/// block; [`new`] and [`set_counter`] allow starting at an arbitrary block.
+///
+/// This implementation uses [`BlockRng64`] to implement the [`RngCore`]
+/// methods, with an output buffer of a single block.
+///
+/// [^1]: J. K. Salmon, M. A. Moraes, R. O. Dror, and D. E. Shaw, [*Parallel
+///       Random Numbers: As Easy as 1, 2, 3*](
+///       http://www.thesalmons.org/john/random123/papers/random123sc11.pdf),
+///       Proceedings of SC11, 2011.
+///
+/// [`Philox4x32Rng`]: ../philox/struct.Philox4x32Rng.html
+/// [`generate_at`]: #method.generate_at
+/// [`new`]: #method.new
+/// [`set_counter`]: #method.set_counter
+/// [`BlockRng64`]: ../../../rand_core/block/struct.BlockRng64.html
+/// [`RngCore`]: ../../trait.RngCore.html
+#[derive(Clone, Debug)]
+pub struct Threefry4x64Rng(BlockRng64<Threefry4x64Core>);
+
+impl RngCore for Threefry4x64Rng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.0.try_fill_bytes(dest)
+    }
+
+    #[inline]
+    fn fill_u64(&mut self, dest: &mut [u64]) {
+        self.0.fill_u64(dest)
+    }
+}
+
+impl SeedableRng for Threefry4x64Rng {
+    type Seed = <Threefry4x64Core as SeedableRng>::Seed;
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        Threefry4x64Rng(BlockRng64::<Threefry4x64Core>::from_seed(seed))
+    }
+
+    fn from_rng<R: RngCore>(rng: R) -> Result<Self, Error> {
+        BlockRng64::<Threefry4x64Core>::from_rng(rng).map(Threefry4x64Rng)
+    }
+}
+
+/// The state is the key as four `u64`, the counter of the next block as four
+/// `u64`, the index into the result buffer and the `half_used` flag as `u32`,
+/// and the result buffer as four `u64`: 104 bytes.
+impl StateBytes for Threefry4x64Rng {
+    const ALGORITHM_ID: [u8; 8] = *b"Threefry";
+    const FORMAT_VERSION: u32 = 1;
+
+    fn state_len() -> usize {
+        8 * 8 + BlockRng64::<Threefry4x64Core>::buffer_state_len()
+    }
+
+    fn write_state(&self, state: &mut [u8]) {
+        let mut w = StateWriter::new(state);
+        w.write_u64s(&self.0.core.key);
+        w.write_u64s(&self.0.core.counter);
+        self.0.write_buffer_state(&mut w);
+    }
+
+    fn read_state(state: &[u8]) -> Result<Self, Error> {
+        let mut r = StateReader::new(state);
+        let mut core = Threefry4x64Core { key: [0; 4], counter: [0; 4] };
+        r.read_u64s(&mut core.key);
+        r.read_u64s(&mut core.counter);
+        let mut rng = BlockRng64::new(core);
+        rng.read_buffer_state(&mut r)?;
+        Ok(Threefry4x64Rng(rng))
+    }
+}
+
+impl Threefry4x64Rng {
+    /// Create a generator with the given key, whose first output is the
+    /// block for `counter`.
+    pub fn new(key: [u64; 4], counter: [u64; 4]) -> Self {
+        Threefry4x64Rng(BlockRng64::new(Threefry4x64Core { key, counter }))
+    }
+
+    /// Set the counter of the next block, discarding any remaining output of
+    /// the current block.
+    pub fn set_counter(&mut self, counter: [u64; 4]) {
+        self.0.core.counter = counter;
+        self.0.reset();
+    }
+
+    /// Compute the output block for `key` and `counter`.
+    ///
+    /// This is a pure function: the result is the same as the block a
+    /// generator constructed with `new(key, counter)` returns first.
+    pub fn generate_at(key: [u64; 4], counter: [u64; 4]) -> [u64; 4] {
+        Threefry4x64Core::generate_at(key, counter)
+    }
+}
+
+/// The core of `Threefry4x64Rng`, used with `BlockRng64`.
+#[derive(Clone)]
+pub struct Threefry4x64Core {
+    key: [u64; 4],
+    counter: [u64; 4],
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Threefry4x64Core {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Threefry4x64Core {{}}")
+    }
+}
+
+impl Threefry4x64Core {
+    /// Compute the output block for `key` and `counter`.
+    pub fn generate_at(key: [u64; 4], counter: [u64; 4]) -> [u64; 4] {
+        let ks = [key[0], key[1], key[2], key[3],
+                  SKEIN_KS_PARITY ^ key[0] ^ key[1] ^ key[2] ^ key[3]];
+
+        let mut x = [0u64; 4];
+        for i in 0..4 {
+            x[i] = counter[i].wrapping_add(ks[i]);
+        }
+
+        for round in 0..ROUNDS {
+            let (r0, r1) = ROTATIONS[round % 8];
+            if round % 2 == 0 {
+                x[0] = x[0].wrapping_add(x[1]); x[1] = x[1].rotate_left(r0) ^ x[0];
+                x[2] = x[2].wrapping_add(x[3]); x[3] = x[3].rotate_left(r1) ^ x[2];
+            } else {
+                x[0] = x[0].wrapping_add(x[3]); x[3] = x[3].rotate_left(r0) ^ x[0];
+                x[2] = x[2].wrapping_add(x[1]); x[1] = x[1].rotate_left(r1) ^ x[2];
+            }
+
+            // Key injection after every four rounds
+            if round % 4 == 3 {
+                let s = round / 4 + 1;
+                for i in 0..4 {
+                    x[i] = x[i].wrapping_add(ks[(s + i) % 5]);
+                }
+                x[3] = x[3].wrapping_add(s as u64);
+            }
+        }
+        x
+    }
+}
+
+impl BlockRngCore for Threefry4x64Core {
+    type Item = u64;
+    type Results = [u64; 4];
+
+    fn generate(&mut self, results: &mut Self::Results) {
+        *results = Self::generate_at(self.key, self.counter);
+
+        // update 256-bit counter
+        for word in self.counter.iter_mut() {
+            *word = word.wrapping_add(1);
+            if *word != 0 { break; }
+        }
+    }
+}
+
+impl SeedableRng for Threefry4x64Core {
+    type Seed = [u8; 32];
+
+    fn from_seed(seed: Self::Seed) -> Self {
+        let mut key = [0u64; 4];
+        le::read_u64_into(&seed, &mut key);
+        Self { key, counter: [0; 4] }
+    }
+}
+
+impl From<Threefry4x64Core> for Threefry4x64Rng {
+    fn from(core: Threefry4x64Core) -> Self {
+        Threefry4x64Rng(BlockRng64::new(core))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {RngCore, SeedableRng};
+    use super::Threefry4x64Rng;
+
+    #[test]
+    fn test_threefry_true_values() {
+        // Known-answer test vectors from Random123 (`kat_vectors`).
+        assert_eq!(Threefry4x64Rng::generate_at([0; 4], [0; 4]),
+                   [0x09218ebde6c85537, 0x55941f5266d86105,
+                    0x4bd25e16282434dc, 0xee29ec846bd2e40b]);
+        assert_eq!(Threefry4x64Rng::generate_at([!0; 4], [!0; 4]),
+                   [0x29c24097942bba1b, 0x0371bbfb0f6f4e11,
+                    0x3c231ffa33f83a1c, 0xcd29113fde32d168]);
+        assert_eq!(Threefry4x64Rng::generate_at(
+                       [0x452821e638d01377, 0xbe5466cf34e90c6c,
+                        0xc0ac29b7c97c50dd, 0x3f84d5b5b5470917],
+                       [0x243f6a8885a308d3, 0x13198a2e03707344,
+                        0xa4093822299f31d0, 0x082efa98ec4e6c89]),
+                   [0xbb893fd42eac50eb, 0x7ca8b22905f3443a,
+                    0xe204b8dcb4daace7, 0x3e1070a2327bfc09]);
+    }
+
+    #[test]
+    fn test_threefry_rng() {
+        let mut rng = Threefry4x64Rng::from_seed([0; 32]);
+        let mut results = [0u64; 4];
+        for i in results.iter_mut() { *i = rng.next_u64(); }
+        assert_eq!(results, [0x09218ebde6c85537, 0x55941f5266d86105,
+                             0x4bd25e16282434dc, 0xee29ec846bd2e40b]);
+
+        // The second block uses the next counter value.
+        for i in results.iter_mut() { *i = rng.next_u64(); }
+        assert_eq!(results, Threefry4x64Rng::generate_at([0; 4], [1, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_threefry_counter() {
+        let key = [1, 2, 3, 4];
+
+        // The counter carries into the higher words.
+        let mut rng = Threefry4x64Rng::new(key, [!0, !0, !0, 0]);
+        for _ in 0..4 { rng.next_u64(); }
+        let mut results = [0u64; 4];
+        for i in results.iter_mut() { *i = rng.next_u64(); }
+        assert_eq!(results, Threefry4x64Rng::generate_at(key, [0, 0, 0, 1]));
+
+        // `set_counter` discards the rest of the current block.
+        let mut rng = Threefry4x64Rng::new(key, [0; 4]);
+        rng.next_u64();
+        rng.set_counter([5, 6, 7, 8]);
+        for i in results.iter_mut() { *i = rng.next_u64(); }
+        assert_eq!(results, Threefry4x64Rng::generate_at(key, [5, 6, 7, 8]));
+    }
+
+    #[test]
+    fn test_threefry_clone() {
+        let mut rng = Threefry4x64Rng::from_seed([7; 32]);
+        rng.next_u32();
+        let mut clone = rng.clone();
+        for _ in 0..16 {
+            assert_eq!(rng.next_u64(), clone.next_u64());
+        }
+    }
+
+    #[cfg(feature="alloc")]
+    #[test]
+    fn test_threefry_state_bytes() {
+        use rand_core::state::StateBytes;
+
+        let mut rng = Threefry4x64Rng::from_seed([0x55; 32]);
+        rng.next_u64();
+        rng.next_u32(); // leaves half of a u64 in the buffer
+
+        let bytes = rng.to_state_bytes();
+        let mut restored = Threefry4x64Rng::from_state_bytes(&bytes).unwrap();
+        assert_eq!(rng.next_u32(), restored.next_u32());
+        for _ in 0..100 {
+            assert_eq!(rng.next_u64(), restored.next_u64());
+        }
+    }
+}
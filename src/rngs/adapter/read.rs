@@ -11,8 +11,12 @@
 //! A wrapper around any Read to treat it as an RNG.
 
 use std::io::Read;
+#[cfg(feature="async")] use std::io;
+#[cfg(feature="async")] use std::cmp::min;
 
 use rand_core::{RngCore, Error, ErrorKind, impls};
+#[cfg(feature="async")] use rand_core::AsyncRngCore;
+#[cfg(feature="async")] use futures::{Async, Poll};
 
 
 /// An RNG that reads random bytes straight from any type supporting
@@ -30,6 +34,13 @@ use rand_core::{RngCore, Error, ErrorKind, impls};
 /// data, will only be reported through [`try_fill_bytes`]. The other
 /// [`RngCore`] methods will panic in case of an error.
 ///
+/// With the `async` feature `ReadRng` implements [`AsyncRngCore`] for
+/// non-blocking readers following the conventions of `futures` 0.1 (such as
+/// the `AsyncRead` types of Tokio): a read returning
+/// `io::ErrorKind::WouldBlock` makes [`poll_fill_bytes`] return
+/// `Async::NotReady`, and the reader notifies the task when it is ready. The
+/// bytes read until then are kept for the next call to `poll_fill_bytes`.
+///
 /// # Example
 ///
 /// ```
@@ -44,16 +55,23 @@ use rand_core::{RngCore, Error, ErrorKind, impls};
 /// [`OsRng`]: ../struct.OsRng.html
 /// [`RngCore`]: ../../trait.RngCore.html
 /// [`try_fill_bytes`]: ../../trait.RngCore.html#method.tymethod.try_fill_bytes
+/// [`AsyncRngCore`]: ../../trait.AsyncRngCore.html
+/// [`poll_fill_bytes`]: ../../trait.AsyncRngCore.html#tymethod.poll_fill_bytes
 #[derive(Debug)]
 pub struct ReadRng<R> {
-    reader: R
+    reader: R,
+    // Bytes read by `poll_fill_bytes` before the reader would block.
+    #[cfg(feature="async")]
+    pending: Vec<u8>,
 }
 
 impl<R: Read> ReadRng<R> {
     /// Create a new `ReadRng` from a `Read`.
     pub fn new(r: R) -> ReadRng<R> {
         ReadRng {
-            reader: r
+            reader: r,
+            #[cfg(feature="async")]
+            pending: Vec::new(),
         }
     }
 }
@@ -87,6 +105,32 @@ impl<R: Read> RngCore for ReadRng<R> {
     }
 }
 
+#[cfg(feature="async")]
+impl<R: Read> AsyncRngCore for ReadRng<R> {
+    fn poll_fill_bytes(&mut self, dest: &mut [u8]) -> Poll<(), Error> {
+        // Continue with the bytes read before the reader would block
+        let mut read = min(self.pending.len(), dest.len());
+        dest[..read].copy_from_slice(&self.pending[..read]);
+        self.pending.drain(..read);
+
+        while read < dest.len() {
+            match self.reader.read(&mut dest[read..]) {
+                Ok(0) => return Err(Error::new(ErrorKind::Unavailable,
+                    "not enough bytes available, reached end of source")),
+                Ok(n) => read += n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    self.pending.extend_from_slice(&dest[..read]);
+                    return Ok(Async::NotReady);
+                }
+                Err(e) => return Err(Error::with_cause(ErrorKind::Unavailable,
+                    "error reading from Read source", e)),
+            }
+        }
+        Ok(Async::Ready(()))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::ReadRng;
@@ -135,4 +179,40 @@ mod test {
 
         assert!(rng.try_fill_bytes(&mut w).err().unwrap().kind == ErrorKind::Unavailable);
     }
+
+    #[cfg(feature="async")]
+    #[test]
+    fn test_reader_rng_async() {
+        use std::io;
+        use futures::{Future, task};
+        use AsyncRngCore;
+
+        // Returns one byte per read, after blocking once before each.
+        struct SlowReader { bytes: Vec<u8>, blocked: bool }
+
+        impl io::Read for SlowReader {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.blocked = !self.blocked;
+                if self.blocked {
+                    task::current().notify();
+                    return Err(io::ErrorKind::WouldBlock.into());
+                }
+                if self.bytes.is_empty() {
+                    return Ok(0);
+                }
+                buf[0] = self.bytes.remove(0);
+                Ok(1)
+            }
+        }
+
+        let reader = SlowReader { bytes: (1..20).collect(), blocked: false };
+        let (rng, buf) = ReadRng::new(reader).fill_bytes_async([0u8; 4])
+            .wait().unwrap();
+        assert_eq!(buf, [1, 2, 3, 4]);
+        let (rng, buf) = rng.fill_bytes_async([0u8; 8]).wait().unwrap();
+        assert_eq!(buf, [5, 6, 7, 8, 9, 10, 11, 12]);
+
+        let err = rng.fill_bytes_async([0u8; 32]).wait().err().unwrap();
+        assert_eq!(err.kind, ErrorKind::Unavailable);
+    }
 }
@@ -26,7 +26,8 @@ use rand_core::block::{BlockRngCore, BlockRng};
 /// - After a process is forked, the RNG in the child process is reseeded within
 ///   the next few generated values, depending on the block size of the
 ///   underlying PRNG. For [`ChaChaCore`] and [`Hc128Core`] this is a maximum of
-///   15 `u32` values before reseeding.
+///   15 `u32` values before reseeding (63 for [`ChaChaCore`] with the
+///   `simd_support` feature).
 /// - After the PRNG has generated a configurable number of random bytes.
 ///
 /// # When should reseeding after a fixed number of generated bytes be used?
@@ -55,6 +56,13 @@ use rand_core::block::{BlockRngCore, BlockRng};
 /// Manually calling [`reseed()`] will not have this retry or delay logic, but
 /// reports the error.
 ///
+/// # Wiping the old state
+///
+/// On reseeding the old PRNG is dropped immediately, without being moved
+/// elsewhere first. With the `zeroize` feature the cryptographic PRNG cores in
+/// this crate, and the `BlockRng` result buffer, overwrite their state on
+/// drop, so no key material of the old PRNG remains in memory.
+///
 /// # Example
 ///
 /// ```
@@ -129,6 +137,10 @@ where R: BlockRngCore<Item = u32> + SeedableRng,
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         self.0.try_fill_bytes(dest)
     }
+
+    fn fill_u32(&mut self, dest: &mut [u32]) {
+        self.0.fill_u32(dest)
+    }
 }
 
 impl<R, Rsdr> Clone for ReseedingRng<R, Rsdr>
@@ -208,6 +220,7 @@ where R: BlockRngCore + SeedableRng,
     fn reseed(&mut self) -> Result<(), Error> {
         R::from_rng(&mut self.reseeder).map(|result| {
             self.bytes_until_reseed = self.threshold;
+            // This drops (and so wipes) the old core in place.
             self.inner = result
         })
     }
@@ -329,7 +342,9 @@ mod fork {
 
 #[cfg(test)]
 mod test {
+    use core::sync::atomic::{AtomicUsize, ATOMIC_USIZE_INIT, Ordering};
     use {Rng, SeedableRng};
+    use rand_core::block::BlockRngCore;
     use prng::chacha::ChaChaCore;
     use rngs::mock::StepRng;
     use super::ReseedingRng;
@@ -340,15 +355,14 @@ mod test {
         let rng = ChaChaCore::from_rng(&mut zero).unwrap();
         let mut reseeding = ReseedingRng::new(rng, 32*4, zero);
 
-        // Currently we only support for arrays up to length 32.
         // TODO: cannot generate seq via Rng::gen because it uses different alg
-        let mut buf = [0u32; 32]; // Needs to be a multiple of the RNGs result
+        let mut buf = [0u32; 64]; // Needs to be a multiple of the RNGs result
                                   // size to test exactly.
-        reseeding.fill(&mut buf);
+        reseeding.fill(&mut buf[..]);
         let seq = buf;
         for _ in 0..10 {
-            reseeding.fill(&mut buf);
-            assert_eq!(buf, seq);
+            reseeding.fill(&mut buf[..]);
+            assert_eq!(&buf[..], &seq[..]);
         }
     }
 
@@ -364,4 +378,41 @@ mod test {
         let mut rng2 = rng1.clone();
         assert_eq!(first, rng2.gen::<u32>());
     }
+
+    #[test]
+    fn test_reseeding_drops_old_core() {
+        static DROPS: AtomicUsize = ATOMIC_USIZE_INIT;
+
+        struct DropCounter(u32);
+
+        impl BlockRngCore for DropCounter {
+            type Item = u32;
+            type Results = [u32; 4];
+
+            fn generate(&mut self, results: &mut Self::Results) {
+                for x in results.iter_mut() {
+                    *x = self.0;
+                }
+            }
+        }
+
+        impl SeedableRng for DropCounter {
+            type Seed = [u8; 4];
+
+            fn from_seed(seed: Self::Seed) -> Self {
+                DropCounter(seed[0] as u32)
+            }
+        }
+
+        impl Drop for DropCounter {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let mut rng = ReseedingRng::new(DropCounter(1), 0, StepRng::new(0, 0));
+        assert_eq!(rng.gen::<u32>(), 1);
+        rng.reseed().unwrap();
+        assert_eq!(DROPS.load(Ordering::SeqCst), 1);
+    }
 }
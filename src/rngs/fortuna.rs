@@ -0,0 +1,466 @@
+// Copyright 2018 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// https://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The Fortuna entropy accumulator and generator.
+
+use core::{fmt, mem};
+use rand_core::{RngCore, SeedableRng, CryptoRng, Error, ErrorKind, impls};
+use prng::ChaChaRng;
+use prng::drbg::{DrbgHash, Sha256};
+
+#[cfg(feature="std")] use std::fs::File;
+#[cfg(feature="std")] use std::io::{self, Read, Write};
+#[cfg(feature="std")] use std::path::Path;
+
+const NUM_POOLS: usize = 32;
+const KEY_LEN: usize = 32;
+// Minimum number of bytes in pool 0 before a reseed.
+const MIN_POOL_SIZE: usize = 64;
+// Minimum time between reseeds from the pools, in milliseconds.
+const MIN_RESEED_TIME: u64 = 100;
+// The generator is rekeyed after each request of at most this many bytes.
+const MAX_REQUEST_LEN: usize = 1 << 20;
+
+/// The length of the seed file in bytes.
+pub const SEED_FILE_LEN: usize = 64;
+
+/// A cryptographically secure random number generator that accumulates
+/// entropy from multiple event sources, using the Fortuna design of Ferguson
+/// and Schneier[^1].
+///
+/// Unlike [`EntropyRng`], which uses a single entropy source, `Fortuna`
+/// collects *random events*, like the timing of interrupts, key presses or
+/// network packets, from any number of sources with [`add_random_event`].
+/// None of these sources needs to provide much entropy, and some may even be
+/// controlled by an attacker: the events are distributed over 32 pools, and
+/// pool *i* is only used for every 2<sup>*i*</sup>-th reseed. Eventually a
+/// pool will have collected enough entropy to recover from a compromised
+/// state, without the need to estimate the entropy of each event.
+///
+/// The generator reseeds itself when pool 0 contains at least 64 bytes of
+/// event data, and at least 100 ms have passed since the previous reseed.
+/// Until the first reseed `try_fill_bytes` returns an error of kind
+/// [`ErrorKind::NotReady`], and the other `RngCore` methods panic.
+///
+/// To have random data available at startup, the state can be saved to a
+/// *seed file* with [`write_seed_file`] and restored with
+/// [`update_seed_file`], which also writes a new seed file so that the same
+/// seed is never used twice. The seed file should also be rewritten
+/// regularly (for example every 10 minutes) and on shutdown. Without `std`
+/// [`save_seed`] and [`restore_seed`] provide the same with a byte array.
+///
+/// ## Differences from the original design
+///
+/// - The generator uses ChaCha20 ([`ChaChaRng`]) instead of AES-256 in counter
+///   mode. As in the original design, the key is replaced with new output of
+///   the generator after every request, so the state does not reveal previous
+///   output.
+/// - The pools are hashed with SHA-256, and the generator is reseeded with
+///   the double hash SHA-256(SHA-256(key || pool hashes)).
+///
+/// ## Example
+///
+/// ```
+/// use rand::{Rng, RngCore};
+/// use rand::rngs::{Fortuna, OsRng};
+///
+/// let mut rng = Fortuna::new();
+///
+/// // Events from a source, for example the timing of interrupts, go into the
+/// // pools in turn.
+/// for (i, t) in [0x5a17u16, 0x4299, 0x0c3e, 0x71f0].iter().enumerate() {
+///     rng.add_random_event(0, i % 32, &[*t as u8, (*t >> 8) as u8]);
+/// }
+/// let mut buf = [0u8; 16];
+/// assert!(rng.try_fill_bytes(&mut buf).is_err()); // not seeded yet
+///
+/// // Once the OS has random data available, it can be used as another source.
+/// if let Ok(mut os_rng) = OsRng::new() {
+///     let mut data = [0u8; 32];
+///     os_rng.fill_bytes(&mut data);
+///     rng.add_random_event(1, 0, &data);
+///     os_rng.fill_bytes(&mut data);
+///     rng.add_random_event(1, 0, &data);
+///     println!("{}", rng.gen::<u64>());
+/// }
+/// ```
+///
+/// [^1]: N. Ferguson, B. Schneier and T. Kohno, *Cryptography Engineering*,
+///       chapter 9, Wiley, 2010.
+///
+/// [`EntropyRng`]: struct.EntropyRng.html
+/// [`add_random_event`]: #method.add_random_event
+/// [`ErrorKind::NotReady`]: ../enum.ErrorKind.html#variant.NotReady
+/// [`write_seed_file`]: #method.write_seed_file
+/// [`update_seed_file`]: #method.update_seed_file
+/// [`save_seed`]: #method.save_seed
+/// [`restore_seed`]: #method.restore_seed
+/// [`ChaChaRng`]: ../prng/chacha/struct.ChaChaRng.html
+pub struct Fortuna {
+    key: [u8; KEY_LEN],
+    seeded: bool,
+    pools: [Sha256; NUM_POOLS],
+    pool0_len: usize,
+    reseed_count: u64,
+    last_reseed: u64,
+    timer: fn() -> u64,
+}
+
+impl Fortuna {
+    /// Create a new, unseeded `Fortuna`. Uses `std::time` for the reseed
+    /// schedule.
+    #[cfg(feature="std")]
+    pub fn new() -> Self {
+        Fortuna::new_with_timer(get_mstime)
+    }
+
+    /// Create a new, unseeded `Fortuna` with a custom timer, making it
+    /// possible to use `Fortuna` in `no_std` environments.
+    ///
+    /// The timer must return the time in milliseconds. It does not have to
+    /// start at zero, and only needs to be monotonic between calls.
+    pub fn new_with_timer(timer: fn() -> u64) -> Self {
+        Fortuna {
+            key: [0; KEY_LEN],
+            seeded: false,
+            pools: Default::default(),
+            pool0_len: 0,
+            reseed_count: 0,
+            last_reseed: 0,
+            timer,
+        }
+    }
+
+    /// Add a random event from the source with number `source_id` to `pool`.
+    ///
+    /// Each source should distribute its events over the pools in turn,
+    /// starting with pool 0. The event data should not be longer than
+    /// necessary: only the bytes that contain entropy, for example the low
+    /// bytes of a timestamp.
+    ///
+    /// # Panics
+    ///
+    /// If `pool` is not less than 32, or `data` is empty or longer than 32
+    /// bytes.
+    pub fn add_random_event(&mut self, source_id: u8, pool: usize, data: &[u8]) {
+        assert!(pool < NUM_POOLS, "Fortuna: pool number out of range");
+        assert!(!data.is_empty() && data.len() <= 32,
+                "Fortuna: event data must be 1 to 32 bytes");
+        let h = &mut self.pools[pool];
+        h.update(&[source_id, data.len() as u8]);
+        h.update(data);
+        if pool == 0 {
+            self.pool0_len += 2 + data.len();
+        }
+    }
+
+    /// Returns the number of times the generator has been reseeded from the
+    /// pools.
+    pub fn reseed_count(&self) -> u64 {
+        self.reseed_count
+    }
+
+    /// Generate a new seed, to be stored and later passed to
+    /// [`restore_seed`].
+    ///
+    /// Returns an error of kind `ErrorKind::NotReady` if the generator is not
+    /// seeded yet.
+    ///
+    /// [`restore_seed`]: #method.restore_seed
+    pub fn save_seed(&mut self, seed: &mut [u8; SEED_FILE_LEN])
+        -> Result<(), Error>
+    {
+        self.try_fill_bytes(seed)
+    }
+
+    /// Reseed the generator with a seed created by [`save_seed`].
+    ///
+    /// A seed must not be restored more than once: call [`save_seed`] and
+    /// store the new seed immediately after restoring it.
+    ///
+    /// [`save_seed`]: #method.save_seed
+    pub fn restore_seed(&mut self, seed: &[u8; SEED_FILE_LEN]) {
+        self.reseed_key(&[seed]);
+    }
+
+    /// Write a new seed file to `path`.
+    ///
+    /// Returns an error of kind `io::ErrorKind::WouldBlock` if the generator is
+    /// not seeded yet.
+    #[cfg(feature="std")]
+    pub fn write_seed_file<P: AsRef<Path>>(&mut self, path: P)
+        -> io::Result<()>
+    {
+        let mut seed = [0u8; SEED_FILE_LEN];
+        self.save_seed(&mut seed)?;
+        File::create(path)?.write_all(&seed)
+    }
+
+    /// Reseed the generator with the seed file at `path`, and replace it with
+    /// a new seed file.
+    ///
+    /// Returns an error of kind `io::ErrorKind::InvalidData` if the file does not
+    /// have the right length.
+    #[cfg(feature="std")]
+    pub fn update_seed_file<P: AsRef<Path>>(&mut self, path: P)
+        -> io::Result<()>
+    {
+        let mut data = Vec::with_capacity(SEED_FILE_LEN);
+        File::open(path.as_ref())?.read_to_end(&mut data)?;
+        if data.len() != SEED_FILE_LEN {
+            return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                      "Fortuna: invalid seed file length"));
+        }
+        let mut seed = [0u8; SEED_FILE_LEN];
+        seed.copy_from_slice(&data);
+        self.restore_seed(&seed);
+        self.write_seed_file(path)
+    }
+
+    // Reseed from the pools if pool 0 has collected enough data, and enough
+    // time has passed since the previous reseed.
+    fn reseed_from_pools(&mut self) {
+        if self.pool0_len < MIN_POOL_SIZE {
+            return;
+        }
+        let now = (self.timer)();
+        if self.reseed_count > 0
+            && now.wrapping_sub(self.last_reseed) < MIN_RESEED_TIME
+        {
+            return;
+        }
+        self.reseed_count += 1;
+        self.last_reseed = now;
+        self.pool0_len = 0;
+
+        // Pool i is used if 2^i divides the reseed count.
+        let mut digests = [[0u8; 32]; NUM_POOLS];
+        let mut n = 0;
+        for (i, pool) in self.pools.iter_mut().enumerate() {
+            if self.reseed_count % (1 << i) != 0 {
+                break;
+            }
+            mem::replace(pool, Sha256::default()).finish(&mut digests[i]);
+            n += 1;
+        }
+        let mut parts: [&[u8]; NUM_POOLS] = [&[]; NUM_POOLS];
+        for (part, digest) in parts.iter_mut().zip(digests.iter()) {
+            *part = digest;
+        }
+        self.reseed_key(&parts[..n]);
+    }
+
+    // key = SHA-256(SHA-256(key || seed))
+    fn reseed_key(&mut self, seed: &[&[u8]]) {
+        let mut h = Sha256::default();
+        h.update(&self.key);
+        for part in seed {
+            h.update(part);
+        }
+        let mut digest = [0u8; 32];
+        h.finish(&mut digest);
+        let mut h = Sha256::default();
+        h.update(&digest);
+        h.finish(&mut self.key);
+        self.seeded = true;
+    }
+}
+
+impl RngCore for Fortuna {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).unwrap_or_else(|err|
+                panic!("Fortuna failed: {}", err))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.reseed_from_pools();
+        if !self.seeded {
+            return Err(Error::new(ErrorKind::NotReady,
+                                  "Fortuna: not seeded yet"));
+        }
+        for chunk in dest.chunks_mut(MAX_REQUEST_LEN) {
+            let mut rng = ChaChaRng::from_seed(self.key);
+            rng.fill_bytes(chunk);
+            rng.fill_bytes(&mut self.key);
+        }
+        Ok(())
+    }
+}
+
+impl CryptoRng for Fortuna {}
+
+// The pools wipe themselves on drop.
+#[cfg(feature="zeroize")]
+impl Drop for Fortuna {
+    fn drop(&mut self) {
+        impls::zeroize(&mut self.key);
+    }
+}
+
+#[cfg(feature="std")]
+impl Default for Fortuna {
+    fn default() -> Self {
+        Fortuna::new()
+    }
+}
+
+// Custom Debug implementation that does not expose the internal state
+impl fmt::Debug for Fortuna {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Fortuna {{ reseed_count: {} }}", self.reseed_count)
+    }
+}
+
+#[cfg(feature="std")]
+fn get_mstime() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let dur = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    dur.as_secs() * 1000 + (dur.subsec_nanos() / 1_000_000) as u64
+}
+
+#[cfg(test)]
+mod test {
+    use {RngCore, ErrorKind};
+    use super::{Fortuna, SEED_FILE_LEN};
+
+    fn zero_timer() -> u64 { 0 }
+
+    fn seeded() -> Fortuna {
+        let mut rng = Fortuna::new_with_timer(zero_timer);
+        for i in 0..32 {
+            rng.add_random_event(0, 0, &[i]);
+            rng.add_random_event(1, 0, &[i, i]);
+        }
+        rng
+    }
+
+    #[test]
+    fn test_fortuna_true_values() {
+        let mut rng = seeded();
+        let mut results = [0u32; 8];
+        for i in results.iter_mut() {
+            *i = rng.next_u32();
+        }
+        let expected = [0x57a5ad44, 0xea5092f1, 0xa34b6e61, 0x4f65e4a7,
+                        0xd6e777c4, 0xb57a3ca6, 0x3c17836e, 0x5e8b5089];
+        assert_eq!(results, expected);
+        assert_eq!(rng.reseed_count(), 1);
+    }
+
+    #[test]
+    fn test_fortuna_not_seeded() {
+        let mut rng = Fortuna::new_with_timer(zero_timer);
+        // 60 bytes in pool 0
+        for i in 0..20 {
+            rng.add_random_event(0, 0, &[i]);
+        }
+        let mut buf = [0u8; 16];
+        let err = rng.try_fill_bytes(&mut buf).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::NotReady);
+        assert_eq!(rng.reseed_count(), 0);
+    }
+
+    #[test]
+    fn test_fortuna_reseed_schedule() {
+        use core::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+        static TIME: AtomicUsize = ATOMIC_USIZE_INIT;
+        fn timer() -> u64 { TIME.load(Ordering::Relaxed) as u64 }
+
+        let mut rng = Fortuna::new_with_timer(timer);
+        let fill_pools = |rng: &mut Fortuna| {
+            for pool in 0..32 {
+                rng.add_random_event(0, pool, &[0x55; 32]);
+                rng.add_random_event(0, pool, &[0x55; 32]);
+            }
+        };
+        fill_pools(&mut rng);
+        rng.next_u32();
+        assert_eq!(rng.reseed_count(), 1);
+
+        // Not enough time has passed
+        fill_pools(&mut rng);
+        rng.next_u32();
+        assert_eq!(rng.reseed_count(), 1);
+
+        TIME.store(100, Ordering::Relaxed);
+        rng.next_u32();
+        assert_eq!(rng.reseed_count(), 2);
+
+        // Not enough data in pool 0
+        TIME.store(200, Ordering::Relaxed);
+        rng.next_u32();
+        assert_eq!(rng.reseed_count(), 2);
+    }
+
+    #[test]
+    fn test_fortuna_seed() {
+        let mut rng = seeded();
+        let mut seed = [0u8; SEED_FILE_LEN];
+        rng.save_seed(&mut seed).unwrap();
+
+        let mut rng1 = Fortuna::new_with_timer(zero_timer);
+        let mut rng2 = Fortuna::new_with_timer(zero_timer);
+        assert!(rng1.save_seed(&mut [0; SEED_FILE_LEN]).is_err());
+        rng1.restore_seed(&seed);
+        rng2.restore_seed(&seed);
+        assert_eq!(rng1.next_u64(), rng2.next_u64());
+        assert!(rng.next_u64() != rng1.next_u64());
+    }
+
+    #[test]
+    #[cfg(all(feature="std", rust_1_26))]
+    fn test_fortuna_seed_file() {
+        use std::{env, fs, process};
+        use std::io::{Read, Write};
+        use std::path::PathBuf;
+        use Rng;
+
+        // Removes the seed file however the test exits.
+        struct TempFile(PathBuf);
+        impl Drop for TempFile {
+            fn drop(&mut self) {
+                let _ = fs::remove_file(&self.0);
+            }
+        }
+
+        let read = |path: &::std::path::Path| {
+            let mut data = Vec::new();
+            fs::File::open(path).unwrap().read_to_end(&mut data).unwrap();
+            data
+        };
+
+        let name = format!("rand-fortuna-test-seed-{}-{:016x}",
+                           process::id(), ::thread_rng().gen::<u64>());
+        let file = TempFile(env::temp_dir().join(name));
+        let path = &file.0;
+        let mut rng = seeded();
+        rng.write_seed_file(path).unwrap();
+        let seed = read(path);
+        assert_eq!(seed.len(), SEED_FILE_LEN);
+
+        let mut rng2 = Fortuna::new_with_timer(zero_timer);
+        rng2.update_seed_file(path).unwrap();
+        let seed2 = read(path);
+        assert_eq!(seed2.len(), SEED_FILE_LEN);
+        assert!(seed != seed2);
+
+        fs::File::create(path).unwrap().write_all(&[0u8; 10]).unwrap();
+        assert!(rng2.update_seed_file(path).is_err());
+    }
+}
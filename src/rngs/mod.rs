@@ -13,6 +13,7 @@
 //! - [`ThreadRng`], a fast, secure, auto-seeded thread-local generator
 //! - [`StdRng`] and [`SmallRng`], algorithms to cover typical usage
 //! - [`EntropyRng`], [`OsRng`] and [`JitterRng`] as entropy sources
+//! - [`Fortuna`] to accumulate entropy from multiple event sources
 //! - [`mock::StepRng`] as a simple counter for tests
 //! - [`adapter::ReadRng`] to read from a file/stream
 //! - [`adapter::ReseedingRng`] to reseed a PRNG on clone / process fork etc.
@@ -148,6 +149,7 @@
 //! [`EntropyRng`]: struct.EntropyRng.html
 //! [`OsRng`]: struct.OsRng.html
 //! [`JitterRng`]: struct.JitterRng.html
+//! [`Fortuna`]: struct.Fortuna.html
 // Other traits and functions:
 //! [`rand_core`]: https://crates.io/crates/rand_core
 //! [`prng` module]: ../prng/index.html
@@ -168,6 +170,7 @@
 pub mod adapter;
 
 #[cfg(feature="std")] mod entropy;
+mod fortuna;
 mod jitter;
 pub mod mock;   // Public so we don't export `StepRng` directly, making it a bit
                 // more clear it is intended for testing.
@@ -176,6 +179,7 @@ mod std;
 #[cfg(feature="std")] pub(crate) mod thread;
 
 
+pub use self::fortuna::{Fortuna, SEED_FILE_LEN};
 pub use self::jitter::{JitterRng, TimerError};
 #[cfg(feature="std")] pub use self::entropy::EntropyRng;
 
@@ -12,6 +12,8 @@
 
 use std::fmt;
 use rand_core::{CryptoRng, RngCore, Error, impls};
+#[cfg(feature="async")] use rand_core::{AsyncRngCore, ErrorKind};
+#[cfg(feature="async")] use futures::{Async, Poll, task};
 
 /// A random number generator that retrieves randomness straight from the
 /// operating system.
@@ -78,6 +80,11 @@ use rand_core::{CryptoRng, RngCore, Error, impls};
 /// getting an error. With `try_fill_bytes` we choose to get the error
 /// ([`ErrorKind::NotReady`]), while the other methods use a blocking interface.
 ///
+/// With the `async` feature, `OsRng` implements [`AsyncRngCore`]: instead of
+/// returning `ErrorKind::NotReady`, [`poll_fill_bytes`] returns
+/// `Async::NotReady` and notifies the task once the OS RNG is initialized,
+/// so async applications can wait without blocking an executor thread.
+///
 /// On Linux (when the `genrandom` system call is not available) and on NetBSD
 /// reading from `/dev/urandom` never blocks, even when the OS hasn't collected
 /// enough entropy yet. As a countermeasure we try to do a single read from
@@ -95,6 +102,8 @@ use rand_core::{CryptoRng, RngCore, Error, impls};
 /// [`RngCore`]: ../trait.RngCore.html
 /// [`try_fill_bytes`]: ../trait.RngCore.html#method.tymethod.try_fill_bytes
 /// [`ErrorKind::NotReady`]: ../enum.ErrorKind.html#variant.NotReady
+/// [`AsyncRngCore`]: ../trait.AsyncRngCore.html
+/// [`poll_fill_bytes`]: ../trait.AsyncRngCore.html#tymethod.poll_fill_bytes
 ///
 /// [1]: http://man7.org/linux/man-pages/man2/getrandom.2.html
 /// [2]: http://man7.org/linux/man-pages/man4/urandom.4.html
@@ -216,6 +225,78 @@ impl RngCore for OsRng {
     }
 }
 
+#[cfg(all(feature="async", not(any(target_arch = "wasm32",
+                                    target_arch = "asmjs"))))]
+impl AsyncRngCore for OsRng {
+    /// Fill `dest` without blocking if the OS RNG is not initialized yet.
+    ///
+    /// While it is not, a waiter thread blocks until it is and then notifies
+    /// the waiting tasks. There is at most one waiter thread at a time.
+    fn poll_fill_bytes(&mut self, dest: &mut [u8]) -> Poll<(), Error> {
+        match self.try_fill_bytes(dest) {
+            Ok(()) => Ok(Async::Ready(())),
+            Err(ref e) if e.kind == ErrorKind::NotReady => {
+                let mut rng = self.0.clone();
+                waiter::notify_when_done(task::current(), move || {
+                    // Errors are reported by the next poll.
+                    let mut buf = [0u8; 1];
+                    let _ = rng.test_initialized(&mut buf, true);
+                })?;
+                Ok(Async::NotReady)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+// A thread waiting for the OS RNG to be initialized, on behalf of the tasks
+// polling `OsRng`.
+#[cfg(all(feature="async", not(any(target_arch = "wasm32",
+                                    target_arch = "asmjs"))))]
+mod waiter {
+    use {Error, ErrorKind};
+    use futures::task::Task;
+    use std::sync::{Once, Mutex, ONCE_INIT};
+    use std::thread;
+
+    // The tasks to notify, or `None` if no waiter thread is running.
+    // TODO: remove outer Option when `Mutex::new(None)` is a constant expression
+    static mut WAITING_TASKS: Option<Mutex<Option<Vec<Task>>>> = None;
+    static WAITING_TASKS_ONCE: Once = ONCE_INIT;
+
+    // Notify `task` after `wait` returns. `wait` is only run if no waiter
+    // thread is running already.
+    pub fn notify_when_done<F>(task: Task, wait: F) -> Result<(), Error>
+        where F: FnOnce() + Send + 'static
+    {
+        WAITING_TASKS_ONCE.call_once(|| {
+            unsafe { WAITING_TASKS = Some(Mutex::new(None)) }
+        });
+        let mutex = unsafe { WAITING_TASKS.as_ref().unwrap() };
+
+        let mut guard = mutex.lock().unwrap();
+        if let Some(ref mut tasks) = *guard {
+            tasks.push(task);
+            return Ok(());
+        }
+
+        debug!("OsRng: starting thread to wait for initialization");
+        thread::Builder::new()
+            .name("OsRng waiter".to_string())
+            .spawn(move || {
+                wait();
+                let tasks = mutex.lock().unwrap().take();
+                for task in tasks.unwrap_or_default() {
+                    task.notify();
+                }
+            })
+            .map_err(|err| Error::with_cause(ErrorKind::Unavailable,
+                "failed to start OsRng waiter thread", err))?;
+        *guard = Some(vec![task]);
+        Ok(())
+    }
+}
+
 trait OsRngImpl where Self: Sized {
     // Create a new `OsRng` platform interface.
     fn new() -> Result<Self, Error>;
@@ -1231,6 +1312,44 @@ mod test {
         r.fill_bytes(&mut huge);
     }
 
+    #[cfg(all(feature="async",
+              not(any(target_arch = "wasm32", target_arch = "asmjs"))))]
+    #[test]
+    fn test_os_rng_async() {
+        use futures::Future;
+        use AsyncRngCore;
+
+        let r = OsRng::new().unwrap();
+        let (_, buf) = r.fill_bytes_async([0u8; 1000]).wait().unwrap();
+        assert!(buf.iter().any(|&b| b != 0));
+    }
+
+    #[cfg(all(feature="async",
+              not(any(target_arch = "wasm32", target_arch = "asmjs"))))]
+    #[test]
+    fn test_os_rng_waiter() {
+        use futures::{future, task, Async, Future};
+        use std::thread;
+        use std::time::Duration;
+        use Error;
+
+        // Not ready on the first poll, and notified by the waiter thread.
+        fn waiting() -> Box<Future<Item=(), Error=Error>> {
+            let mut polled = false;
+            Box::new(future::poll_fn(move || {
+                if polled { return Ok(Async::Ready(())); }
+                polled = true;
+                super::waiter::notify_when_done(task::current(), || {
+                    thread::sleep(Duration::from_millis(20));
+                })?;
+                Ok(Async::NotReady)
+            }))
+        }
+
+        // Both tasks are notified, sharing one waiter thread.
+        waiting().join(waiting()).wait().unwrap();
+    }
+
     #[cfg(not(any(target_arch = "wasm32", target_arch = "asmjs")))]
     #[test]
     fn test_os_rng_tasks() {
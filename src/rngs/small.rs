@@ -10,7 +10,7 @@
 
 //! A small fast RNG
 
-use {RngCore, SeedableRng, Error};
+use {RngCore, SeedableRng, SplittableRng, Error};
 use ::rand_xorshift::XorShiftRng;
 
 /// An RNG recommended when small state, cheap initialization and good
@@ -66,6 +66,7 @@ use ::rand_xorshift::XorShiftRng;
 /// [Xorshift]: ../../rand_xorshift/struct.XorShiftRng.html
 /// [`XorShiftRng`]: ../../rand_xorshift/struct.XorShiftRng.html
 #[derive(Clone, Debug)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize))]
 pub struct SmallRng(XorShiftRng);
 
 impl RngCore for SmallRng {
@@ -99,3 +100,97 @@ impl SeedableRng for SmallRng {
         XorShiftRng::from_rng(rng).map(SmallRng)
     }
 }
+
+/// `split` seeds the child with the next 128 bits of output, passed through a
+/// bijective mixing function. Seeding it with the output directly would give
+/// a child that continues the output of the parent.
+///
+/// `fork` does the same with the next 128 bits of output of this generator
+/// combined with `index`, without advancing it. The child depends on the
+/// position of this generator.
+///
+/// The children of `SmallRng` are only statistically independent of each
+/// other and the parent.
+impl SplittableRng for SmallRng {
+    fn split(&mut self) -> Self {
+        let a = self.next_u64();
+        let b = self.next_u64();
+        SmallRng::from_u64s(mix(a), mix(b))
+    }
+
+    fn fork(&self, index: u64) -> Self {
+        let mut rng = self.0.clone();
+        let a = rng.next_u64();
+        let b = rng.next_u64();
+        let k = mix(index ^ 0x5851_f42d_4c95_7f2d);
+        SmallRng::from_u64s(mix(a ^ k), mix(b.wrapping_add(k)))
+    }
+}
+
+impl SmallRng {
+    fn from_u64s(a: u64, b: u64) -> Self {
+        let mut seed = <Self as SeedableRng>::Seed::default();
+        for i in 0..8 {
+            seed[i] = (a >> (8 * i)) as u8;
+            seed[i + 8] = (b >> (8 * i)) as u8;
+        }
+        SmallRng::from_seed(seed)
+    }
+}
+
+// The output function of SplitMix64, a bijection.
+fn mix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod test {
+    use {RngCore, SeedableRng, SplittableRng};
+    use super::SmallRng;
+
+    #[test]
+    fn test_smallrng_split_fork() {
+        let seed = [1,2,3,4, 5,6,7,8, 9,10,11,12, 13,14,15,16];
+        let mut rng = SmallRng::from_seed(seed);
+        let mut fork1 = rng.fork(1);
+        let mut fork1_again = SmallRng::from_seed(seed).fork(1);
+        let mut fork2 = rng.fork(2);
+        for _ in 0..16 {
+            assert_eq!(fork1.next_u64(), fork1_again.next_u64());
+        }
+        assert!(fork1.next_u64() != fork2.next_u64());
+
+        // The child does not continue the output of the parent
+        let mut child = rng.split();
+        let x = child.next_u32();
+        for _ in 0..100 {
+            assert!(rng.next_u32() != x);
+        }
+    }
+
+    #[cfg(feature="serde1")]
+    #[test]
+    fn test_smallrng_serde() {
+        use bincode;
+        use std::io::{BufWriter, BufReader};
+
+        let mut rng = SmallRng::from_seed([0x55; 16]);
+        rng.next_u32();
+
+        let buf: Vec<u8> = Vec::new();
+        let mut buf = BufWriter::new(buf);
+        bincode::serialize_into(&mut buf, &rng).expect("Could not serialize");
+
+        let buf = buf.into_inner().unwrap();
+        let mut read = BufReader::new(&buf[..]);
+        let mut deserialized: SmallRng =
+            bincode::deserialize_from(&mut read).expect("Could not deserialize");
+
+        for _ in 0..100 {
+            assert_eq!(rng.next_u32(), deserialized.next_u32());
+        }
+    }
+}
@@ -10,7 +10,7 @@
 
 //! The standard RNG
 
-use {RngCore, CryptoRng, Error, SeedableRng};
+use {RngCore, CryptoRng, Error, SeedableRng, SplittableRng};
 use prng::Hc128Rng;
 
 /// The standard RNG. The PRNG algorithm in `StdRng` is chosen to be efficient
@@ -25,9 +25,17 @@ use prng::Hc128Rng;
 /// produce different output depending on the architecture. If you require
 /// reproducible output, use a named RNG, for example [`ChaChaRng`].
 ///
+/// Users who prefer a different trade-off between security margin and
+/// throughput can use one of the reduced-round ChaCha variants directly:
+/// [`ChaCha12Rng`] is faster while remaining secure, and [`ChaCha8Rng`] is
+/// faster still, suitable for simulations where there is no adversary.
+///
 /// [HC-128]: ../prng/hc128/struct.Hc128Rng.html
 /// [`ChaChaRng`]: ../prng/chacha/struct.ChaChaRng.html
+/// [`ChaCha12Rng`]: ../prng/chacha/struct.ChaCha12Rng.html
+/// [`ChaCha8Rng`]: ../prng/chacha/struct.ChaCha8Rng.html
 #[derive(Clone, Debug)]
+#[cfg_attr(feature="serde1", derive(Serialize, Deserialize))]
 pub struct StdRng(Hc128Rng);
 
 impl RngCore for StdRng {
@@ -48,6 +56,10 @@ impl RngCore for StdRng {
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         self.0.try_fill_bytes(dest)
     }
+
+    fn fill_u32(&mut self, dest: &mut [u32]) {
+        self.0.fill_u32(dest)
+    }
 }
 
 impl SeedableRng for StdRng {
@@ -64,6 +76,18 @@ impl SeedableRng for StdRng {
 
 impl CryptoRng for StdRng {}
 
+/// `split` and `fork` have the same guarantees as the implementation of the
+/// current algorithm; which may change in future versions of Rand.
+impl SplittableRng for StdRng {
+    fn split(&mut self) -> Self {
+        StdRng(self.0.split())
+    }
+
+    fn fork(&self, index: u64) -> Self {
+        StdRng(self.0.fork(index))
+    }
+}
+
 
 #[cfg(test)]
 mod test {
@@ -80,4 +104,51 @@ mod test {
         let mut rng2 = StdRng::from_rng(rng1).unwrap();
         assert_eq!(rng2.next_u64(), 6766915756997287454);
     }
+
+    #[test]
+    fn test_stdrng_split_fork() {
+        use SplittableRng;
+
+        let seed = [1,0,0,0, 23,0,0,0, 200,1,0,0, 210,30,0,0,
+                    0,0,0,0, 0,0,0,0, 0,0,0,0, 0,0,0,0];
+        let mut rng = StdRng::from_seed(seed);
+        let mut fork1 = rng.fork(1);
+        let mut fork1_again = StdRng::from_seed(seed).fork(1);
+        let mut fork2 = rng.fork(2);
+        for _ in 0..16 {
+            assert_eq!(fork1.next_u64(), fork1_again.next_u64());
+        }
+        assert!(fork1.next_u64() != fork2.next_u64());
+
+        let mut child1 = rng.split();
+        let mut child2 = rng.split();
+        assert!(child1.next_u64() != child2.next_u64());
+        assert!(rng.next_u64() != child1.next_u64());
+        // The state has changed
+        let mut fork1_initial = StdRng::from_seed(seed).fork(1);
+        assert!(rng.fork(1).next_u64() != fork1_initial.next_u64());
+    }
+
+    #[cfg(feature="serde1")]
+    #[test]
+    fn test_stdrng_serde() {
+        use bincode;
+        use std::io::{BufWriter, BufReader};
+
+        let mut rng = StdRng::from_seed([0x55; 32]);
+        rng.next_u32();
+
+        let buf: Vec<u8> = Vec::new();
+        let mut buf = BufWriter::new(buf);
+        bincode::serialize_into(&mut buf, &rng).expect("Could not serialize");
+
+        let buf = buf.into_inner().unwrap();
+        let mut read = BufReader::new(&buf[..]);
+        let mut deserialized: StdRng =
+            bincode::deserialize_from(&mut read).expect("Could not deserialize");
+
+        for _ in 0..100 {
+            assert_eq!(rng.next_u32(), deserialized.next_u32());
+        }
+    }
 }
@@ -119,6 +119,10 @@ impl RngCore for ThreadRng {
     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
         unsafe { (*self.rng.get()).try_fill_bytes(dest) }
     }
+
+    fn fill_u32(&mut self, dest: &mut [u32]) {
+        unsafe { (*self.rng.get()).fill_u32(dest) }
+    }
 }
 
 impl CryptoRng for ThreadRng {}
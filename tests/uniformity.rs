@@ -57,3 +57,44 @@ fn unit_circle() {
         assert!((p - 1.0 / (N_BINS as f64)).abs() < TOL, "{}", p);
     }
 }
+
+/// Assert that the frequencies in `h` match the probabilities `pmf(k)` of the
+/// integers `k = 1, ..., 100`, within five standard deviations.
+fn check_pmf<F: Fn(f64) -> f64>(h: &Histogram100, pmf: F) {
+    println!("{:?}", h);
+    for (i, &b) in h.bins().iter().enumerate() {
+        let p = (b as f64) / (N_SAMPLES as f64);
+        let expected = pmf((i + 1) as f64);
+        let tol = 5.0 * (expected * (1.0 - expected) / (N_SAMPLES as f64)).sqrt();
+        assert!((p - expected).abs() < tol, "{}: {} vs {}", i + 1, p, expected);
+    }
+}
+
+#[test]
+fn zipf() {
+    const S: f64 = 1.5;
+    let mut h = Histogram100::with_const_width(0.5, 100.5);
+    let dist = rand::distributions::Zipf::new(100, S);
+    let mut rng = rand::rngs::SmallRng::from_entropy();
+    for _ in 0..N_SAMPLES {
+        let k: u64 = dist.sample(&mut rng);
+        h.add(k as f64).unwrap();
+    }
+    let norm: f64 = (1..101).map(|k| (k as f64).powf(-S)).sum();
+    check_pmf(&h, |k| k.powf(-S) / norm);
+}
+
+#[test]
+fn zeta() {
+    use ::std::f64::consts::PI;
+    let mut h = Histogram100::with_const_width(0.5, 100.5);
+    let dist = rand::distributions::Zeta::new(2.0);
+    let mut rng = rand::rngs::SmallRng::from_entropy();
+    for _ in 0..N_SAMPLES {
+        let x: f64 = dist.sample(&mut rng);
+        // samples above 100 are not counted, but still part of `N_SAMPLES`
+        let _ = h.add(x);
+    }
+    // ζ(2) = π²/6
+    check_pmf(&h, |k| 6.0 / (PI * PI * k * k));
+}
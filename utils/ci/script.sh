@@ -18,16 +18,23 @@ main() {
         cross test --examples --target $TARGET
         cross test --package rand_core --target $TARGET
         cross test --package rand_core --no-default-features --features=alloc --target $TARGET
-        cross test --package rand_isaac --features=serde1 --target $TARGET
+        cross test --package rand_isaac --features=serde1,zeroize --target $TARGET
+        cross test --package rand_pcg --features=serde1 --target $TARGET
         cross test --package rand_xorshift --features=serde1 --target $TARGET
+        cross test --package rand_xoshiro --features=serde1 --target $TARGET
+        cross test --package rand_mt --features=serde1 --target $TARGET
     else    # have stable Rust
         cross test --lib --no-default-features --target $TARGET
-        cross test --features=serde1,log,i128_support --target $TARGET
+        cross test --features=serde1,log,i128_support,zeroize,async --target $TARGET
         cross test --examples --target $TARGET
         cross test --package rand_core --target $TARGET
+        cross test --package rand_core --features=futures --target $TARGET
         cross test --package rand_core --no-default-features --target $TARGET
-        cross test --package rand_isaac --features=serde1 --target $TARGET
+        cross test --package rand_isaac --features=serde1,zeroize --target $TARGET
+        cross test --package rand_pcg --features=serde1 --target $TARGET
         cross test --package rand_xorshift --features=serde1 --target $TARGET
+        cross test --package rand_xoshiro --features=serde1 --target $TARGET
+        cross test --package rand_mt --features=serde1 --target $TARGET
     fi
 }
 